@@ -15,6 +15,23 @@ export interface Project {
   group_id: number | null
   created_at: string
   updated_at: string | null
+  deleted_at?: string | null
+}
+
+export interface RecycledProject extends Project {
+  record_count: number
+}
+
+export interface PurgeProjectResult {
+  project_id: number
+  fields: number
+  records: number
+  tasks: number
+  task_file_progress: number
+  batches: number
+  mapping_templates: number
+  mapping_cache: number
+  rolled_back_records: number
 }
 
 export interface CreateProjectRequest {
@@ -40,6 +57,10 @@ export interface ProjectField {
   additional_requirement?: string | null
   validation_rule: string | null
   extraction_hint: string | null
+  type_options: string | null    // 字段类型配置（JSON）
+  transforms: string | null      // 字段转换配置（JSON 数组）
+  expression: string | null      // 计算字段表达式
+  ai_extract_source: string | null  // AI 提取来源字段
   display_order: number
   created_at: string
   deleted_at: string | null  // 删除时间
@@ -55,6 +76,10 @@ export interface CreateFieldRequest {
   additional_requirement?: string | null
   validation_rule?: string | null
   extraction_hint?: string | null
+  type_options?: string | null
+  transforms?: string | null
+  expression?: string | null
+  ai_extract_source?: string | null
 }
 
 export interface GenerateFieldMetadataRequest {
@@ -92,6 +117,18 @@ export interface ColumnMappingResponse {
   unmatched_columns: number[]
 }
 
+// 列映射模板（按表头指纹复用已确认的映射）
+export interface MappingTemplate {
+  id: number
+  project_id: number
+  header_fingerprint: string
+  headers: string[]
+  header_row: number
+  mappings: FieldMapping[]
+  created_at: string
+  updated_at: string | null
+}
+
 // AI 配置类型
 export interface AiConfig {
   id: number
@@ -121,7 +158,7 @@ export interface ProcessingTask {
   id: string              // 与 task_id 一致，用于 UI 组件 key
   task_id: string
   project_id: number
-  status: 'pending' | 'processing' | 'paused' | 'completed' | 'cancelled' | 'error'
+  status: 'pending' | 'processing' | 'paused' | 'awaiting_review' | 'completed' | 'cancelled' | 'error' | 'interrupted'
   total_files: number
   processed_files: number
   total_rows: number
@@ -130,6 +167,7 @@ export interface ProcessingTask {
   error_count: number
   batch_number: string | null
   source_files?: string[]  // 源文件名列表
+  resumable?: boolean      // 已中断且可从检查点继续
   message?: string
   // UI 扩展字段
   file_name?: string
@@ -157,10 +195,23 @@ export interface LogEntry {
   category?: 'ai' | 'ai_request' | 'progress' | 'system'  // 日志类别
 }
 
+export interface FileSpec {
+  file_path: string
+  include_sheets?: string[]
+  exclude_sheets?: string[]
+  header_row?: number
+  start_row?: number
+  end_row?: number
+  empty_row_threshold?: number
+}
+
 export interface StartProcessingRequest {
   project_id: number
   file_paths: string[]
   ai_config_id?: number
+  review_mapping?: boolean
+  mapping_mode?: 'ai' | 'rules' | 'ai_with_rules_fallback'
+  file_specs?: FileSpec[]
 }
 
 // 进度更新类型
@@ -332,6 +383,40 @@ export interface RollbackResult {
   success: boolean
   deleted_count: number
   message: string
+  restorable_until: string
+}
+
+// 恢复撤回的导入结果
+export interface RestoreBatchResult {
+  success: boolean
+  restored_count: number
+  // 恢复时与现有记录重复、已标记为重复的记录数
+  duplicate_count: number
+  message: string
+}
+
+// 撤回范围（批次 / 文件 / Sheet 任意组合，指定 Sheet 时须同时指定文件）
+export interface RollbackScope {
+  batch_number?: string | null
+  source_file?: string | null
+  source_sheet?: string | null
+}
+
+// 撤回预览分组（按批次 + 文件 + Sheet）
+export interface RollbackPreviewGroup {
+  batch_number: string | null
+  source_file: string | null
+  source_sheet: string | null
+  success_count: number
+  error_count: number
+}
+
+// 撤回预览（删除前确认）
+export interface RollbackPreview {
+  total_count: number
+  success_count: number
+  error_count: number
+  groups: RollbackPreviewGroup[]
 }
 
 // 导入记录详情（以任务为单位，1任务=1文件）
@@ -343,6 +428,27 @@ export interface BatchDetailResponse {
   created_at: string
   status: string
   total_records: number
+  rolled_back_count: number
+  rolled_back_at: string | null
+  restorable_until: string | null
+}
+
+// ── 记录历史相关类型 ─────────────────────────────────────────────────
+
+// 记录修改历史（只追加）
+export interface RecordHistoryEntry {
+  id: number
+  record_id: number
+  project_id: number
+  operation: 'update' | 'revalidate' | 'delete' | 'rollback' | 'restore' | 'import_update' | 'purge' | 'revert' | 'backfill'
+  before_data: Record<string, any> | null
+  before_status: string | null
+  before_error_message: string | null
+  after_data: Record<string, any> | null
+  after_status: string | null
+  after_error_message: string | null
+  actor: string
+  created_at: string
 }
 
 // ── 项目分组相关类型 ─────────────────────────────────────────────────
@@ -391,6 +497,7 @@ export type FilterOperator =
   | 'between'   // 在范围内
   | 'is_empty'  // 为空
   | 'is_not_empty' // 不为空
+  | 'includes'  // 包含任一项（多值列表）
 
 // 筛选条件
 export interface FilterCondition {
@@ -432,6 +539,7 @@ export const OPERATOR_LABELS: Record<FilterOperator, string> = {
   between: '在范围内',
   is_empty: '为空',
   is_not_empty: '不为空',
+  includes: '包含任一项',
 }
 
 // 根据字段类型获取可用运算符
@@ -445,8 +553,15 @@ export function getOperatorsForFieldType(fieldType: string): FilterOperator[] {
     case 'email':
       return ['eq', 'neq', 'contains', 'starts_with', 'is_empty', 'is_not_empty']
     case 'number':
+    case 'currency':
     case 'id_card':
       return ['eq', 'neq', 'gt', 'lt', 'gte', 'lte', 'between', 'is_empty', 'is_not_empty']
+    case 'enum':
+      return ['eq', 'neq', 'includes', 'is_empty', 'is_not_empty']
+    case 'boolean':
+      return ['eq', 'is_empty', 'is_not_empty']
+    case 'list':
+      return ['includes', 'contains', 'is_empty', 'is_not_empty']
     case 'date':
       return ['eq', 'gt', 'lt', 'gte', 'lte', 'between', 'is_empty', 'is_not_empty']
     default:
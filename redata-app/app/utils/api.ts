@@ -3,6 +3,8 @@ import { listen } from '@tauri-apps/api/event'
 import type { UnlistenFn } from '@tauri-apps/api/event'
 import type {
   Project,
+  RecycledProject,
+  PurgeProjectResult,
   CreateProjectRequest,
   UpdateProjectRequest,
   ProjectField,
@@ -17,6 +19,8 @@ import type {
   ExcelPreview,
   FieldDefinition,
   ColumnMappingResponse,
+  FieldMapping,
+  MappingTemplate,
   ProjectRecord,
   QueryRecordsResponse,
   Batch,
@@ -24,6 +28,10 @@ import type {
   ProcessingProgress,
   FullTaskProgressResponse,
   RollbackResult,
+  RestoreBatchResult,
+  RollbackScope,
+  RollbackPreview,
+  RecordHistoryEntry,
   BatchDetailResponse,
   ProjectGroupResponse,
   GroupWithChildren,
@@ -110,6 +118,21 @@ export const projectsApi = {
     })
   },
 
+  // 回收站中的项目
+  listDeleted: async () => {
+    return await invoke<RecycledProject[]>('get_deleted_projects')
+  },
+
+  // 从回收站恢复项目
+  restore: async (id: number) => {
+    return await invoke<Project>('restore_project', { id })
+  },
+
+  // 彻底删除回收站中的项目及其全部数据
+  purge: async (id: number) => {
+    return await invoke<PurgeProjectResult>('purge_project', { id })
+  },
+
   // 移动项目到分组
   moveToGroup: async (projectId: number, groupId: number | null) => {
     return await invoke<void>('move_project_to_group', { projectId, groupId })
@@ -213,6 +236,10 @@ export const fieldsApi = {
         additionalRequirement: data.additional_requirement || null,
         validationRule: data.validation_rule || null,
         extractionHint: data.extraction_hint || null,
+        typeOptions: data.type_options || null,
+        transforms: data.transforms || null,
+        expression: data.expression || null,
+        aiExtractSource: data.ai_extract_source || null,
       }
 
       return await invoke<ProjectField>('create_field', params)
@@ -240,6 +267,11 @@ export const fieldsApi = {
         additionalRequirement: data.additional_requirement || null,
         validationRule: data.validation_rule || null,
         extractionHint: data.extraction_hint || null,
+        // 未传入时保留原配置，空字符串表示清除
+        typeOptions: data.type_options ?? null,
+        transforms: data.transforms ?? null,
+        expression: data.expression ?? null,
+        aiExtractSource: data.ai_extract_source ?? null,
       }
 
       return await invoke<ProjectField>('update_field', params)
@@ -416,9 +448,14 @@ export const processingApi = {
   start: async (data: StartProcessingRequest) => {
     if (USE_TAURI_COMMANDS) {
       return await invoke<{ task_id: string; batch_number: string; project_id: number; status: string; source_files: string[] }>('start_processing', {
-        projectId: data.project_id,
-        filePaths: data.file_paths,
-        aiConfigId: data.ai_config_id,
+        request: {
+          project_id: data.project_id,
+          file_paths: data.file_paths,
+          ai_config_id: data.ai_config_id ?? null,
+          review_mapping: data.review_mapping ?? false,
+          mapping_mode: data.mapping_mode ?? null,
+          file_specs: data.file_specs ?? [],
+        },
       })
     }
     return request<ProcessingTask>('/processing/start', {
@@ -491,6 +528,21 @@ export const processingApi = {
     })
   },
 
+  // 从检查点继续被中断的任务（已完成的文件和 Sheet 跳过，不重新分析列映射）
+  resumeInterrupted: async (taskId: string) => {
+    if (USE_TAURI_COMMANDS) {
+      return await invoke<{ task_id: string; batch_number: string; project_id: number; status: string; source_files: string[] }>('resume_interrupted_task', { taskId })
+    }
+    return request<ProcessingTask>(`/processing/resume-interrupted/${taskId}`, {
+      method: 'POST',
+    })
+  },
+
+  // 确认（或修改）列映射，等待确认的导入随即继续
+  confirmMapping: async (taskId: string, headerRow: number, mappings: FieldMapping[]): Promise<void> => {
+    return await invoke<void>('confirm_column_mapping', { taskId, headerRow, mappings })
+  },
+
   // 监听进度事件（Tauri 模式）
   onProgress: (callback: (progress: ProcessingProgress) => void): Promise<UnlistenFn> => {
     return listen<ProcessingProgress>('processing-progress', (event) => {
@@ -511,6 +563,26 @@ export const batchesApi = {
   rollback: async (projectId: number, batchNumber: string): Promise<RollbackResult> => {
     return await invoke<RollbackResult>('rollback_batch', { projectId, batchNumber })
   },
+
+  // 恢复撤回的导入（保留期内）
+  restore: async (projectId: number, batchNumber: string): Promise<RestoreBatchResult> => {
+    return await invoke<RestoreBatchResult>('restore_batch', { projectId, batchNumber })
+  },
+
+  // 预览撤回范围内的记录数
+  previewRollback: async (projectId: number, scope: RollbackScope): Promise<RollbackPreview> => {
+    return await invoke<RollbackPreview>('preview_rollback', { projectId, scope })
+  },
+
+  // 按文件或 Sheet 撤回部分导入
+  rollbackScope: async (projectId: number, scope: RollbackScope): Promise<RollbackResult> => {
+    return await invoke<RollbackResult>('rollback_records', { projectId, scope })
+  },
+
+  // 恢复按文件或 Sheet 撤回的记录
+  restoreScope: async (projectId: number, scope: RollbackScope): Promise<RestoreBatchResult> => {
+    return await invoke<RestoreBatchResult>('restore_records', { projectId, scope })
+  },
 }
 
 // ============ 结果 API ============
@@ -733,6 +805,34 @@ export const recordsApi = {
   },
 }
 
+// ============ 映射模板 API ============
+
+export const mappingTemplatesApi = {
+  // 获取项目已保存的列映射模板（最新在前）
+  list: async (projectId: number): Promise<MappingTemplate[]> => {
+    return await invoke<MappingTemplate[]>('list_mapping_templates', { projectId })
+  },
+
+  // 删除映射模板
+  delete: async (id: number): Promise<void> => {
+    return await invoke<void>('delete_mapping_template', { id })
+  },
+}
+
+// ============ 记录历史 API ============
+
+export const recordHistoryApi = {
+  // 获取记录的修改历史（最新在前）
+  list: async (recordId: number): Promise<RecordHistoryEntry[]> => {
+    return await invoke<RecordHistoryEntry[]>('get_record_history', { recordId })
+  },
+
+  // 将记录回退到某条历史修改之前的版本
+  revert: async (historyId: number, actor?: string): Promise<ProjectRecord> => {
+    return await invoke<ProjectRecord>('revert_record', { historyId, actor })
+  },
+}
+
 export const aiServiceApi = {
   // 分析列映射
   analyzeColumnMapping: async (
@@ -793,6 +893,7 @@ export const aiServiceApi = {
 
 export interface ProjectStatistics {
   total_records: number
+  rejected_records: number
   today_records: number
   week_records: number
   month_records: number
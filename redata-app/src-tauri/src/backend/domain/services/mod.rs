@@ -1 +1,6 @@
 // services module
+//
+// 不依赖数据库与 Tauri 的共用领域逻辑（命令层与持久化层共用）
+
+pub mod number_utils;
+pub mod text_utils;
@@ -0,0 +1,154 @@
+// 数值解析与规范化
+//
+// number 字段在导入时统一为规范的十进制数字字符串，并以 JSON 数字存储，筛选时按数值比较：
+// - 去除货币符号 / 币种（¥ ￥ $ € £ 元 RMB CNY USD 等）和千分位分隔符
+// - 中文单位换算：万、亿、百万、千万、万亿（按字符串移位，避免浮点误差）
+// - 会计格式的负数：(1,234.50) → -1234.5
+// - 全角数字与符号转为半角
+
+use serde_json::{Number, Value};
+
+use super::text_utils::to_half_width;
+
+/// 可去除的货币符号与币种写法（较长的写在前面）
+const CURRENCY_TOKENS: &[&str] = &[
+    "RMB", "CNY", "USD", "EUR", "HKD", "JPY", "GBP", "人民币", "美元", "港币", "欧元", "日元",
+    "元整", "元", "圆", "块", "¥", "￥", "$", "€", "£", "₩",
+];
+
+/// 中文数量单位及其十进制位数（较长的写在前面）
+const UNITS: &[(&str, u32)] = &[
+    ("万亿", 12),
+    ("千万", 7),
+    ("百万", 6),
+    ("亿", 8),
+    ("万", 4),
+];
+
+/// 将十进制数字串乘以 10^shift（移动小数点），返回规范形式
+fn shift_decimal(int_part: &str, frac_part: &str, shift: u32) -> String {
+    let mut digits = format!("{}{}", int_part, frac_part);
+    let mut point = int_part.len() + shift as usize;
+    while digits.len() < point {
+        digits.push('0');
+    }
+    let (int_digits, frac_digits) = digits.split_at(point);
+
+    let int_digits = int_digits.trim_start_matches('0');
+    let frac_digits = frac_digits.trim_end_matches('0');
+    point = int_digits.len();
+    let int_digits = if point == 0 { "0" } else { int_digits };
+
+    if frac_digits.is_empty() {
+        int_digits.to_string()
+    } else {
+        format!("{}.{}", int_digits, frac_digits)
+    }
+}
+
+/// 解析数值并规范化为十进制字符串（如 "¥1,234.50万" → "12345000"）；无法识别时返回 None
+pub fn normalize_number(value: &str) -> Option<String> {
+    let mut s: String = to_half_width(value)
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, ',' | '\'' | '_'))
+        .collect();
+    if s.is_empty() {
+        return None;
+    }
+
+    // 会计格式的负数
+    let mut negative = false;
+    if s.starts_with('(') && s.ends_with(')') {
+        negative = true;
+        s = s[1..s.len() - 1].to_string();
+    }
+
+    // 币种 / 货币符号可能出现在数字前后，单位只出现在数字后（如 1.5万元、$1.2亿）
+    for token in CURRENCY_TOKENS {
+        s = s.replace(token, "");
+    }
+    let mut shift = 0;
+    if let Some((unit, digits)) = UNITS.iter().find(|(unit, _)| s.ends_with(unit)) {
+        shift = *digits;
+        s.truncate(s.len() - unit.len());
+    }
+
+    if let Some(rest) = s.strip_prefix('-') {
+        negative = !negative;
+        s = rest.to_string();
+    } else if let Some(rest) = s.strip_prefix('+') {
+        s = rest.to_string();
+    }
+
+    let (int_part, frac_part) = match s.split_once('.') {
+        Some((i, f)) => (i, f),
+        None => (s.as_str(), ""),
+    };
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+    if !int_part.chars().all(|c| c.is_ascii_digit()) || !frac_part.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let normalized = shift_decimal(int_part, frac_part, shift);
+    if negative && normalized != "0" {
+        Some(format!("-{}", normalized))
+    } else {
+        Some(normalized)
+    }
+}
+
+/// 将规范化后的数字字符串转为 JSON 数字（整数优先），无法表示时返回 None
+pub fn number_json_value(normalized: &str) -> Option<Value> {
+    if let Ok(i) = normalized.parse::<i64>() {
+        return Some(Value::Number(i.into()));
+    }
+    normalized
+        .parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map(Value::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_number() {
+        assert_eq!(normalize_number("1,234.50").as_deref(), Some("1234.5"));
+        assert_eq!(normalize_number("¥ 1,234").as_deref(), Some("1234"));
+        assert_eq!(normalize_number("$99.90").as_deref(), Some("99.9"));
+        assert_eq!(normalize_number("100元").as_deref(), Some("100"));
+        assert_eq!(normalize_number("-12").as_deref(), Some("-12"));
+        assert_eq!(normalize_number("(1,200.00)").as_deref(), Some("-1200"));
+        assert_eq!(normalize_number("１２３．５").as_deref(), Some("123.5"));
+        assert_eq!(normalize_number("007").as_deref(), Some("7"));
+        assert_eq!(normalize_number(".5").as_deref(), Some("0.5"));
+    }
+
+    #[test]
+    fn test_chinese_units() {
+        assert_eq!(normalize_number("1.23万").as_deref(), Some("12300"));
+        assert_eq!(normalize_number("3亿").as_deref(), Some("300000000"));
+        assert_eq!(normalize_number("1.5万元").as_deref(), Some("15000"));
+        assert_eq!(normalize_number("¥2.5千万").as_deref(), Some("25000000"));
+        assert_eq!(normalize_number("0.00001万").as_deref(), Some("0.1"));
+    }
+
+    #[test]
+    fn test_rejects_non_numbers() {
+        assert_eq!(normalize_number(""), None);
+        assert_eq!(normalize_number("abc"), None);
+        assert_eq!(normalize_number("12a"), None);
+        assert_eq!(normalize_number("1.2.3"), None);
+        assert_eq!(normalize_number("元"), None);
+    }
+
+    #[test]
+    fn test_number_json_value() {
+        assert_eq!(number_json_value("100"), Some(serde_json::json!(100)));
+        assert_eq!(number_json_value("-1234.5"), Some(serde_json::json!(-1234.5)));
+    }
+}
@@ -0,0 +1,30 @@
+// 文本规范化公共函数（数值、电话等解析共用）
+
+/// 全角数字、符号转半角
+pub fn to_half_width(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            '．' | '。' => '.',
+            '，' => ',',
+            '－' | '−' | '—' => '-',
+            '＋' => '+',
+            '（' => '(',
+            '）' => ')',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_half_width() {
+        assert_eq!(to_half_width("１２３．５"), "123.5");
+        assert_eq!(to_half_width("（０７５５）—８８８８６６６６"), "(0755)-88886666");
+        assert_eq!(to_half_width("＋８６ １３８，１３９"), "+86 138,139");
+        assert_eq!(to_half_width("北京市"), "北京市");
+    }
+}
@@ -1,6 +1,8 @@
 // 数据库迁移模块
 
-use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, Statement};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, Statement, TransactionTrait};
+
+use crate::backend::domain::services::number_utils::{normalize_number, number_json_value};
 
 /// 运行所有迁移
 pub async fn run_migrations(db: &DatabaseConnection) -> Result<(), DbErr> {
@@ -39,6 +41,51 @@ pub async fn run_migrations(db: &DatabaseConnection) -> Result<(), DbErr> {
     // v0.1.2 迁移：为项目表添加 group_id 字段
     add_group_id_to_projects(db).await?;
 
+    // v0.1.3 迁移：任务文件进度表添加更新/合并计数列
+    add_dedup_count_columns(db).await?;
+
+    // v0.1.3 迁移：创建列映射模板表
+    create_mapping_templates_table(db).await?;
+
+    // v0.1.3 迁移：创建 AI 列映射缓存表
+    create_mapping_cache_table(db).await?;
+
+    // v0.1.3 迁移：字段表添加类型配置列
+    add_field_type_options_column(db).await?;
+
+    // v0.1.3 迁移：电话字段默认验证规则支持固话、分机号和多号码
+    update_phone_validation_rule(db).await?;
+
+    // v0.1.3 迁移：字段表添加转换管道列
+    add_field_transforms_column(db).await?;
+
+    // v0.1.3 迁移：字段表添加计算表达式列
+    add_field_expression_column(db).await?;
+
+    // v0.1.3 迁移：字段表添加 AI 提取来源列
+    add_field_ai_extract_source_column(db).await?;
+
+    // v0.1.3 迁移：项目表添加 deleted_at 列（回收站）
+    add_project_deleted_at_column(db).await?;
+
+    // v0.1.3 迁移：创建撤回记录表（撤回导入后可恢复）
+    create_rolled_back_records_table(db).await?;
+
+    // v0.1.3 迁移：创建记录修改历史表（只追加）
+    create_record_history_table(db).await?;
+
+    // v0.1.3 迁移：任务表添加处理参数与检查点列（中断后继续）
+    add_task_checkpoint_columns(db).await?;
+
+    // v0.1.3 迁移：创建一次性数据迁移记录表
+    create_schema_migrations_table(db).await?;
+
+    // v0.1.3 迁移（一次性）：数值 / 金额字段中以字符串保存的历史值转为 JSON 数字（筛选按数值比较）
+    if !migration_applied(db, "backfill_number_values").await? {
+        backfill_number_values(db).await?;
+        mark_migration_applied(db, "backfill_number_values").await?;
+    }
+
     tracing::info!("Database migrations completed");
 
     Ok(())
@@ -82,6 +129,10 @@ async fn create_project_fields_table(db: &DatabaseConnection) -> Result<(), DbEr
             additional_requirement TEXT,
             validation_rule TEXT,
             extraction_hint TEXT,
+            type_options TEXT,
+            transforms TEXT,
+            expression TEXT,
+            ai_extract_source TEXT,
             display_order INTEGER NOT NULL DEFAULT 0,
             created_at TEXT NOT NULL,
             deleted_at TEXT
@@ -404,3 +455,496 @@ async fn add_group_id_to_projects(db: &DatabaseConnection) -> Result<(), DbErr>
 
     Ok(())
 }
+
+/// v0.1.3 迁移：为任务文件进度表添加 updated_count / merged_count 列（去重策略 update / merge 计数）
+async fn add_dedup_count_columns(db: &DatabaseConnection) -> Result<(), DbErr> {
+    for column in ["updated_count", "merged_count"] {
+        // 检查列是否已存在
+        let result = db
+            .query_one(Statement::from_string(
+                db.get_database_backend(),
+                format!("SELECT name FROM pragma_table_info('task_file_progress') WHERE name = '{}'", column),
+            ))
+            .await?;
+
+        if result.is_none() {
+            db.execute(Statement::from_string(
+                db.get_database_backend(),
+                format!("ALTER TABLE task_file_progress ADD COLUMN {} INTEGER NOT NULL DEFAULT 0", column),
+            ))
+            .await?;
+            tracing::info!("Added {} column to task_file_progress table", column);
+        }
+    }
+
+    Ok(())
+}
+
+/// v0.1.3 迁移：创建列映射模板表（按项目 + 表头指纹唯一）
+async fn create_mapping_templates_table(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let sql = r#"
+        CREATE TABLE IF NOT EXISTS mapping_templates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            header_fingerprint TEXT NOT NULL,
+            headers TEXT NOT NULL DEFAULT '[]',
+            header_row INTEGER NOT NULL DEFAULT 0,
+            mappings TEXT NOT NULL DEFAULT '[]',
+            created_at TEXT NOT NULL,
+            updated_at TEXT
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        sql.to_string(),
+    ))
+    .await?;
+
+    // 创建索引
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_mapping_templates_fingerprint ON mapping_templates(project_id, header_fingerprint)".to_string(),
+    ))
+    .await?;
+
+    tracing::info!("Created mapping_templates table");
+    Ok(())
+}
+
+/// v0.1.3 迁移：创建 AI 列映射缓存表（按项目 + 表头指纹 + 字段定义哈希唯一）
+async fn create_mapping_cache_table(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let sql = r#"
+        CREATE TABLE IF NOT EXISTS ai_mapping_cache (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            header_fingerprint TEXT NOT NULL,
+            field_hash TEXT NOT NULL,
+            mapping TEXT NOT NULL,
+            hit_count INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            last_hit_at TEXT
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        sql.to_string(),
+    ))
+    .await?;
+
+    // 创建索引
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_ai_mapping_cache_key ON ai_mapping_cache(project_id, header_fingerprint, field_hash)".to_string(),
+    ))
+    .await?;
+
+    tracing::info!("Created ai_mapping_cache table");
+    Ok(())
+}
+
+/// v0.1.3 迁移：为字段表添加 type_options 列（字段类型相关配置，JSON）
+async fn add_field_type_options_column(db: &DatabaseConnection) -> Result<(), DbErr> {
+    // 检查列是否已存在
+    let result = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT name FROM pragma_table_info('project_fields') WHERE name = 'type_options'".to_string(),
+        ))
+        .await?;
+
+    if result.is_none() {
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            "ALTER TABLE project_fields ADD COLUMN type_options TEXT".to_string(),
+        ))
+        .await?;
+        tracing::info!("Added type_options column to project_fields table");
+    }
+
+    Ok(())
+}
+
+/// v0.1.3 迁移：将电话字段旧的默认验证规则（仅手机号）更新为新规则（手机号 / 固话 / 分机号 / 多号码）
+///
+/// 只更新仍为旧默认值的字段，用户自定义的规则保持不变
+async fn update_phone_validation_rule(db: &DatabaseConnection) -> Result<(), DbErr> {
+    const OLD_RULE: &str = r"^1[3-9]\d{9}$";
+    const NUMBER: &str = r"(1[3-9]\d{9}|0\d{2,3}-\d{7,8}(转\d{1,6})?|[48]00\d{7}|\d{7,8}(转\d{1,6})?)";
+    let new_rule = format!("^{}(; {})*$", NUMBER, NUMBER);
+
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "UPDATE project_fields SET validation_rule = ? WHERE field_type = 'phone' AND validation_rule = ?",
+            vec![new_rule.into(), OLD_RULE.into()],
+        ))
+        .await?;
+
+    if result.rows_affected() > 0 {
+        tracing::info!("Updated validation_rule of {} phone fields", result.rows_affected());
+    }
+
+    Ok(())
+}
+
+/// v0.1.3 迁移：为字段表添加 transforms 列（字段转换管道，JSON 数组）
+async fn add_field_transforms_column(db: &DatabaseConnection) -> Result<(), DbErr> {
+    // 检查列是否已存在
+    let result = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT name FROM pragma_table_info('project_fields') WHERE name = 'transforms'".to_string(),
+        ))
+        .await?;
+
+    if result.is_none() {
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            "ALTER TABLE project_fields ADD COLUMN transforms TEXT".to_string(),
+        ))
+        .await?;
+        tracing::info!("Added transforms column to project_fields table");
+    }
+
+    Ok(())
+}
+
+/// v0.1.3 迁移：为字段表添加 expression 列（计算字段表达式）
+async fn add_field_expression_column(db: &DatabaseConnection) -> Result<(), DbErr> {
+    // 检查列是否已存在
+    let result = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT name FROM pragma_table_info('project_fields') WHERE name = 'expression'".to_string(),
+        ))
+        .await?;
+
+    if result.is_none() {
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            "ALTER TABLE project_fields ADD COLUMN expression TEXT".to_string(),
+        ))
+        .await?;
+        tracing::info!("Added expression column to project_fields table");
+    }
+
+    Ok(())
+}
+
+/// v0.1.3 迁移：为字段表添加 ai_extract_source 列（AI 逐行提取的来源列）
+async fn add_field_ai_extract_source_column(db: &DatabaseConnection) -> Result<(), DbErr> {
+    // 检查列是否已存在
+    let result = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT name FROM pragma_table_info('project_fields') WHERE name = 'ai_extract_source'".to_string(),
+        ))
+        .await?;
+
+    if result.is_none() {
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            "ALTER TABLE project_fields ADD COLUMN ai_extract_source TEXT".to_string(),
+        ))
+        .await?;
+        tracing::info!("Added ai_extract_source column to project_fields table");
+    }
+
+    Ok(())
+}
+
+/// v0.1.3 迁移：为项目表添加 deleted_at 列（软删除，移入回收站的项目保留全部关联数据）
+async fn add_project_deleted_at_column(db: &DatabaseConnection) -> Result<(), DbErr> {
+    // 检查列是否已存在
+    let result = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT name FROM pragma_table_info('projects') WHERE name = 'deleted_at'".to_string(),
+        ))
+        .await?;
+
+    if result.is_none() {
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            "ALTER TABLE projects ADD COLUMN deleted_at TEXT".to_string(),
+        ))
+        .await?;
+
+        // 创建索引
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            "CREATE INDEX IF NOT EXISTS idx_projects_deleted ON projects(deleted_at)".to_string(),
+        ))
+        .await?;
+
+        tracing::info!("Added deleted_at column to projects table");
+    }
+
+    Ok(())
+}
+
+/// v0.1.3 迁移：创建撤回记录表（撤回导入时记录从 project_records 移入此表，保留原 ID，过期后清除）
+async fn create_rolled_back_records_table(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let sql = r#"
+        CREATE TABLE IF NOT EXISTS rolled_back_records (
+            id INTEGER PRIMARY KEY,
+            project_id INTEGER NOT NULL,
+            data TEXT NOT NULL DEFAULT '{}',
+            raw_data TEXT,
+            source_file TEXT,
+            source_sheet TEXT,
+            row_number INTEGER,
+            batch_number TEXT,
+            status TEXT NOT NULL DEFAULT 'success',
+            error_message TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT,
+            rolled_back_at TEXT NOT NULL
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        sql.to_string(),
+    ))
+    .await?;
+
+    // 创建索引
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        "CREATE INDEX IF NOT EXISTS idx_rolled_back_records_batch ON rolled_back_records(project_id, batch_number)".to_string(),
+    ))
+    .await?;
+
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        "CREATE INDEX IF NOT EXISTS idx_rolled_back_records_time ON rolled_back_records(rolled_back_at)".to_string(),
+    ))
+    .await?;
+
+    tracing::info!("Created rolled_back_records table");
+    Ok(())
+}
+
+/// v0.1.3 迁移：创建记录修改历史表（只追加，触发器禁止修改和删除）
+async fn create_record_history_table(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let sql = r#"
+        CREATE TABLE IF NOT EXISTS record_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            record_id INTEGER NOT NULL,
+            project_id INTEGER NOT NULL,
+            operation TEXT NOT NULL,
+            before_data TEXT,
+            before_status TEXT,
+            before_error_message TEXT,
+            after_data TEXT,
+            after_status TEXT,
+            after_error_message TEXT,
+            actor TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        sql.to_string(),
+    ))
+    .await?;
+
+    // 创建索引
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        "CREATE INDEX IF NOT EXISTS idx_record_history_record ON record_history(record_id)".to_string(),
+    ))
+    .await?;
+
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        "CREATE INDEX IF NOT EXISTS idx_record_history_project ON record_history(project_id)".to_string(),
+    ))
+    .await?;
+
+    // 只追加：禁止修改和删除历史
+    for (name, event) in [("record_history_no_update", "UPDATE"), ("record_history_no_delete", "DELETE")] {
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            format!(
+                "CREATE TRIGGER IF NOT EXISTS {} BEFORE {} ON record_history \
+                 BEGIN SELECT RAISE(ABORT, 'record_history is append-only'); END",
+                name, event
+            ),
+        ))
+        .await?;
+    }
+
+    tracing::info!("Created record_history table");
+    Ok(())
+}
+
+/// v0.1.3 迁移：为任务表添加 options / checkpoint 列（处理参数与行级检查点，中断后从检查点继续），
+/// 为任务文件进度表添加 file_path 列（继续时按完整路径识别已完成的文件，同名文件不会混淆）
+async fn add_task_checkpoint_columns(db: &DatabaseConnection) -> Result<(), DbErr> {
+    for (table, column) in [
+        ("processing_tasks", "options"),
+        ("processing_tasks", "checkpoint"),
+        ("task_file_progress", "file_path"),
+    ] {
+        // 检查列是否已存在
+        let result = db
+            .query_one(Statement::from_string(
+                db.get_database_backend(),
+                format!("SELECT name FROM pragma_table_info('{}') WHERE name = '{}'", table, column),
+            ))
+            .await?;
+
+        if result.is_none() {
+            db.execute(Statement::from_string(
+                db.get_database_backend(),
+                format!("ALTER TABLE {} ADD COLUMN {} TEXT", table, column),
+            ))
+            .await?;
+            tracing::info!("Added {} column to {} table", column, table);
+        }
+    }
+
+    Ok(())
+}
+
+/// 一次性数据迁移的执行记录（表结构迁移可重复执行，数据迁移只执行一次）
+async fn create_schema_migrations_table(db: &DatabaseConnection) -> Result<(), DbErr> {
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        "CREATE TABLE IF NOT EXISTS schema_migrations (name TEXT PRIMARY KEY, applied_at TEXT NOT NULL)".to_string(),
+    ))
+    .await?;
+    Ok(())
+}
+
+async fn migration_applied(db: &DatabaseConnection, name: &str) -> Result<bool, DbErr> {
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "SELECT name FROM schema_migrations WHERE name = ?",
+            vec![name.into()],
+        ))
+        .await?;
+    Ok(row.is_some())
+}
+
+async fn mark_migration_applied(db: &DatabaseConnection, name: &str) -> Result<(), DbErr> {
+    db.execute(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        "INSERT OR IGNORE INTO schema_migrations (name, applied_at) VALUES (?, ?)",
+        vec![name.into(), chrono::Utc::now().to_rfc3339().into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// v0.1.3 迁移：将数值 / 金额字段中以字符串保存的值转为 JSON 数字
+///
+/// 早期版本把数值存为字符串（如 "1,200"、"¥3.5万"），而数值筛选按 CAST(... AS REAL) 比较，
+/// 这类值会被截断（"1,200" → 1）。这里按导入时相同的规则规范化后改存为 JSON 数字；
+/// 无法识别为数值的文本保持原样。只在升级时执行一次，每条修改的记录写入 record_history（操作类型 backfill）。
+async fn backfill_number_values(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let fields = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT id, project_id FROM project_fields WHERE field_type IN ('number', 'currency') AND is_deleted = 0"
+                .to_string(),
+        ))
+        .await?;
+
+    let mut by_project: std::collections::BTreeMap<i32, Vec<i32>> = std::collections::BTreeMap::new();
+    for row in fields {
+        let field_id: i32 = row.try_get("", "id")?;
+        let project_id: i32 = row.try_get("", "project_id")?;
+        by_project.entry(project_id).or_default().push(field_id);
+    }
+
+    let mut converted = 0u64;
+    for (project_id, field_ids) in by_project {
+        // 字段 ID 为整数，可直接拼入 JSON 路径
+        let text_filter = field_ids
+            .iter()
+            .map(|id| format!("json_type(data, '$.{}') = 'text'", id))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let records = db
+            .query_all(Statement::from_sql_and_values(
+                db.get_database_backend(),
+                format!(
+                    "SELECT id, data, status, error_message FROM project_records WHERE project_id = ? AND ({})",
+                    text_filter
+                ),
+                vec![project_id.into()],
+            ))
+            .await?;
+        if records.is_empty() {
+            continue;
+        }
+
+        let txn = db.begin().await?;
+        for row in records {
+            let record_id: i32 = row.try_get("", "id")?;
+            let data_str: String = row.try_get("", "data")?;
+            let Ok(mut data) = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&data_str) else {
+                continue;
+            };
+
+            let mut changed = false;
+            for field_id in &field_ids {
+                let key = field_id.to_string();
+                let number = match data.get(&key) {
+                    Some(serde_json::Value::String(s)) => normalize_number(s).and_then(|n| number_json_value(&n)),
+                    _ => None,
+                };
+                if let Some(number) = number {
+                    data.insert(key, number);
+                    changed = true;
+                }
+            }
+
+            if changed {
+                let status: String = row.try_get("", "status")?;
+                let error_message: Option<String> = row.try_get("", "error_message")?;
+                let new_data = serde_json::Value::Object(data).to_string();
+                txn.execute(Statement::from_sql_and_values(
+                    db.get_database_backend(),
+                    "INSERT INTO record_history \
+                     (record_id, project_id, operation, before_data, before_status, before_error_message, \
+                      after_data, after_status, after_error_message, actor, created_at) \
+                     VALUES (?, ?, 'backfill', ?, ?, ?, ?, ?, ?, 'system:migration', ?)",
+                    vec![
+                        record_id.into(),
+                        project_id.into(),
+                        data_str.into(),
+                        status.clone().into(),
+                        error_message.clone().into(),
+                        new_data.clone().into(),
+                        status.into(),
+                        error_message.into(),
+                        chrono::Utc::now().to_rfc3339().into(),
+                    ],
+                ))
+                .await?;
+                txn.execute(Statement::from_sql_and_values(
+                    db.get_database_backend(),
+                    "UPDATE project_records SET data = ? WHERE id = ?",
+                    vec![new_data.into(), record_id.into()],
+                ))
+                .await?;
+                converted += 1;
+            }
+        }
+        txn.commit().await?;
+    }
+
+    if converted > 0 {
+        tracing::info!("Converted number values of {} records to JSON numbers", converted);
+    }
+
+    Ok(())
+}
@@ -36,6 +36,18 @@ pub struct Model {
 
     pub extraction_hint: Option<String>,
 
+    /// 字段类型相关配置（JSON，如日期的日/月顺序）
+    pub type_options: Option<String>,
+
+    /// 字段转换管道（JSON 数组，导入时在类型清洗前按顺序执行）
+    pub transforms: Option<String>,
+
+    /// 计算字段表达式（非空时字段值由其他字段或原始列计算得出，不参与列映射）
+    pub expression: Option<String>,
+
+    /// AI 逐行提取的来源列（表头名称；非空时由 AI 从该列的自由文本中提取字段值，不参与列映射）
+    pub ai_extract_source: Option<String>,
+
     #[sea_orm(default_value = "0")]
     pub display_order: i32,
 
@@ -0,0 +1,37 @@
+// MappingCache 模型 - AI 列映射缓存
+// 以「项目 + 归一化表头 + 字段定义哈希」为 key，相同版式的 Sheet 直接复用 AI 映射结果
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "ai_mapping_cache")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(indexed)]
+    pub project_id: i32,
+
+    /// 归一化表头的指纹
+    pub header_fingerprint: String,
+
+    /// 字段定义哈希（字段增删改后自动失效）
+    pub field_hash: String,
+
+    /// 完整的 AI 映射结果，JSON 格式（ColumnMappingResponse）
+    #[sea_orm(column_type = "Text")]
+    pub mapping: String,
+
+    /// 命中次数
+    pub hit_count: i32,
+
+    pub created_at: DateTimeUtc,
+
+    pub last_hit_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
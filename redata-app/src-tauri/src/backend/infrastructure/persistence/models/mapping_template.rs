@@ -0,0 +1,38 @@
+// MappingTemplate 模型 - 人工确认的列映射模板
+// 以表头指纹为 key，相同表头的文件直接复用映射，跳过 AI 分析
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "mapping_templates")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    #[sea_orm(indexed)]
+    pub project_id: i32,
+
+    /// 归一化表头的指纹
+    pub header_fingerprint: String,
+
+    /// 表头列名，JSON 数组格式
+    #[sea_orm(column_type = "Text")]
+    pub headers: String,
+
+    /// 表头所在行（从 0 计数）
+    pub header_row: i32,
+
+    /// 字段映射列表，JSON 数组格式（FieldMapping）
+    #[sea_orm(column_type = "Text")]
+    pub mappings: String,
+
+    pub created_at: DateTimeUtc,
+
+    pub updated_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
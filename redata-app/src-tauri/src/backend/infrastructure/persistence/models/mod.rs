@@ -3,6 +3,8 @@
 pub mod ai_config;
 pub mod batch;
 pub mod field;
+pub mod mapping_cache;
+pub mod mapping_template;
 pub mod project;
 pub mod project_group;
 pub mod record;
@@ -13,6 +15,8 @@ pub mod task_file_progress;
 pub use ai_config::Entity as AiConfig;
 pub use batch::Entity as Batch;
 pub use field::Entity as ProjectField;
+pub use mapping_cache::Entity as MappingCache;
+pub use mapping_template::Entity as MappingTemplate;
 pub use project::Entity as Project;
 pub use project_group::Entity as ProjectGroup;
 pub use record::Entity as ProjectRecord;
@@ -29,6 +29,9 @@ pub struct Model {
     pub created_at: DateTimeUtc,
 
     pub updated_at: Option<DateTimeUtc>,
+
+    /// 移入回收站的时间（为空表示未删除）
+    pub deleted_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
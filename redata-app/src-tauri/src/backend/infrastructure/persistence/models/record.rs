@@ -26,9 +26,11 @@ pub struct Model {
     pub row_number: Option<i32>,
     pub batch_number: Option<String>,
 
+    /// success, validation_error, duplicate
     #[sea_orm(default_value = "success")]
     pub status: String,
 
+    /// 被拒绝原因（验证失败项 / 重复记录）
     pub error_message: Option<String>,
     pub created_at: String,
     pub updated_at: Option<String>,
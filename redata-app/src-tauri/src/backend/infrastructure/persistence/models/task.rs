@@ -40,6 +40,14 @@ pub struct Model {
     #[sea_orm(column_type = "Text", nullable)]
     pub source_files: Option<String>,
 
+    /// 处理参数（文件路径、处理范围、映射模式等），JSON 格式，用于中断后继续
+    #[sea_orm(column_type = "Text", nullable)]
+    pub options: Option<String>,
+
+    /// 行级检查点（当前文件、Sheet、最后落库的行及使用的列映射），JSON 格式
+    #[sea_orm(column_type = "Text", nullable)]
+    pub checkpoint: Option<String>,
+
     pub created_at: DateTimeUtc,
 
     pub updated_at: Option<DateTimeUtc>,
@@ -14,6 +14,9 @@ pub struct Model {
 
     pub file_name: String,
 
+    /// 文件完整路径（中断后继续时按路径匹配，同名文件不会混淆）
+    pub file_path: Option<String>,
+
     /// waiting, processing, done, error
     pub file_phase: String,
 
@@ -38,6 +41,14 @@ pub struct Model {
     #[sea_orm(default_value = "0")]
     pub total_rows: i32,
 
+    /// 去重策略为 update 时覆盖的已有记录数
+    #[sea_orm(default_value = "0")]
+    pub updated_count: i32,
+
+    /// 去重策略为 merge 时补全的已有记录数
+    #[sea_orm(default_value = "0")]
+    pub merged_count: i32,
+
     pub error_message: Option<String>,
 
     pub created_at: DateTimeUtc,
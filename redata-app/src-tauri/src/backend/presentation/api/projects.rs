@@ -18,6 +18,7 @@ use crate::backend::infrastructure::{
     config::{AppError, Result},
     persistence::models::{project, Project},
 };
+use crate::commands::projects::move_to_recycle_bin;
 
 /// 项目创建请求
 #[derive(Debug, Deserialize)]
@@ -77,11 +78,12 @@ pub fn create_routes() -> Router<AppState> {
         .route("/:id", get(get_project).put(update_project).delete(delete_project))
 }
 
-/// 获取项目列表
+/// 获取项目列表（不含回收站中的项目）
 async fn list_projects(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<ProjectResponse>>> {
     let projects = Project::find()
+        .filter(project::Column::DeletedAt.is_null())
         .all(state.db.as_ref())
         .await
         .map_err(AppError::from)?;
@@ -128,7 +130,7 @@ async fn create_project(
     Ok((StatusCode::CREATED, Json(result.into())))
 }
 
-/// 获取单个项目
+/// 获取单个项目（回收站中的项目视为不存在）
 async fn get_project(
     State(state): State<AppState>,
     Path(id): Path<i32>,
@@ -137,6 +139,7 @@ async fn get_project(
         .one(state.db.as_ref())
         .await
         .map_err(AppError::from)?
+        .filter(|p| p.deleted_at.is_none())
         .ok_or_else(|| AppError::NotFound(format!("Project {} not found", id)))?;
 
     Ok(Json(project.into()))
@@ -153,6 +156,7 @@ async fn update_project(
         .one(state.db.as_ref())
         .await
         .map_err(AppError::from)?
+        .filter(|p| p.deleted_at.is_none())
         .ok_or_else(|| AppError::NotFound(format!("Project {} not found", id)))?;
 
     // 更新项目
@@ -186,21 +190,23 @@ async fn update_project(
     Ok(Json(result.into()))
 }
 
-/// 删除项目
+/// 删除项目（与 Tauri Command 相同，移入回收站）
 async fn delete_project(
     State(state): State<AppState>,
     Path(id): Path<i32>,
 ) -> Result<StatusCode> {
     // 查找项目
-    let project = Project::find_by_id(id)
+    Project::find_by_id(id)
         .one(state.db.as_ref())
         .await
         .map_err(AppError::from)?
+        .filter(|p| p.deleted_at.is_none())
         .ok_or_else(|| AppError::NotFound(format!("Project {} not found", id)))?;
 
-    // 删除项目
-    let active: project::ActiveModel = project.into();
-    active.delete(state.db.as_ref()).await.map_err(AppError::from)?;
+    // 移入回收站（有未结束的导入任务时拒绝）
+    move_to_recycle_bin(state.db.as_ref(), id)
+        .await
+        .map_err(AppError::InvalidInput)?;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -0,0 +1,225 @@
+// 地址解析（离线，基于 regions.rs 内置的行政区划数据）
+//
+// 将自由文本地址拆分为 省 / 市 / 区县 / 详细地址：
+// - 省份、地级市识别全称与简称（「广东省深圳市」「广东深圳」），缺省份时由城市反推
+// - 直辖市的城市即直辖市本身（「北京朝阳区」→ 北京市 / 北京市 / 朝阳区）
+// - 区县按行政区划后缀（区、县、市、旗）识别
+//
+// 解析结果与原值一起存储在记录 data 的「{field_id}_address」键下，供筛选与分面使用。
+
+use serde::{Deserialize, Serialize};
+
+use super::regions::{CITIES, MUNICIPALITIES, PROVINCES};
+
+/// 地址组成部分（筛选条件中以「{field_id}.{component}」引用）
+pub const ADDRESS_COMPONENTS: &[&str] = &["province", "city", "district", "detail"];
+
+/// 简称后紧跟这些字符时不视为行政区划（如「中山路」「朝阳区」「北京大学」）
+const NON_REGION_FOLLOWERS: &[char] = &[
+    '区', '县', '旗', '镇', '乡', '村', '路', '街', '道', '巷', '弄', '里', '号', '大',
+];
+
+/// 区县名称的后缀
+const DISTRICT_SUFFIXES: &[char] = &['区', '县', '市', '旗'];
+
+/// 区县名称中不应出现的字符（出现时说明已进入街道 / 门牌部分）
+const DETAIL_CHARS: &[char] = &[
+    '路', '街', '道', '号', '园', '村', '镇', '乡', '巷', '弄', '楼', '栋', '室', '厦', '场',
+];
+
+/// 区县名称的最大长度（含后缀，如「长阳土家族自治县」）
+const MAX_DISTRICT_CHARS: usize = 9;
+
+/// 地址解析结果（省、市、区县为行政区划全称，无法识别时为 None）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParsedAddress {
+    pub province: Option<String>,
+    pub city: Option<String>,
+    pub district: Option<String>,
+    pub detail: String,
+}
+
+/// 存储解析结果的 data 键
+pub fn address_components_key(field_id: &str) -> String {
+    format!("{}_address", field_id)
+}
+
+/// 匹配行政区划名称：全称直接匹配，简称需后面不是街道 / 区县等字符
+fn strip_region<'a>(text: &'a str, full: &str, short: &str, allow_short: bool) -> Option<&'a str> {
+    if let Some(rest) = text.strip_prefix(full) {
+        return Some(rest);
+    }
+    if !allow_short {
+        return None;
+    }
+    let rest = text.strip_prefix(short)?;
+    match rest.chars().next() {
+        Some(c) if NON_REGION_FOLLOWERS.contains(&c) => None,
+        _ => Some(rest),
+    }
+}
+
+/// 在候选列表（代码, 全称, 简称）中查找开头匹配的行政区划（全称优先，其次简称），返回（代码, 全称, 剩余文本）
+fn match_region(
+    text: &str,
+    candidates: impl Iterator<Item = (&'static str, &'static str, &'static str)> + Clone,
+    allow_short: bool,
+) -> Option<(&'static str, &'static str, &str)> {
+    if let Some(found) = candidates
+        .clone()
+        .find_map(|(key, full, short)| strip_region(text, full, short, false).map(|rest| (key, full, rest)))
+    {
+        return Some(found);
+    }
+    if !allow_short {
+        return None;
+    }
+    candidates.filter(|(_, _, short)| short.chars().count() >= 2).find_map(|(key, full, short)| {
+        strip_region(text, full, short, true).map(|rest| (key, full, rest))
+    })
+}
+
+/// 识别区县：开头到第一个区县后缀为止，长度合理且不含街道、门牌字符
+fn match_district(text: &str) -> Option<(String, &str)> {
+    for (count, (idx, c)) in text.char_indices().enumerate() {
+        if count >= MAX_DISTRICT_CHARS || DETAIL_CHARS.contains(&c) {
+            return None;
+        }
+        if DISTRICT_SUFFIXES.contains(&c) && count >= 1 {
+            let end = idx + c.len_utf8();
+            return Some((text[..end].to_string(), &text[end..]));
+        }
+    }
+    None
+}
+
+/// 去除地址开头的分隔符
+fn trim_separators(text: &str) -> &str {
+    text.trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ',' | '，' | '、' | '-' | '/'))
+}
+
+/// 解析地址；无法识别省份或城市时，整段地址作为详细地址返回
+pub fn parse_address(value: &str) -> ParsedAddress {
+    let text: String = value.split_whitespace().collect();
+    let mut rest = text.strip_prefix("中国").unwrap_or(&text);
+    rest = trim_separators(rest);
+
+    let provinces = PROVINCES.iter().copied();
+    let mut province_code = None;
+    let mut province = None;
+    let mut city = None;
+
+    // 省份全称 → 城市全称（反推省份）→ 省份简称 → 城市简称（反推省份）
+    if let Some((code, full, r)) = match_region(rest, provinces.clone(), false) {
+        (province_code, province, rest) = (Some(code), Some(full), trim_separators(r));
+    } else if let Some((code, full, r)) = match_region(rest, CITIES.iter().copied(), false) {
+        (province_code, city, rest) = (Some(code), Some(full), trim_separators(r));
+    } else if let Some((code, full, r)) = match_region(rest, provinces, true) {
+        (province_code, province, rest) = (Some(code), Some(full), trim_separators(r));
+    } else if let Some((code, full, r)) = match_region(rest, CITIES.iter().copied(), true) {
+        (province_code, city, rest) = (Some(code), Some(full), trim_separators(r));
+    }
+
+    let Some(code) = province_code else {
+        return ParsedAddress { detail: text, ..Default::default() };
+    };
+    if province.is_none() {
+        province = PROVINCES.iter().find(|(c, _, _)| *c == code).map(|(_, full, _)| *full);
+    }
+
+    if city.is_none() {
+        if MUNICIPALITIES.contains(&code) {
+            // 直辖市：城市即直辖市本身，地址中可能重复写一次（「北京市北京市朝阳区」）
+            city = province;
+            if let Some(r) = province.and_then(|p| rest.strip_prefix(p)) {
+                rest = trim_separators(r);
+            }
+        } else {
+            let cities = CITIES.iter().copied().filter(|(c, _, _)| *c == code);
+            if let Some((_, full, r)) = match_region(rest, cities, true) {
+                city = Some(full);
+                rest = trim_separators(r);
+            }
+        }
+    }
+
+    let mut district = None;
+    if city.is_some() {
+        if let Some((name, r)) = match_district(rest) {
+            district = Some(name);
+            rest = trim_separators(r);
+        }
+    }
+
+    ParsedAddress {
+        province: province.map(str::to_string),
+        city: city.map(str::to_string),
+        district,
+        detail: rest.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(value: &str) -> (Option<String>, Option<String>, Option<String>, String) {
+        let p = parse_address(value);
+        (p.province, p.city, p.district, p.detail)
+    }
+
+    fn some(s: &str) -> Option<String> {
+        Some(s.to_string())
+    }
+
+    #[test]
+    fn test_full_and_short_names() {
+        assert_eq!(
+            parts("广东省深圳市南山区科技园南区 1 栋"),
+            (some("广东省"), some("深圳市"), some("南山区"), "科技园南区1栋".to_string())
+        );
+        assert_eq!(
+            parts("广东深圳南山区科技园"),
+            (some("广东省"), some("深圳市"), some("南山区"), "科技园".to_string())
+        );
+        assert_eq!(
+            parts("中国浙江省杭州市余杭区文一西路969号"),
+            (some("浙江省"), some("杭州市"), some("余杭区"), "文一西路969号".to_string())
+        );
+        assert_eq!(
+            parts("湖北省宜昌市长阳土家族自治县龙舟坪镇"),
+            (some("湖北省"), some("宜昌市"), some("长阳土家族自治县"), "龙舟坪镇".to_string())
+        );
+    }
+
+    #[test]
+    fn test_infers_province_from_city() {
+        assert_eq!(
+            parts("苏州市昆山市前进中路"),
+            (some("江苏省"), some("苏州市"), some("昆山市"), "前进中路".to_string())
+        );
+        assert_eq!(
+            parts("吉林市船营区"),
+            (some("吉林省"), some("吉林市"), some("船营区"), String::new())
+        );
+    }
+
+    #[test]
+    fn test_municipalities() {
+        assert_eq!(
+            parts("北京朝阳区建国路88号"),
+            (some("北京市"), some("北京市"), some("朝阳区"), "建国路88号".to_string())
+        );
+        assert_eq!(
+            parts("上海市上海市浦东新区世纪大道"),
+            (some("上海市"), some("上海市"), some("浦东新区"), "世纪大道".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unrecognized_address() {
+        assert_eq!(parts("中山路100号"), (None, None, None, "中山路100号".to_string()));
+        assert_eq!(parts("朝阳区建国路"), (None, None, None, "朝阳区建国路".to_string()));
+        assert_eq!(parse_address(""), ParsedAddress::default());
+    }
+}
@@ -0,0 +1,316 @@
+// AI 逐行提取（project_fields.ai_extract_source）
+//
+// 部分表格用一列自由文本（如「备注」「联系方式」）混写姓名、电话、公司等信息，
+// 无法按列映射。配置了提取来源列的字段不参与列映射，导入时将该列的单元格文本
+// 分批发送给 AI，按字段的 extraction_hint 提取结构化值，再与普通字段一样经过
+// 转换管道、类型清洗与验证。
+//
+// - 同一来源列的多个字段共用一次调用；相同文本在同一 Sheet 内只提取一次
+// - 每批最多 EXTRACT_BATCH_SIZE 条文本，最多 EXTRACT_CONCURRENCY 个请求并发
+// - 调用次数、文本条数与 token 用量累计到任务的 AiUsageCounter
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::backend::infrastructure::persistence::models::field::Model as FieldModel;
+use super::ai_utils::{call_ai_with_usage, extract_json, AiSettings};
+use super::data_utils::json_value_to_string;
+
+/// 每次请求提取的文本条数
+pub const EXTRACT_BATCH_SIZE: usize = 20;
+
+/// 同时进行的提取请求数
+pub const EXTRACT_CONCURRENCY: usize = 3;
+
+/// 单条文本的提取结果：字段名 → 提取值；所在批次调用失败时为错误信息
+pub type ExtractedValues = Result<HashMap<String, String>, String>;
+
+/// AI 调用用量累计（按任务统计，用于估算成本）
+#[derive(Debug, Default)]
+pub struct AiUsageCounter {
+    calls: AtomicU64,
+    failed_calls: AtomicU64,
+    cells: AtomicU64,
+    prompt_tokens: AtomicU64,
+    completion_tokens: AtomicU64,
+}
+
+/// AI 调用用量快照（随处理事件发送给前端）
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct AiUsageStats {
+    pub calls: u64,
+    pub failed_calls: u64,
+    pub cells: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl AiUsageCounter {
+    pub fn snapshot(&self) -> AiUsageStats {
+        AiUsageStats {
+            calls: self.calls.load(Ordering::Relaxed),
+            failed_calls: self.failed_calls.load(Ordering::Relaxed),
+            cells: self.cells.load(Ordering::Relaxed),
+            prompt_tokens: self.prompt_tokens.load(Ordering::Relaxed),
+            completion_tokens: self.completion_tokens.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 同一来源列的 AI 提取字段及其已提取结果（按 Sheet 构建）
+pub struct ExtractionGroup<'a> {
+    pub column_index: usize,
+    pub fields: Vec<&'a FieldModel>,
+    results: HashMap<String, ExtractedValues>,
+}
+
+/// 按来源列（表头名称）分组 AI 提取字段；表头中找不到来源列的字段记录警告后跳过
+pub fn build_extraction_groups<'a>(fields: &'a [FieldModel], headers: &[String]) -> Vec<ExtractionGroup<'a>> {
+    let mut groups: Vec<ExtractionGroup<'a>> = Vec::new();
+    for field in fields {
+        let Some(source) = field.ai_extract_source.as_deref().map(str::trim).filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        let Some(column_index) = headers.iter().position(|h| h.trim() == source) else {
+            tracing::warn!("字段 {} 的提取来源列「{}」不在表头中，已跳过", field.field_name, source);
+            continue;
+        };
+        match groups.iter_mut().find(|g| g.column_index == column_index) {
+            Some(group) => group.fields.push(field),
+            None => groups.push(ExtractionGroup { column_index, fields: vec![field], results: HashMap::new() }),
+        }
+    }
+    groups
+}
+
+impl ExtractionGroup<'_> {
+    /// 当前行来源列文本的提取结果（文本为空时为 None）
+    pub fn values_for(&self, row: &[String]) -> Option<&ExtractedValues> {
+        let text = row.get(self.column_index)?.trim();
+        self.results.get(text)
+    }
+
+    /// 提取这些行中尚未提取过的文本（分批、限制并发）；返回本次提取的文本条数
+    pub async fn extract_pending<'r>(
+        &mut self,
+        rows: impl IntoIterator<Item = &'r Vec<String>>,
+        ai: Option<&AiSettings>,
+        usage: &AiUsageCounter,
+    ) -> usize {
+        let mut pending: Vec<String> = Vec::new();
+        for row in rows {
+            let Some(text) = row.get(self.column_index).map(|s| s.trim()).filter(|s| !s.is_empty()) else {
+                continue;
+            };
+            if !self.results.contains_key(text) && !pending.iter().any(|p| p == text) {
+                pending.push(text.to_string());
+            }
+        }
+        if pending.is_empty() {
+            return 0;
+        }
+
+        let Some(ai) = ai else {
+            for text in pending {
+                self.results.insert(text, Err("未配置 AI，无法提取".to_string()));
+            }
+            return 0;
+        };
+
+        let count = pending.len();
+        let fields = &self.fields;
+        let batches: Vec<Vec<String>> = pending.chunks(EXTRACT_BATCH_SIZE).map(|c| c.to_vec()).collect();
+        let outcomes: Vec<_> = futures_util::stream::iter(batches)
+            .map(|batch| async move {
+                let result = extract_batch(ai, fields, &batch, usage).await;
+                (batch, result)
+            })
+            .buffer_unordered(EXTRACT_CONCURRENCY)
+            .collect()
+            .await;
+
+        for (batch, result) in outcomes {
+            match result {
+                Ok(values) => {
+                    for (text, values) in batch.into_iter().zip(values) {
+                        self.results.insert(text, Ok(values));
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("AI 提取失败（{} 条文本）: {}", batch.len(), e);
+                    for text in batch {
+                        self.results.insert(text, Err(e.clone()));
+                    }
+                }
+            }
+        }
+        count
+    }
+}
+
+/// 调用 AI 提取一批文本，返回与文本一一对应的提取结果
+async fn extract_batch(
+    ai: &AiSettings,
+    fields: &[&FieldModel],
+    texts: &[String],
+    usage: &AiUsageCounter,
+) -> Result<Vec<HashMap<String, String>>, String> {
+    let user_prompt = build_extraction_prompt(fields, texts);
+    usage.calls.fetch_add(1, Ordering::Relaxed);
+    usage.cells.fetch_add(texts.len() as u64, Ordering::Relaxed);
+
+    let result = call_ai_with_usage(ai, EXTRACTION_SYSTEM_PROMPT, &user_prompt, true).await;
+    let (response, tokens) = result.inspect_err(|_| {
+        usage.failed_calls.fetch_add(1, Ordering::Relaxed);
+    })?;
+    usage.prompt_tokens.fetch_add(tokens.prompt_tokens, Ordering::Relaxed);
+    usage.completion_tokens.fetch_add(tokens.completion_tokens, Ordering::Relaxed);
+
+    parse_extraction_response(&response, fields, texts.len()).inspect_err(|_| {
+        usage.failed_calls.fetch_add(1, Ordering::Relaxed);
+    })
+}
+
+const EXTRACTION_SYSTEM_PROMPT: &str = r#"你是数据提取助手，负责从自由文本中提取指定字段的值。
+
+## 规则
+- 只提取文本中明确出现的信息，不要猜测或编造
+- 文本中没有的字段返回空字符串
+- 保持原文写法，不做翻译或改写
+
+## 返回格式（严格 JSON）
+{"results": [{"id": 0, "values": {"字段名": "提取值"}}]}
+
+id 与输入文本的 id 一一对应，每条文本都要返回"#;
+
+/// 构建提取请求：目标字段定义 + 带编号的待提取文本
+pub fn build_extraction_prompt(fields: &[&FieldModel], texts: &[String]) -> String {
+    let mut prompt = String::from("## 目标字段\n\n");
+    for field in fields {
+        let extra = field.additional_requirement
+            .as_ref()
+            .map(|r| format!("（{}）", r))
+            .unwrap_or_default();
+        let hint = field.extraction_hint
+            .as_ref()
+            .map(|h| format!("\n  提取要求: {}", h))
+            .unwrap_or_default();
+        prompt.push_str(&format!(
+            "- {} [{}]{}: {}{}\n",
+            field.field_name, field.field_type, extra, field.field_label, hint
+        ));
+    }
+
+    let items: Vec<serde_json::Value> = texts
+        .iter()
+        .enumerate()
+        .map(|(id, text)| serde_json::json!({"id": id, "text": text}))
+        .collect();
+    prompt.push_str(&format!(
+        "\n## 待提取文本（共 {} 条）\n\n{}\n\n## 任务\n从每条文本中提取目标字段，输出 JSON 结果。",
+        texts.len(),
+        serde_json::Value::Array(items)
+    ));
+    prompt
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractionResponse {
+    #[serde(default)]
+    results: Vec<ExtractionItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractionItem {
+    id: usize,
+    #[serde(default)]
+    values: serde_json::Map<String, serde_json::Value>,
+}
+
+/// 解析提取结果：按 id 对应到输入文本，只保留目标字段的非空值（未返回的文本视为无可提取内容）
+pub fn parse_extraction_response(
+    response: &str,
+    fields: &[&FieldModel],
+    count: usize,
+) -> Result<Vec<HashMap<String, String>>, String> {
+    let json_str = extract_json(response)?;
+    let parsed: ExtractionResponse = serde_json::from_str(&json_str)
+        .map_err(|e| format!("解析 JSON 失败: {}", e))?;
+
+    let mut results = vec![HashMap::new(); count];
+    for item in parsed.results.into_iter().filter(|item| item.id < count) {
+        for field in fields {
+            let value = item.values.get(&field.field_name).filter(|v| !v.is_null()).map(json_value_to_string);
+            if let Some(value) = value.filter(|v| !v.trim().is_empty()) {
+                results[item.id].insert(field.field_name.clone(), value);
+            }
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_field(name: &str, source: Option<&str>) -> FieldModel {
+        FieldModel {
+            id: 1,
+            project_id: 1,
+            field_name: name.to_string(),
+            field_label: name.to_string(),
+            field_type: "text".to_string(),
+            is_required: false,
+            is_dedup_key: false,
+            is_deleted: false,
+            additional_requirement: None,
+            validation_rule: None,
+            extraction_hint: None,
+            type_options: None,
+            transforms: None,
+            expression: None,
+            ai_extract_source: source.map(str::to_string),
+            display_order: 0,
+            created_at: chrono::Utc::now(),
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_build_extraction_groups() {
+        let fields = vec![
+            make_field("name", Some("备注")),
+            make_field("phone", Some(" 备注 ")),
+            make_field("company", Some("不存在")),
+            make_field("email", None),
+        ];
+        let headers = vec!["编号".to_string(), "备注".to_string()];
+        let groups = build_extraction_groups(&fields, &headers);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].column_index, 1);
+        let names: Vec<&str> = groups[0].fields.iter().map(|f| f.field_name.as_str()).collect();
+        assert_eq!(names, vec!["name", "phone"]);
+    }
+
+    #[test]
+    fn test_parse_extraction_response() {
+        let name = make_field("name", None);
+        let phone = make_field("phone", None);
+        let fields = vec![&name, &phone];
+        let response = r#"结果如下：{"results": [
+            {"id": 1, "values": {"name": "李四", "phone": "", "other": "x"}},
+            {"id": 0, "values": {"name": "张三", "phone": 13800138000}},
+            {"id": 5, "values": {"name": "越界"}}
+        ]}"#;
+        let results = parse_extraction_response(response, &fields, 3).unwrap();
+        assert_eq!(results[0].get("name").map(String::as_str), Some("张三"));
+        assert_eq!(results[0].get("phone").map(String::as_str), Some("13800138000"));
+        assert_eq!(results[1].get("name").map(String::as_str), Some("李四"));
+        assert!(!results[1].contains_key("phone") && !results[1].contains_key("other"));
+        assert!(results[2].is_empty());
+
+        assert!(parse_extraction_response("无法提取", &fields, 1).is_err());
+    }
+}
@@ -11,6 +11,7 @@ use crate::backend::infrastructure::{
     persistence::models::AiConfig,
 };
 use super::ai_utils::{call_ai, extract_json};
+use super::fields::get_validation_rule;
 
 // ============ 请求/响应结构 ============
 
@@ -35,7 +36,7 @@ pub struct FieldDefinition {
 }
 
 /// 列映射分析结果
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnMappingResponse {
     pub header_row: i32,  // 表头所在行（-1 表示无表头）
     pub mappings: Vec<FieldMapping>,
@@ -44,7 +45,7 @@ pub struct ColumnMappingResponse {
 }
 
 /// 字段映射
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldMapping {
     pub field_name: String,
     pub column_index: i32,
@@ -161,23 +162,11 @@ pub async fn ai_generate_field_metadata(
     // 构建返回结果（验证规则使用内置逻辑）
     Ok(serde_json::json!({
         "field_name": field_name,
-        "validation_rule": get_builtin_validation_rule(&field_type),
+        "validation_rule": get_validation_rule(&field_type),
         "extraction_hint": format!("提取{}字段", field_label)
     }))
 }
 
-/// 根据字段类型获取内置验证规则
-fn get_builtin_validation_rule(field_type: &str) -> Option<String> {
-    match field_type {
-        "phone" => Some(r"^1[3-9]\d{9}$".to_string()),
-        "email" => Some(r"^[\w\.-]+@[\w\.-]+\.\w+$".to_string()),
-        "url" => Some(r"^https?://".to_string()),
-        "date" => Some(r"^\d{4}[-/]\d{1,2}[-/]\d{1,2}$".to_string()),
-        "number" => Some(r"^-?\d+(\.\d+)?$".to_string()),
-        _ => None, // text 等类型无需验证
-    }
-}
-
 // ============ 辅助函数 ============
 
 /// 构建系统提示（根据实际字段类型动态生成规则表）
@@ -210,6 +199,10 @@ fn build_system_prompt(field_definitions: &[FieldDefinition]) -> String {
         ("number",  "纯数字或小数",                                    "❌ 含字母的编号不是数字字段"),
         ("id_card", "15或18位含字母X的身份证格式",                       "❌ 普通15位数字不是身份证"),
         ("url",     "以 http:// 或 https:// 开头",                    "❌ 没有协议前缀不是URL"),
+        ("enum",    "取值为少数固定选项（或其同义写法）",                 "❌ 取值各不相同的自由文本不是枚举"),
+        ("boolean", "是/否、Y/N、true/false、1/0、✓/✗ 等二值写法",         "❌ 含其他取值的列不是布尔字段"),
+        ("currency", "金额数字，可带 ¥/$/元/USD 等币种",                  "❌ 单价以外的编号、数量不是金额"),
+        ("list",    "一个单元格含多个值，以逗号/顿号/分号等分隔",          "❌ 含标点的普通句子不是多值列表"),
         ("text",    "通用文本，列名语义匹配 + 满足字段定义中的识别条件",    "—"),
     ];
 
@@ -305,6 +298,10 @@ fn get_field_type_rules(field_type: &str) -> &'static str {
         "number"  => "数据应为纯数字、整数或小数；含字母或特殊符号的编号不是数字字段",
         "id_card" => "数据应为15位纯数字或18位（前17位数字+最后1位数字或X）的身份证号格式",
         "url"     => "数据必须以 http:// 或 https:// 开头",
+        "enum"    => "数据取值应为少数固定选项（或其同义写法，如 男/M/male）；取值各不相同的自由文本不是枚举",
+        "boolean" => "数据应为是/否、Y/N、true/false、1/0、✓/✗ 等二值写法",
+        "currency" => "数据应为金额，可带 ¥、$、元、USD 等币种符号或万/亿单位；编号、数量不是金额",
+        "list"    => "一个单元格包含多个值，以逗号、顿号、分号、斜杠等分隔",
         "text"    => "通用文本字段，列名语义匹配即可，但不应映射已被其他类型明确拒绝的列",
         _         => "根据列名语义和样本数据内容综合判断",
     }
@@ -16,6 +16,22 @@ static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
         .expect("Failed to create HTTP client")
 });
 
+/// AI 调用参数（来自 AI 配置，API Key 已解密）
+pub struct AiSettings {
+    pub api_url: String,
+    pub api_key: String,
+    pub model_name: String,
+    pub temperature: f32,
+    pub max_tokens: i32,
+}
+
+/// 单次调用的 token 用量（接口未返回 usage 时为 0）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AiUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
 /// 调用 AI API（OpenAI 兼容接口，支持阿里云结构化输出）
 ///
 /// # 参数
@@ -32,9 +48,36 @@ pub async fn call_ai(
     max_tokens: i32,
     json_mode: bool,
 ) -> Result<String, String> {
-    let url = format!("{}/chat/completions", api_url.trim_end_matches('/'));
+    let body = chat_body(model_name, system_prompt, user_prompt, temperature, max_tokens, json_mode);
+    let json = post_chat_completion(api_url, api_key, &body).await?;
+    completion_content(&json)
+}
 
-    // 构建请求体
+/// 调用 AI API，同时返回 token 用量（用于统计调用成本）
+pub async fn call_ai_with_usage(
+    ai: &AiSettings,
+    system_prompt: &str,
+    user_prompt: &str,
+    json_mode: bool,
+) -> Result<(String, AiUsage), String> {
+    let body = chat_body(&ai.model_name, system_prompt, user_prompt, ai.temperature, ai.max_tokens, json_mode);
+    let json = post_chat_completion(&ai.api_url, &ai.api_key, &body).await?;
+    let usage = AiUsage {
+        prompt_tokens: json["usage"]["prompt_tokens"].as_u64().unwrap_or(0),
+        completion_tokens: json["usage"]["completion_tokens"].as_u64().unwrap_or(0),
+    };
+    Ok((completion_content(&json)?, usage))
+}
+
+/// 构建非流式请求体
+fn chat_body(
+    model_name: &str,
+    system_prompt: &str,
+    user_prompt: &str,
+    temperature: f32,
+    max_tokens: i32,
+    json_mode: bool,
+) -> serde_json::Value {
     let mut body = serde_json::json!({
         "model": model_name,
         "messages": [
@@ -49,12 +92,18 @@ pub async fn call_ai(
     if json_mode {
         body["response_format"] = serde_json::json!({"type": "json_object"});
     }
+    body
+}
+
+/// 发送请求并解析响应 JSON
+async fn post_chat_completion(api_url: &str, api_key: &str, body: &serde_json::Value) -> Result<serde_json::Value, String> {
+    let url = format!("{}/chat/completions", api_url.trim_end_matches('/'));
 
     let response = HTTP_CLIENT
         .post(&url)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
-        .json(&body)
+        .json(body)
         .send()
         .await
         .map_err(|e| format!("AI API 请求失败: {}", e))?;
@@ -65,17 +114,18 @@ pub async fn call_ai(
         return Err(format!("AI API 返回错误 {}: {}", status, body));
     }
 
-    let json: serde_json::Value = response
+    response
         .json()
         .await
-        .map_err(|e| format!("解析 AI 响应失败: {}", e))?;
+        .map_err(|e| format!("解析 AI 响应失败: {}", e))
+}
 
-    let content = json["choices"][0]["message"]["content"]
+/// 取出响应中的回复内容
+fn completion_content(json: &serde_json::Value) -> Result<String, String> {
+    json["choices"][0]["message"]["content"]
         .as_str()
-        .ok_or("AI 响应格式错误")?
-        .to_string();
-
-    Ok(content)
+        .map(str::to_string)
+        .ok_or_else(|| "AI 响应格式错误".to_string())
 }
 
 /// 流式调用 AI API，支持回调处理每个 chunk
@@ -0,0 +1,115 @@
+// 选项类字段：枚举（enum）、布尔（boolean）、多值列表（list）
+//
+// - enum：按字段配置的可选值与同义词归一（如 男 / M / male → 男），忽略大小写与全角
+// - boolean：识别 是/否、Y/N、true/false、1/0、✓/✗ 等写法，统一为 true / false
+// - list：按常见分隔符拆分为多个值，逐项去空白、去重（可选再按枚举配置归一）
+
+use std::collections::HashMap;
+
+/// 多值列表拼接时的分隔符（清洗结果中使用，存储时拆为数组）
+pub const LIST_JOIN_SEPARATOR: &str = "; ";
+
+/// 多值列表的分隔符
+const LIST_SEPARATORS: &[char] = &[',', '，', ';', '；', '、', '/', '|', '\n'];
+
+/// 表示「是」的写法（比较前已转小写）
+const TRUE_WORDS: &[&str] = &[
+    "是", "对", "有", "真", "已", "y", "yes", "true", "t", "1", "✓", "✔", "√", "☑", "on",
+];
+
+/// 表示「否」的写法（比较前已转小写）
+const FALSE_WORDS: &[&str] = &[
+    "否", "不是", "错", "无", "没有", "假", "未", "n", "no", "false", "f", "0", "✗", "✘", "×", "x", "☐", "off",
+];
+
+/// 比较用的键：全角转半角、去除空白、转小写
+fn choice_key(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            c => c,
+        })
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// 识别布尔值；无法识别时返回 None
+pub fn normalize_boolean(value: &str) -> Option<bool> {
+    let key = choice_key(value);
+    if key.is_empty() {
+        return None;
+    }
+    if TRUE_WORDS.contains(&key.as_str()) {
+        Some(true)
+    } else if FALSE_WORDS.contains(&key.as_str()) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// 按可选值与同义词（同义词 → 可选值）归一枚举值；无法匹配时返回 None
+pub fn normalize_choice(value: &str, values: &[String], synonyms: &HashMap<String, String>) -> Option<String> {
+    let key = choice_key(value);
+    if key.is_empty() {
+        return None;
+    }
+    if let Some(v) = values.iter().find(|v| choice_key(v) == key) {
+        return Some(v.clone());
+    }
+    synonyms
+        .iter()
+        .find(|(synonym, _)| choice_key(synonym) == key)
+        .map(|(_, v)| v.clone())
+}
+
+/// 拆分多值单元格，逐项去除首尾空白，去掉空项与重复项（保持原顺序）
+pub fn split_list(value: &str) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    for item in value.split(LIST_SEPARATORS).map(str::trim).filter(|s| !s.is_empty()) {
+        if !items.iter().any(|existing| existing == item) {
+            items.push(item.to_string());
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_boolean() {
+        for v in ["是", "Y", "yes", "TRUE", "1", "✓", "√", "ｙｅｓ"] {
+            assert_eq!(normalize_boolean(v), Some(true), "{}", v);
+        }
+        for v in ["否", "N", "No", "false", "0", "✗", "×"] {
+            assert_eq!(normalize_boolean(v), Some(false), "{}", v);
+        }
+        assert_eq!(normalize_boolean("也许"), None);
+        assert_eq!(normalize_boolean(" "), None);
+    }
+
+    #[test]
+    fn test_normalize_choice() {
+        let values = vec!["男".to_string(), "女".to_string()];
+        let synonyms: HashMap<String, String> = [("M", "男"), ("male", "男"), ("F", "女"), ("female", "女")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(normalize_choice("男", &values, &synonyms).as_deref(), Some("男"));
+        assert_eq!(normalize_choice(" m ", &values, &synonyms).as_deref(), Some("男"));
+        assert_eq!(normalize_choice("Male", &values, &synonyms).as_deref(), Some("男"));
+        assert_eq!(normalize_choice("FEMALE", &values, &synonyms).as_deref(), Some("女"));
+        assert_eq!(normalize_choice("未知", &values, &synonyms), None);
+    }
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(split_list("红色, 蓝色、绿色 / 红色"), vec!["红色", "蓝色", "绿色"]);
+        assert_eq!(split_list("单个值"), vec!["单个值"]);
+        assert!(split_list(" ; ").is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+// CSV / TSV 读取
+//
+// CSV/TSV 文件视为只有一个 Sheet 的工作簿（Sheet 名为文件名），
+// 自动识别编码（UTF-8 / UTF-8 BOM / GBK 等）和分隔符，导入、Sheet 列表和预览共用。
+
+use encoding_rs::{Encoding, GB18030, GBK, UTF_8};
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// 用于识别编码和分隔符的采样字节数
+const SAMPLE_BYTES: usize = 64 * 1024;
+
+/// 识别分隔符时检查的行数
+const SNIFF_LINES: usize = 20;
+
+/// 候选分隔符
+const DELIMITERS: [u8; 4] = [b',', b'\t', b';', b'|'];
+
+/// 是否为按分隔符存储的文本文件（.csv / .tsv）
+pub fn is_delimited_file(path: &str) -> bool {
+    matches!(extension(path).as_deref(), Some("csv") | Some("tsv"))
+}
+
+fn extension(path: &str) -> Option<String> {
+    Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+}
+
+/// CSV 文件对应的 Sheet 名（与 Excel 打开 CSV 时一致，取文件名）
+pub fn csv_sheet_name(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "Sheet1".to_string())
+}
+
+/// 识别文本编码：BOM > 合法 UTF-8 > 统计猜测（GBK 按其超集 GB18030 解码）
+pub fn detect_encoding(sample: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(sample) {
+        return encoding;
+    }
+
+    match std::str::from_utf8(sample) {
+        Ok(_) => return UTF_8,
+        // 采样截断在多字节字符中间时也视为 UTF-8
+        Err(e) if e.error_len().is_none() => return UTF_8,
+        Err(_) => {}
+    }
+
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(sample, true);
+    let guessed = detector.guess(None, false);
+    if guessed == GBK {
+        GB18030
+    } else {
+        guessed
+    }
+}
+
+/// 统计一行中引号外的分隔符个数
+fn count_delimiter(line: &str, delimiter: u8) -> usize {
+    let mut in_quotes = false;
+    let mut count = 0;
+    for b in line.bytes() {
+        if b == b'"' {
+            in_quotes = !in_quotes;
+        } else if b == delimiter && !in_quotes {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// 识别分隔符：优先选择各行出现次数一致且最多的候选，其次选择各行最少出现次数最多的候选
+pub fn sniff_delimiter(text: &str, default: u8) -> u8 {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .take(SNIFF_LINES)
+        .collect();
+    if lines.is_empty() {
+        return default;
+    }
+
+    let mut best: Option<(bool, usize, u8)> = None;
+    for &delimiter in &DELIMITERS {
+        let counts: Vec<usize> = lines.iter().map(|l| count_delimiter(l, delimiter)).collect();
+        let min = *counts.iter().min().unwrap_or(&0);
+        if min == 0 {
+            continue;
+        }
+        let consistent = counts.iter().all(|&c| c == counts[0]);
+        let candidate = (consistent, min, delimiter);
+        let better = match best {
+            None => true,
+            Some((best_consistent, best_min, _)) => {
+                (consistent, min) > (best_consistent, best_min)
+            }
+        };
+        if better {
+            best = Some(candidate);
+        }
+    }
+
+    best.map(|(_, _, d)| d).unwrap_or(default)
+}
+
+/// 打开 CSV/TSV 文件，返回逐行读取的迭代器（已按识别的编码解码）
+pub fn open_delimited(
+    path: &str,
+) -> Result<impl Iterator<Item = Result<Vec<String>, String>>, String> {
+    let mut file = File::open(path).map_err(|e| format!("无法打开文件 {}: {}", path, e))?;
+
+    let mut sample = Vec::with_capacity(SAMPLE_BYTES);
+    (&mut file)
+        .take(SAMPLE_BYTES as u64)
+        .read_to_end(&mut sample)
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+
+    let encoding = detect_encoding(&sample);
+    let (text, _, _) = encoding.decode(&sample);
+    let default = if extension(path).as_deref() == Some("tsv") { b'\t' } else { b',' };
+    let delimiter = sniff_delimiter(&text, default);
+
+    // 文件带 BOM 时解码器会按 BOM 解码并去掉 BOM
+    let decoder = DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding))
+        .build(file);
+
+    let reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(decoder);
+
+    Ok(reader.into_records().map(|record| {
+        record
+            .map(|r| r.iter().map(|v| v.to_string()).collect())
+            .map_err(|e| format!("CSV 解析失败: {}", e))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(detect_encoding(b"\xEF\xBB\xBFname,phone"), UTF_8);
+        assert_eq!(detect_encoding("姓名,电话".as_bytes()), UTF_8);
+
+        let (gbk, _, _) = GBK.encode("姓名,电话,地址\n张三,13800138000,北京市海淀区中关村大街\n");
+        assert_eq!(detect_encoding(&gbk), GB18030);
+    }
+
+    #[test]
+    fn test_sniff_delimiter() {
+        assert_eq!(sniff_delimiter("a,b,c\n1,2,3\n", b','), b',');
+        assert_eq!(sniff_delimiter("a\tb\tc\n1\t2\t3\n", b','), b'\t');
+        assert_eq!(sniff_delimiter("a;b;c\n1;2,5;3\n", b','), b';');
+        // 引号内的逗号不计入
+        assert_eq!(sniff_delimiter("name|note\n张三|\"a,b,c\"\n", b','), b'|');
+        assert_eq!(sniff_delimiter("single\n", b','), b',');
+    }
+
+    #[test]
+    fn test_open_gbk_csv() {
+        let path = std::env::temp_dir().join(format!("redata_test_{}.csv", uuid::Uuid::new_v4()));
+        let (gbk, _, _) = GBK.encode("姓名,电话\n张三,13800138000\n\"李四, Jr\",13900139000\n");
+        std::fs::write(&path, &gbk).unwrap();
+
+        let rows: Vec<Vec<String>> = open_delimited(path.to_str().unwrap())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], vec!["姓名", "电话"]);
+        assert_eq!(rows[2], vec!["李四, Jr", "13900139000"]);
+    }
+}
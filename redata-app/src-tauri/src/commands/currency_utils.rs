@@ -0,0 +1,73 @@
+// 金额（currency）字段解析
+//
+// 金额与币种分开存储：金额以 JSON 数字存储在字段值中（与 number 一致，筛选时按数值比较），
+// 币种代码（ISO 4217，如 CNY、USD）存储在记录 data 的「{field_id}_unit」键下。
+
+use crate::backend::domain::services::number_utils::normalize_number;
+
+/// 筛选条件中引用币种的组成部分名（「{field_id}.unit」）
+pub const CURRENCY_UNIT_COMPONENT: &str = "unit";
+
+/// 未写明币种时的默认币种
+pub const DEFAULT_CURRENCY: &str = "CNY";
+
+/// 币种写法与对应的币种代码（较长、较明确的写在前面，匹配前已转大写）
+const CURRENCY_TOKENS: &[(&str, &str)] = &[
+    ("HK$", "HKD"),
+    ("US$", "USD"),
+    ("人民币", "CNY"),
+    ("港币", "HKD"),
+    ("港元", "HKD"),
+    ("美元", "USD"),
+    ("美金", "USD"),
+    ("欧元", "EUR"),
+    ("英镑", "GBP"),
+    ("日元", "JPY"),
+    ("RMB", "CNY"),
+    ("CNY", "CNY"),
+    ("HKD", "HKD"),
+    ("USD", "USD"),
+    ("EUR", "EUR"),
+    ("GBP", "GBP"),
+    ("JPY", "JPY"),
+    ("元", "CNY"),
+    ("圆", "CNY"),
+    ("円", "JPY"),
+    ("¥", "CNY"),
+    ("￥", "CNY"),
+    ("$", "USD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+];
+
+/// 存储币种的 data 键
+pub fn currency_unit_key(field_id: &str) -> String {
+    format!("{}_{}", field_id, CURRENCY_UNIT_COMPONENT)
+}
+
+/// 解析金额，返回（规范化的金额, 单元格中写明的币种代码）；金额无法识别时返回 None
+pub fn parse_currency(value: &str) -> Option<(String, Option<&'static str>)> {
+    let mut text = value.trim().to_ascii_uppercase();
+    let mut unit = None;
+    if let Some(&(token, code)) = CURRENCY_TOKENS.iter().find(|(token, _)| text.contains(token)) {
+        unit = Some(code);
+        text = text.replacen(token, "", 1);
+    }
+    normalize_number(&text).map(|amount| (amount, unit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_currency() {
+        assert_eq!(parse_currency("¥1,234.50"), Some(("1234.5".to_string(), Some("CNY"))));
+        assert_eq!(parse_currency("100 usd"), Some(("100".to_string(), Some("USD"))));
+        assert_eq!(parse_currency("HK$ 88"), Some(("88".to_string(), Some("HKD"))));
+        assert_eq!(parse_currency("1.5万港元"), Some(("15000".to_string(), Some("HKD"))));
+        assert_eq!(parse_currency("€-20"), Some(("-20".to_string(), Some("EUR"))));
+        assert_eq!(parse_currency("2000"), Some(("2000".to_string(), None)));
+        assert_eq!(parse_currency("面议"), None);
+    }
+}
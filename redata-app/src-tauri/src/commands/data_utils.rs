@@ -0,0 +1,544 @@
+// 数据清洗与验证公共工具函数
+//
+// 从 processing.rs 提取，供 processing.rs（导入）和 records.rs（记录修正）共用
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::backend::domain::services::number_utils::{normalize_number, number_json_value};
+use crate::backend::infrastructure::persistence::models::field::Model as FieldModel;
+use super::date_utils::{normalize_date, DateOrder};
+use super::id_card_utils::{normalize_id_card, parse_id_card};
+use super::phone_utils::{split_phone_numbers, PhonePolicy, PHONE_JOIN_SEPARATOR};
+use super::address_utils::{address_components_key, parse_address};
+use super::choice_utils::{normalize_boolean, normalize_choice, split_list, LIST_JOIN_SEPARATOR};
+use super::currency_utils::{currency_unit_key, parse_currency, DEFAULT_CURRENCY};
+use super::expression::{parse_expression, EvalError, Expr, Reference};
+
+/// 字段类型相关配置（project_fields.type_options，JSON 存储）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldTypeOptions {
+    /// date：「03/05/2024」这类写法的日/月顺序
+    pub date_order: DateOrder,
+    /// id_card：从号码派生的属性写入同一记录的哪些字段
+    pub id_card_derived: IdCardDerivedFields,
+    /// phone：一个单元格含多个号码时的处理方式（first / all / join）
+    pub phone_policy: PhonePolicy,
+    /// phone（first 策略）：其余号码写入的字段（字段名）
+    pub phone_extra_field: Option<String>,
+    /// enum / list：可选值（为空时不限制）
+    pub enum_values: Vec<String>,
+    /// enum / list：同义词 → 可选值（如 {"M": "男", "male": "男"}）
+    pub enum_synonyms: HashMap<String, String>,
+    /// currency：单元格未写明币种时的默认币种（未配置时为 CNY）
+    pub currency: Option<String>,
+}
+
+/// 身份证派生属性的目标字段（字段名，未配置的属性不写入）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdCardDerivedFields {
+    /// 出生日期（YYYY-MM-DD）
+    pub birth_date: Option<String>,
+    /// 年龄（导入当天的周岁）
+    pub age: Option<String>,
+    /// 性别（男 / 女）
+    pub gender: Option<String>,
+    /// 省份（如 广东省）
+    pub province: Option<String>,
+}
+
+impl FieldTypeOptions {
+    /// 读取字段配置（未配置或格式错误时使用默认值）
+    pub fn from_field(field: &FieldModel) -> Self {
+        field
+            .type_options
+            .as_deref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// 按字段验证规则（正则）校验值
+pub fn validate_value(value: &str, validation_rule: Option<&str>) -> bool {
+    if value.trim().is_empty() {
+        return true; // 空值通过（由 required 字段处理）
+    }
+    if let Some(rule) = validation_rule {
+        if let Ok(re) = Regex::new(rule) {
+            return re.is_match(value);
+        }
+    }
+    true
+}
+
+/// 按字段类型做内置校验（正则之外的规则），失败时返回原因
+///
+/// - id_card: GB 11643 校验码、出生日期、行政区划代码
+/// - enum / list: 值（或每一项）须在字段配置的可选值中
+pub fn validate_field_type(value: &str, field_type: &str, options: &FieldTypeOptions) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Ok(());
+    }
+    match field_type {
+        "id_card" => parse_id_card(value, chrono::Local::now().date_naive()).map(|_| ()),
+        "enum" | "list" if !options.enum_values.is_empty() => {
+            let items: Vec<&str> = if field_type == "list" {
+                value.split(LIST_JOIN_SEPARATOR).collect()
+            } else {
+                vec![value]
+            };
+            match items.iter().find(|item| !options.enum_values.iter().any(|v| v == *item)) {
+                Some(invalid) => Err(format!("「{}」不在可选值中", invalid)),
+                None => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 从身份证号派生出生日期、年龄、性别、省份，写入字段配置中指定的目标字段
+///
+/// 仅填充目标字段为空的情况，不覆盖表格中已有的值；号码无效时不派生
+pub fn derive_id_card_fields(
+    data: &mut serde_json::Map<String, serde_json::Value>,
+    fields: &[FieldModel],
+    options: &HashMap<i32, FieldTypeOptions>,
+) {
+    let today = chrono::Local::now().date_naive();
+    for field in fields.iter().filter(|f| f.field_type == "id_card") {
+        let Some(targets) = options.get(&field.id).map(|o| &o.id_card_derived) else {
+            continue;
+        };
+        let Some(id) = data.get(&field.id.to_string()).and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let Ok(info) = parse_id_card(id, today) else {
+            continue;
+        };
+
+        let derived = [
+            (&targets.birth_date, info.birth_date.format("%Y-%m-%d").to_string()),
+            (&targets.age, info.age_on(today).to_string()),
+            (&targets.gender, info.gender.to_string()),
+            (&targets.province, info.province.to_string()),
+        ];
+        for (target_name, value) in derived {
+            let Some(target) = target_name
+                .as_deref()
+                .and_then(|name| fields.iter().find(|f| f.field_name == name))
+            else {
+                continue;
+            };
+            let target_options = options.get(&target.id).cloned().unwrap_or_default();
+            let value = typed_json_value(value, &target.field_type, &target_options);
+            fill_if_empty(data, target.id.to_string(), value);
+        }
+    }
+}
+
+/// 解析地址字段，将省 / 市 / 区县 / 详细地址写入「{field_id}_address」
+///
+/// 解析结果随地址值重新计算；地址为空时移除旧的解析结果
+pub fn derive_address_components(
+    data: &mut serde_json::Map<String, serde_json::Value>,
+    fields: &[FieldModel],
+) {
+    for field in fields.iter().filter(|f| f.field_type == "address") {
+        let key = field.id.to_string();
+        let components_key = address_components_key(&key);
+        let address = data.get(&key).and_then(|v| v.as_str()).filter(|s| !s.trim().is_empty());
+        match address.map(parse_address) {
+            Some(parsed) => {
+                data.insert(components_key, serde_json::to_value(parsed).unwrap_or_default());
+            }
+            None => {
+                data.remove(&components_key);
+            }
+        }
+    }
+}
+
+/// currency：写入金额的币种（「{field_id}_unit」）
+///
+/// 单元格中写明币种时以其为准；否则仅在缺失时写入字段配置的默认币种
+pub fn derive_currency_unit(
+    data: &mut serde_json::Map<String, serde_json::Value>,
+    field_id: i32,
+    raw: &str,
+    options: &FieldTypeOptions,
+) {
+    let key = currency_unit_key(&field_id.to_string());
+    match parse_currency(raw) {
+        Some((_, Some(unit))) => {
+            data.insert(key, serde_json::Value::String(unit.to_string()));
+        }
+        Some((_, None)) => {
+            let unit = options.currency.as_deref().unwrap_or(DEFAULT_CURRENCY);
+            fill_if_empty(data, key, serde_json::Value::String(unit.to_string()));
+        }
+        None => {}
+    }
+}
+
+/// 编译项目中的计算字段表达式（按字段顺序）；表达式无效时记录警告并跳过该字段
+pub fn compile_computed_fields(fields: &[FieldModel]) -> Vec<(&FieldModel, Expr)> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let src = field.expression.as_deref().filter(|s| !s.trim().is_empty())?;
+            match parse_expression(src) {
+                Ok(expr) => Some((field, expr)),
+                Err(e) => {
+                    tracing::warn!("字段 {} 的计算表达式无效，已跳过: {}", field.field_name, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// 计算字段求值：结果经字段类型清洗、验证后写入 data（覆盖旧值）
+///
+/// 按字段顺序求值，可引用排在前面的计算字段。columns 为导入时的（表头, 当前行）；
+/// 编辑记录时为 None，引用原始列的计算字段保留原值。求值失败时已有的值同样保留。
+/// 返回（字段, 错误信息）
+pub fn apply_computed_fields<'a>(
+    data: &mut serde_json::Map<String, serde_json::Value>,
+    computed: &[(&'a FieldModel, Expr)],
+    fields: &[FieldModel],
+    options: &HashMap<i32, FieldTypeOptions>,
+    columns: Option<(&[String], &[String])>,
+) -> Vec<(&'a FieldModel, String)> {
+    let mut errors = Vec::new();
+    for (field, expr) in computed {
+        let result = {
+            let lookup = |reference: &Reference| match reference {
+                Reference::Field(name) => {
+                    let target = fields.iter().find(|f| f.field_name == *name || f.field_label == *name)?;
+                    Some(data.get(&target.id.to_string()).map(json_value_to_string).unwrap_or_default())
+                }
+                Reference::Column(name) => {
+                    let (headers, row) = columns?;
+                    let idx = headers.iter().position(|h| h.trim() == name.trim());
+                    Some(idx.and_then(|i| row.get(i)).cloned().unwrap_or_default())
+                }
+            };
+            expr.eval(&lookup)
+        };
+        let value = match result {
+            Ok(value) => value,
+            Err(EvalError::Unavailable) => continue,
+            Err(EvalError::Failed(e)) => {
+                errors.push((*field, format!("{} 计算失败: {}", field.field_label, e)));
+                if data.get(&field.id.to_string()).is_some_and(|v| !v.is_null()) {
+                    continue;
+                }
+                String::new()
+            }
+        };
+
+        let field_options = options.get(&field.id).cloned().unwrap_or_default();
+        let cleaned = clean_value_with_options(&value, &field.field_type, &field_options);
+        if cleaned.is_empty() {
+            if field.is_required {
+                errors.push((*field, format!("{} 为必填项", field.field_label)));
+            }
+        } else if !validate_value(&cleaned, field.validation_rule.as_deref()) {
+            errors.push((*field, format!("{} 验证失败", field.field_label)));
+        } else if let Err(reason) = validate_field_type(&cleaned, &field.field_type, &field_options) {
+            errors.push((*field, format!("{} {}", field.field_label, reason)));
+        }
+        data.insert(field.id.to_string(), typed_json_value(cleaned, &field.field_type, &field_options));
+    }
+    errors
+}
+
+/// 单行数据的清洗与校验（导入与记录修正共用，同一个值在两处得到相同的结果）
+///
+/// 逐个字段调用 add 写入清洗后的值，最后调用 finish 写入派生字段
+/// （电话附加号码、身份证属性、计算字段、地址组成部分）
+pub struct RowCleaner<'a> {
+    fields: &'a [FieldModel],
+    options: &'a HashMap<i32, FieldTypeOptions>,
+    data: serde_json::Map<String, serde_json::Value>,
+    extras: Vec<(String, serde_json::Value)>,
+    errors: Vec<(&'a FieldModel, String)>,
+}
+
+impl<'a> RowCleaner<'a> {
+    /// data 为初始数据（导入时为空；修正记录时为提交的数据，未定义的 key 原样保留）
+    pub fn new(
+        fields: &'a [FieldModel],
+        options: &'a HashMap<i32, FieldTypeOptions>,
+        data: serde_json::Map<String, serde_json::Value>,
+    ) -> Self {
+        Self { fields, options, data, extras: Vec::new(), errors: Vec::new() }
+    }
+
+    /// 按字段类型清洗原始值，校验后写入
+    pub fn add(&mut self, field: &'a FieldModel, raw: &str) {
+        let options = &self.options[&field.id];
+        let value = clean_value_with_options(raw, &field.field_type, options);
+
+        if field.is_required && value.trim().is_empty() {
+            self.error(field, format!("{} 为必填项", field.field_label));
+        } else if !validate_value(&value, field.validation_rule.as_deref()) {
+            self.error(field, format!("{} 验证失败", field.field_label));
+        } else if let Err(reason) = validate_field_type(&value, &field.field_type, options) {
+            self.error(field, format!("{} {}", field.field_label, reason));
+        }
+
+        // 多号码单元格：其余号码在 finish 时写入附加字段；金额：记录币种
+        if field.field_type == "phone" {
+            self.extras.extend(phone_extras(raw, options, self.fields));
+        } else if field.field_type == "currency" {
+            derive_currency_unit(&mut self.data, field.id, raw, options);
+        }
+
+        // 存储（使用 field_id 作为 key）
+        self.data.insert(field.id.to_string(), typed_json_value(value, &field.field_type, options));
+    }
+
+    /// 记录字段错误（如必填列缺失）
+    pub fn error(&mut self, field: &'a FieldModel, message: String) {
+        self.errors.push((field, message));
+    }
+
+    /// 写入派生字段，返回清洗后的数据与（字段, 错误信息）
+    ///
+    /// columns 为导入时的（表头, 当前行），供计算字段引用原始列
+    pub fn finish(
+        mut self,
+        computed: &[(&'a FieldModel, Expr)],
+        columns: Option<(&[String], &[String])>,
+    ) -> (serde_json::Map<String, serde_json::Value>, Vec<(&'a FieldModel, String)>) {
+        for (key, value) in std::mem::take(&mut self.extras) {
+            fill_if_empty(&mut self.data, key, value);
+        }
+        derive_id_card_fields(&mut self.data, self.fields, self.options);
+        let computed_errors = apply_computed_fields(&mut self.data, computed, self.fields, self.options, columns);
+        self.errors.extend(computed_errors);
+        derive_address_components(&mut self.data, self.fields);
+        (self.data, self.errors)
+    }
+}
+
+/// JSON 值转为文本（数组按「; 」拼接）
+pub fn json_value_to_string(v: &serde_json::Value) -> String {
+    match v {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::Array(items) => items.iter().map(json_value_to_string).collect::<Vec<_>>().join("; "),
+        _ => v.to_string(),
+    }
+}
+
+/// phone（first 策略）：单元格中第一个号码之后的其他号码，返回（附加字段 key, 值）
+pub fn phone_extras(
+    raw: &str,
+    options: &FieldTypeOptions,
+    fields: &[FieldModel],
+) -> Option<(String, serde_json::Value)> {
+    if options.phone_policy != PhonePolicy::First {
+        return None;
+    }
+    let target_name = options.phone_extra_field.as_deref()?;
+    let target = fields.iter().find(|f| f.field_name == target_name)?;
+    let numbers = split_phone_numbers(raw);
+    if numbers.len() < 2 {
+        return None;
+    }
+    Some((target.id.to_string(), serde_json::Value::String(numbers[1..].join(PHONE_JOIN_SEPARATOR))))
+}
+
+/// 目标字段为空（缺失、null 或空字符串）时写入值
+pub fn fill_if_empty(data: &mut serde_json::Map<String, serde_json::Value>, key: String, value: serde_json::Value) {
+    let is_empty = match data.get(&key) {
+        None | Some(serde_json::Value::Null) => true,
+        Some(serde_json::Value::String(s)) => s.trim().is_empty(),
+        Some(_) => false,
+    };
+    if is_empty {
+        data.insert(key, value);
+    }
+}
+
+/// 按字段类型生成存储用的 JSON 值：
+/// - number / currency 字段存为 JSON 数字（筛选时按数值比较）
+/// - boolean 字段存为 JSON 布尔值
+/// - phone 字段（all 策略）、list 字段存为数组
+/// - 其余为字符串
+pub fn typed_json_value(value: String, field_type: &str, options: &FieldTypeOptions) -> serde_json::Value {
+    match field_type {
+        "number" | "currency" if !value.is_empty() => {
+            if let Some(number) = number_json_value(&value) {
+                return number;
+            }
+        }
+        "phone" if options.phone_policy == PhonePolicy::All && !value.is_empty() => {
+            return serde_json::Value::Array(
+                value
+                    .split(PHONE_JOIN_SEPARATOR)
+                    .map(|n| serde_json::Value::String(n.to_string()))
+                    .collect(),
+            );
+        }
+        "boolean" if value == "true" || value == "false" => {
+            return serde_json::Value::Bool(value == "true");
+        }
+        "list" if !value.is_empty() => {
+            return serde_json::Value::Array(
+                value
+                    .split(LIST_JOIN_SEPARATOR)
+                    .map(|item| serde_json::Value::String(item.to_string()))
+                    .collect(),
+            );
+        }
+        _ => {}
+    }
+    serde_json::Value::String(value)
+}
+
+/// 根据字段类型清理数据值（使用默认的字段配置）
+pub fn clean_value(value: &str, field_type: &str) -> String {
+    clean_value_with_options(value, field_type, &FieldTypeOptions::default())
+}
+
+/// 根据字段类型和字段配置清理数据值
+///
+/// 清理规则：
+/// - 通用：去除首尾空格、换行符、制表符
+/// - phone: 规范化手机号 / 固话 / 分机号，拆分多号码
+/// - email: 去除空格、换行，转小写
+/// - number: 去除货币符号、千分位，换算万/亿等单位
+/// - date: 识别常见写法并统一为 YYYY-MM-DD
+/// - currency: 解析金额（币种由 derive_currency_unit 单独存储）
+/// - enum: 按可选值与同义词归一
+/// - boolean: 统一为 true / false
+/// - list: 拆分为多个值，逐项归一、去重后以「; 」拼接
+/// - text/其他: 压缩连续空白为单个空格
+pub fn clean_value_with_options(value: &str, field_type: &str, options: &FieldTypeOptions) -> String {
+    // 第一步：通用清理 - 去除首尾空白和控制字符
+    let mut cleaned = value
+        .chars()
+        .map(|c| match c {
+            '\r' | '\n' | '\t' => ' ',  // 换行、制表符转为空格
+            c if c.is_control() => ' ', // 其他控制字符转为空格
+            c => c,
+        })
+        .collect::<String>();
+
+    // 根据字段类型进行特定清理
+    match field_type {
+        "phone" => {
+            // 电话：去除国家代码，识别固话区号与分机号；多个号码按字段配置保留第一个或全部
+            let numbers = split_phone_numbers(&cleaned);
+            cleaned = match options.phone_policy {
+                PhonePolicy::First => numbers.into_iter().next().unwrap_or_default(),
+                PhonePolicy::All | PhonePolicy::Join => numbers.join(PHONE_JOIN_SEPARATOR),
+            };
+        }
+        "company" => {
+            // 公司名称：压缩空白；若清理后为纯数字（如 ID、编号），视为无效值返回空
+            let mut result = String::new();
+            let mut prev_space = false;
+            for c in cleaned.chars() {
+                if c.is_whitespace() {
+                    if !prev_space { result.push(' '); prev_space = true; }
+                } else {
+                    result.push(c);
+                    prev_space = false;
+                }
+            }
+            cleaned = result.trim().to_string();
+            // 纯数字（含空格分隔）不是公司名称，清空
+            if !cleaned.is_empty() && cleaned.chars().all(|c| c.is_ascii_digit() || c.is_whitespace()) {
+                cleaned = String::new();
+            }
+        }
+        "email" => {
+            // 邮箱：去除所有空格，转小写
+            cleaned = cleaned.chars().filter(|c| !c.is_whitespace()).collect();
+            cleaned = cleaned.to_lowercase();
+        }
+        "number" => {
+            // 数字：规范化为十进制数字；无法识别时仅保留数字和字母（由验证规则标记错误）
+            cleaned = normalize_number(&cleaned).unwrap_or_else(|| {
+                cleaned.chars().filter(|c| c.is_ascii_alphanumeric()).collect()
+            });
+        }
+        "id_card" => {
+            // 身份证：仅保留数字和字母，末位 X 大写，15 位升级为 18 位
+            cleaned = normalize_id_card(&cleaned);
+        }
+        "currency" => {
+            // 金额：去除币种写法后按数字规范化；无法识别时仅保留数字和字母（由验证规则标记错误）
+            cleaned = match parse_currency(&cleaned) {
+                Some((amount, _)) => amount,
+                None => cleaned.chars().filter(|c| c.is_ascii_alphanumeric()).collect(),
+            };
+        }
+        "enum" => {
+            // 枚举：匹配可选值或同义词时替换为可选值，否则保留原值（由可选值校验标记错误）
+            let value = compress_whitespace(&cleaned);
+            cleaned = normalize_choice(&value, &options.enum_values, &options.enum_synonyms).unwrap_or(value);
+        }
+        "boolean" => {
+            // 布尔：统一为 true / false，无法识别时保留原值（由验证规则标记错误）
+            if let Some(b) = normalize_boolean(&cleaned) {
+                cleaned = b.to_string();
+            }
+        }
+        "list" => {
+            // 多值列表：拆分后逐项压缩空白、按枚举配置归一并去重
+            let mut items: Vec<String> = Vec::new();
+            for item in split_list(value) {
+                let item = compress_whitespace(&item);
+                let item = normalize_choice(&item, &options.enum_values, &options.enum_synonyms).unwrap_or(item);
+                if !items.contains(&item) {
+                    items.push(item);
+                }
+            }
+            cleaned = items.join(LIST_JOIN_SEPARATOR);
+        }
+        "date" => {
+            // 日期：统一为 YYYY-MM-DD；无法识别时去除空格，保留数字、日期分隔符（由验证规则标记错误）
+            cleaned = match normalize_date(&cleaned, options.date_order) {
+                Some(date) => date,
+                None => cleaned
+                    .chars()
+                    .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '/' | '.' | ':'))
+                    .collect(),
+            };
+        }
+        _ => {
+            // 默认文本类型：压缩连续空白为单个空格
+            cleaned = compress_whitespace(&cleaned);
+        }
+    }
+
+    // 最后再次 trim
+    cleaned.trim().to_string()
+}
+
+/// 压缩连续空白为单个空格，并去除首尾空白
+fn compress_whitespace(value: &str) -> String {
+    let mut result = String::new();
+    let mut prev_space = false;
+    for c in value.chars() {
+        if c.is_whitespace() {
+            if !prev_space {
+                result.push(' ');
+                prev_space = true;
+            }
+        } else {
+            result.push(c);
+            prev_space = false;
+        }
+    }
+    result.trim().to_string()
+}
@@ -0,0 +1,145 @@
+// 日期解析与规范化
+//
+// 将常见日期写法统一为 YYYY-MM-DD，使日期字段可以按字符串正确比较（Gt / Between 等筛选）：
+// - 2024-03-05 / 2024/3/5 / 2024.03.05（可带时间部分）
+// - 2024年3月5日 / 24年3月5日
+// - 20240305
+// - 03/05/2024（日/月顺序有歧义时按字段配置）
+// - Excel 日期序列号（如 45356）
+
+use chrono::{Duration, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// 「数字/数字/四位年份」写法的日月顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateOrder {
+    /// 月/日/年（如 03/05/2024 = 3 月 5 日）
+    #[default]
+    MonthFirst,
+    /// 日/月/年（如 03/05/2024 = 5 月 3 日）
+    DayFirst,
+}
+
+/// Excel 序列号的合理范围：10000（1927 年）~ 2958465（9999-12-31）
+const EXCEL_SERIAL_MIN: f64 = 10000.0;
+const EXCEL_SERIAL_MAX: f64 = 2958465.0;
+
+static YMD_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4})\s*[-/.]\s*(\d{1,2})\s*[-/.]\s*(\d{1,2})(?:[T\s].*)?$").unwrap()
+});
+
+static CHINESE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{2}|\d{4})\s*年\s*(\d{1,2})\s*月\s*(\d{1,2})\s*[日号]?(?:\s.*)?$").unwrap()
+});
+
+static DMY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{1,2})\s*[-/.]\s*(\d{1,2})\s*[-/.]\s*(\d{4})(?:\s.*)?$").unwrap()
+});
+
+static COMPACT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\d{4})(\d{2})(\d{2})$").unwrap());
+
+static SERIAL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\d+(\.\d+)?$").unwrap());
+
+fn format_ymd(year: i32, month: u32, day: u32) -> Option<String> {
+    NaiveDate::from_ymd_opt(year, month, day).map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+fn num<T: std::str::FromStr>(caps: &regex::Captures, i: usize) -> Option<T> {
+    caps.get(i)?.as_str().parse().ok()
+}
+
+/// Excel 日期序列号转日期（1900 日期系统，小数部分为时间，忽略）
+pub fn excel_serial_to_date(serial: f64) -> Option<NaiveDate> {
+    if !(EXCEL_SERIAL_MIN..=EXCEL_SERIAL_MAX).contains(&serial) {
+        return None;
+    }
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?;
+    epoch.checked_add_signed(Duration::days(serial.trunc() as i64))
+}
+
+/// 解析日期并规范化为 YYYY-MM-DD；无法识别时返回 None
+pub fn normalize_date(value: &str, order: DateOrder) -> Option<String> {
+    let s = value.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    if let Some(caps) = YMD_RE.captures(s) {
+        return format_ymd(num(&caps, 1)?, num(&caps, 2)?, num(&caps, 3)?);
+    }
+
+    if let Some(caps) = CHINESE_RE.captures(s) {
+        let year: i32 = num(&caps, 1)?;
+        let year = if year < 100 { 2000 + year } else { year };
+        return format_ymd(year, num(&caps, 2)?, num(&caps, 3)?);
+    }
+
+    if let Some(caps) = DMY_RE.captures(s) {
+        let (a, b): (u32, u32) = (num(&caps, 1)?, num(&caps, 2)?);
+        let year: i32 = num(&caps, 3)?;
+        // 有一个数大于 12 时顺序是确定的，否则按字段配置
+        let (month, day) = if a > 12 {
+            (b, a)
+        } else if b > 12 {
+            (a, b)
+        } else {
+            match order {
+                DateOrder::MonthFirst => (a, b),
+                DateOrder::DayFirst => (b, a),
+            }
+        };
+        return format_ymd(year, month, day);
+    }
+
+    if let Some(caps) = COMPACT_RE.captures(s) {
+        return format_ymd(num(&caps, 1)?, num(&caps, 2)?, num(&caps, 3)?);
+    }
+
+    if SERIAL_RE.is_match(s) {
+        let serial: f64 = s.parse().ok()?;
+        return excel_serial_to_date(serial).map(|d| d.format("%Y-%m-%d").to_string());
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_common_formats() {
+        let order = DateOrder::MonthFirst;
+        assert_eq!(normalize_date("2024-03-05", order).as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("2024/3/5", order).as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("2024.03.05 14:30:00", order).as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("2024-03-05T08:00:00", order).as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("2024年3月5日", order).as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("24年03月05号", order).as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("20240305", order).as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("45356", order).as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("45356.5", order).as_deref(), Some("2024-03-05"));
+    }
+
+    #[test]
+    fn test_day_month_order() {
+        assert_eq!(normalize_date("03/05/2024", DateOrder::MonthFirst).as_deref(), Some("2024-03-05"));
+        assert_eq!(normalize_date("03/05/2024", DateOrder::DayFirst).as_deref(), Some("2024-05-03"));
+        // 不歧义时忽略配置
+        assert_eq!(normalize_date("25/12/2024", DateOrder::MonthFirst).as_deref(), Some("2024-12-25"));
+        assert_eq!(normalize_date("12/25/2024", DateOrder::DayFirst).as_deref(), Some("2024-12-25"));
+    }
+
+    #[test]
+    fn test_rejects_invalid_dates() {
+        let order = DateOrder::MonthFirst;
+        assert_eq!(normalize_date("2024-02-30", order), None);
+        assert_eq!(normalize_date("20241301", order), None);
+        assert_eq!(normalize_date("2024", order), None);
+        assert_eq!(normalize_date("下周一", order), None);
+        assert_eq!(normalize_date("", order), None);
+    }
+}
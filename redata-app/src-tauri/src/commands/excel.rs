@@ -1,9 +1,15 @@
 // Excel 解析 Tauri Commands
 //
-// 使用 calamine 读取 .xlsx/.xls 文件
+// 使用 calamine 读取 .xlsx/.xls 文件，CSV/TSV 按单 Sheet 工作簿处理
 
-use calamine::{open_workbook_auto, Reader, Data};
-use serde::Serialize;
+use calamine::{open_workbook_auto, Reader};
+use serde::{Deserialize, Serialize};
+
+use super::csv_reader::{csv_sheet_name, is_delimited_file, open_delimited};
+use super::data_utils::{clean_value_with_options, validate_field_type, validate_value, FieldTypeOptions};
+use super::field_transforms::{Transform, TransformPipeline};
+use super::fields::get_validation_rule;
+use super::processing::data_to_string;
 
 // ============ 响应结构 ============
 
@@ -19,28 +25,84 @@ pub struct ExcelPreviewResponse {
     pub sheets: Vec<SheetInfoResponse>,
     pub rows: Vec<Vec<String>>,
     pub sheet_name: String,
+    /// 字段转换试运行结果（请求了 transform 时返回）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transform_preview: Option<Vec<TransformPreviewRow>>,
+}
+
+/// 字段转换试运行请求：对预览行执行转换管道和字段类型清洗，不写入数据库
+#[derive(Debug, Deserialize)]
+pub struct TransformPreviewRequest {
+    pub transforms: Vec<Transform>,
+    /// 字段对应的列（从 0 计数）；未映射时以空值执行转换
+    pub column_index: Option<usize>,
+    /// 表头行（从 0 计数，默认 0），拼接列按表头名称解析
+    pub header_row: Option<usize>,
+    /// 字段类型（默认 text）
+    pub field_type: Option<String>,
+    pub type_options: Option<FieldTypeOptions>,
+}
+
+/// 单行的转换试运行结果
+#[derive(Debug, Serialize)]
+pub struct TransformPreviewRow {
+    pub row_index: usize,
+    /// 单元格原值
+    pub input: String,
+    /// 转换管道输出
+    pub output: String,
+    /// 字段类型清洗后的值（即实际导入的值）
+    pub cleaned: String,
+    /// 验证失败原因
+    pub error: Option<String>,
 }
 
 // ============ 辅助函数 ============
 
-fn data_to_string(data: &Data) -> String {
-    match data {
-        Data::Int(i) => i.to_string(),
-        Data::Float(f) => {
-            if *f == (*f as i64) as f64 {
-                (*f as i64).to_string()
+/// 字段转换试运行：对表头之后的预览行执行转换管道、字段类型清洗与验证
+fn preview_transform(
+    req: &TransformPreviewRequest,
+    rows: &[Vec<String>],
+) -> Result<Vec<TransformPreviewRow>, String> {
+    let header_row = req.header_row.unwrap_or(0);
+    let headers = rows.get(header_row).cloned().unwrap_or_default();
+    let pipeline = TransformPipeline::compile(&req.transforms, &headers)?;
+    let field_type = req.field_type.as_deref().unwrap_or("text");
+    let options = req.type_options.clone().unwrap_or_default();
+    let rule = get_validation_rule(field_type);
+
+    Ok(rows
+        .iter()
+        .enumerate()
+        .skip(header_row + 1)
+        .map(|(row_index, row)| {
+            let input = req.column_index.and_then(|i| row.get(i)).cloned().unwrap_or_default();
+            let output = pipeline.apply(&input, row);
+            let cleaned = clean_value_with_options(&output, field_type, &options);
+            let error = if !validate_value(&cleaned, rule.as_deref()) {
+                Some("验证失败".to_string())
             } else {
-                f.to_string()
-            }
-        }
-        Data::String(s) => s.clone(),
-        Data::Bool(b) => b.to_string(),
-        Data::DateTime(dt) => dt.to_string(),
-        Data::DateTimeIso(s) => s.clone(),
-        Data::DurationIso(s) => s.clone(),
-        Data::Error(e) => format!("#ERR:{:?}", e),
-        Data::Empty => String::new(),
+                validate_field_type(&cleaned, field_type, &options).err()
+            };
+            TransformPreviewRow { row_index, input, output, cleaned, error }
+        })
+        .collect())
+}
+
+/// 统计 CSV/TSV 文件的行数和最大列数
+fn delimited_sheet_info(path: &str) -> Result<SheetInfoResponse, String> {
+    let mut row_count = 0u32;
+    let mut column_count = 0u32;
+    for record in open_delimited(path)? {
+        let row = record?;
+        row_count += 1;
+        column_count = column_count.max(row.len() as u32);
     }
+    Ok(SheetInfoResponse {
+        name: csv_sheet_name(path),
+        row_count,
+        column_count,
+    })
 }
 
 // ============ Tauri Commands ============
@@ -52,6 +114,10 @@ pub async fn get_excel_sheets(
 ) -> Result<Vec<SheetInfoResponse>, String> {
     let path = file_path.clone();
     tokio::task::spawn_blocking(move || {
+        if is_delimited_file(&path) {
+            return Ok(vec![delimited_sheet_info(&path)?]);
+        }
+
         let mut workbook = open_workbook_auto(&path)
             .map_err(|e| format!("无法打开文件 {}: {}", path, e))?;
 
@@ -82,16 +148,37 @@ pub async fn get_excel_sheets(
 }
 
 /// 预览 Excel 文件内容
+///
+/// 传入 transform 时，同时返回字段转换管道在预览行上的试运行结果
 #[tauri::command]
 pub async fn preview_excel(
     file_path: String,
     sheet_name: Option<String>,
     max_rows: Option<u32>,
+    transform: Option<TransformPreviewRequest>,
 ) -> Result<ExcelPreviewResponse, String> {
     let path = file_path.clone();
     let max = max_rows.unwrap_or(10) as usize;
 
     tokio::task::spawn_blocking(move || {
+        if is_delimited_file(&path) {
+            let mut rows: Vec<Vec<String>> = open_delimited(&path)?
+                .take(max)
+                .collect::<Result<_, _>>()?;
+            // 短行补齐，与工作簿预览的矩形区域一致
+            let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+            for row in &mut rows {
+                row.resize(width, String::new());
+            }
+            let transform_preview = transform.as_ref().map(|t| preview_transform(t, &rows)).transpose()?;
+            return Ok(ExcelPreviewResponse {
+                sheets: vec![delimited_sheet_info(&path)?],
+                rows,
+                sheet_name: csv_sheet_name(&path),
+                transform_preview,
+            });
+        }
+
         let mut workbook = open_workbook_auto(&path)
             .map_err(|e| format!("无法打开文件 {}: {}", path, e))?;
 
@@ -125,13 +212,43 @@ pub async fn preview_excel(
             .take(max)
             .map(|row| row.iter().map(data_to_string).collect())
             .collect();
+        let transform_preview = transform.as_ref().map(|t| preview_transform(t, &rows)).transpose()?;
 
         Ok::<_, String>(ExcelPreviewResponse {
             sheets,
             rows,
             sheet_name: target_sheet,
+            transform_preview,
         })
     })
     .await
     .map_err(|e| format!("任务执行失败: {}", e))?
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+
+    #[tokio::test]
+    async fn test_preview_formats_dates_like_import() {
+        let path = std::env::temp_dir().join(format!("redata_test_{}.xlsx", uuid::Uuid::new_v4()));
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_string(0, 0, "姓名").unwrap();
+        sheet.write_string(0, 1, "入职日期").unwrap();
+        sheet.write_string(0, 2, "打卡时间").unwrap();
+        sheet.write_string(1, 0, "张三").unwrap();
+        let date = ExcelDateTime::from_ymd(2024, 3, 15).unwrap();
+        sheet.write_datetime_with_format(1, 1, &date, &Format::new().set_num_format("yyyy/m/d")).unwrap();
+        let time = ExcelDateTime::from_ymd(2024, 3, 15).unwrap().and_hms(8, 30, 0).unwrap();
+        sheet.write_datetime_with_format(1, 2, &time, &Format::new().set_num_format("yyyy-mm-dd hh:mm")).unwrap();
+        workbook.save(&path).unwrap();
+
+        let preview = preview_excel(path.to_string_lossy().to_string(), None, None, None).await.unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        // 预览与导入使用同一套单元格格式化，日期不会显示为 Excel 序列号
+        assert_eq!(preview.rows[1], vec!["张三", "2024-03-15", "2024-03-15 08:30:00"]);
+    }
+}
@@ -0,0 +1,510 @@
+// 计算字段表达式（project_fields.expression）
+//
+// 计算字段不对应表格中的列，而是由其他字段或原始列按表达式计算得出，结果与普通字段一样存储在 data 中。
+//
+// 语法：
+// - 引用：{字段名}（字段名或字段标签）、[列名]（原始表格的表头名称，仅导入时可用）
+// - 字面量："文本"、123、1.5
+// - 运算：& 拼接文本；+ - * / 数值运算（任一操作数为空时结果为空）；
+//         = != > < >= <= 比较（两侧均为数字时按数值比较），结果为 true / false
+// - 函数：concat(a, b, ...)、left(s, n)、right(s, n)、mid(s, start, n)（start 从 1 开始）、
+//         upper(s)、lower(s)、trim(s)、len(s)、replace(s, from, to)、
+//         regex(s, pattern[, group])（提取匹配，未匹配时为空）、
+//         if(cond, a[, b])、coalesce(a, b, ...)（第一个非空值）、isempty(s)
+//
+// 示例：{姓} & {名}、regex({电话}, "^(0\d{2,3})-", 1)、if({金额} > 10000, "大额", "普通")
+
+use regex::Regex;
+
+/// 表达式中的引用
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reference {
+    /// {字段名}
+    Field(String),
+    /// [列名]
+    Column(String),
+}
+
+/// 比较运算符
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+}
+
+/// 表达式语法树
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(String),
+    Ref(Reference),
+    Concat(Box<Expr>, Box<Expr>),
+    Arith(char, Box<Expr>, Box<Expr>),
+    Negate(Box<Expr>),
+    Compare(CompareOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+/// 求值失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    /// 引用的值不可用（如编辑记录时引用了原始列），应保留原值
+    Unavailable,
+    /// 表达式执行出错（如对非数字做数值运算）
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Text(String),
+    Ident(String),
+    Field(String),
+    Column(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+/// 词法分析
+fn tokenize(src: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    // 读取到指定结束符为止的内容
+    let read_until = |start: usize, end: char| -> Result<(String, usize), String> {
+        let mut j = start;
+        let mut s = String::new();
+        while j < chars.len() && chars[j] != end {
+            // 字符串中支持 \" 转义
+            if end == '"' && chars[j] == '\\' && j + 1 < chars.len() && chars[j + 1] == '"' {
+                j += 1;
+            }
+            s.push(chars[j]);
+            j += 1;
+        }
+        if j >= chars.len() {
+            return Err(format!("缺少结束符 {}", end));
+        }
+        Ok((s, j + 1))
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '"' | '“' | '”' => {
+                let (s, next) = read_until(i + 1, if c == '"' { '"' } else { '”' })?;
+                tokens.push(Token::Text(s));
+                i = next;
+            }
+            '{' => {
+                let (s, next) = read_until(i + 1, '}')?;
+                tokens.push(Token::Field(s.trim().to_string()));
+                i = next;
+            }
+            '[' => {
+                let (s, next) = read_until(i + 1, ']')?;
+                tokens.push(Token::Column(s.trim().to_string()));
+                i = next;
+            }
+            '(' | '（' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' | '）' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' | '，' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            c if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                tokens.push(Token::Number(chars[start..i].iter().collect()));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect::<String>().to_lowercase()));
+            }
+            _ => {
+                let two: String = chars[i..chars.len().min(i + 2)].iter().collect();
+                let op = ["!=", "<>", ">=", "<=", "=="]
+                    .into_iter()
+                    .find(|op| two == *op)
+                    .or_else(|| ["&", "+", "-", "*", "/", "=", ">", "<"].into_iter().find(|op| op.starts_with(c)))
+                    .ok_or_else(|| format!("无法识别的字符 '{}'", c))?;
+                i += op.chars().count();
+                tokens.push(Token::Op(op));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// 递归下降语法分析
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_op(&self, ops: &[&str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, String> {
+        let left = self.parse_concat()?;
+        if let Some(op) = self.peek_op(&["=", "==", "!=", "<>", ">", "<", ">=", "<="]) {
+            self.pos += 1;
+            let right = self.parse_concat()?;
+            let op = match op {
+                "=" | "==" => CompareOp::Eq,
+                "!=" | "<>" => CompareOp::Ne,
+                ">" => CompareOp::Gt,
+                "<" => CompareOp::Lt,
+                ">=" => CompareOp::Ge,
+                _ => CompareOp::Le,
+            };
+            return Ok(Expr::Compare(op, Box::new(left), Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn parse_concat(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_additive()?;
+        while self.peek_op(&["&"]).is_some() {
+            self.pos += 1;
+            let right = self.parse_additive()?;
+            left = Expr::Concat(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_term()?;
+        while let Some(op) = self.peek_op(&["+", "-"]) {
+            self.pos += 1;
+            let right = self.parse_term()?;
+            left = Expr::Arith(op.chars().next().unwrap_or('+'), Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_unary()?;
+        while let Some(op) = self.peek_op(&["*", "/"]) {
+            self.pos += 1;
+            let right = self.parse_unary()?;
+            left = Expr::Arith(op.chars().next().unwrap_or('*'), Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        if self.peek_op(&["-"]).is_some() {
+            self.pos += 1;
+            return Ok(Expr::Negate(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(n)),
+            Some(Token::Text(s)) => Ok(Expr::Literal(s)),
+            Some(Token::Field(name)) => Ok(Expr::Ref(Reference::Field(name))),
+            Some(Token::Column(name)) => Ok(Expr::Ref(Reference::Column(name))),
+            Some(Token::LParen) => {
+                let expr = self.parse_comparison()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => Err("缺少右括号".to_string()),
+                }
+            }
+            Some(Token::Ident(name)) => {
+                if self.next() != Some(Token::LParen) {
+                    return Err(format!("{} 后应为 (", name));
+                }
+                let mut args = Vec::new();
+                if self.peek() == Some(&Token::RParen) {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        args.push(self.parse_comparison()?);
+                        match self.next() {
+                            Some(Token::Comma) => continue,
+                            Some(Token::RParen) => break,
+                            _ => return Err(format!("函数 {} 的参数列表缺少右括号", name)),
+                        }
+                    }
+                }
+                check_arity(&name, args.len())?;
+                Ok(Expr::Call(name, args))
+            }
+            Some(token) => Err(format!("意外的符号 {:?}", token)),
+            None => Err("表达式不完整".to_string()),
+        }
+    }
+}
+
+/// 校验函数名与参数个数
+fn check_arity(name: &str, count: usize) -> Result<(), String> {
+    let (min, max) = match name {
+        "concat" | "coalesce" => (1, usize::MAX),
+        "upper" | "lower" | "trim" | "len" | "isempty" => (1, 1),
+        "left" | "right" => (2, 2),
+        "mid" | "replace" => (3, 3),
+        "regex" | "if" => (2, 3),
+        _ => return Err(format!("未知函数 {}", name)),
+    };
+    if count < min || count > max {
+        return Err(format!("函数 {} 的参数个数不正确", name));
+    }
+    Ok(())
+}
+
+/// 解析表达式
+pub fn parse_expression(src: &str) -> Result<Expr, String> {
+    let mut parser = Parser { tokens: tokenize(src)?, pos: 0 };
+    let expr = parser.parse_comparison()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!("表达式在第 {} 个符号处有多余内容", parser.pos + 1));
+    }
+    Ok(expr)
+}
+
+/// 数值格式化：整数不带小数点
+fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        let s = format!("{:.10}", n);
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+fn to_number(s: &str) -> Result<f64, EvalError> {
+    s.trim()
+        .parse::<f64>()
+        .map_err(|_| EvalError::Failed(format!("「{}」不是数字", s)))
+}
+
+fn to_count(s: &str) -> Result<usize, EvalError> {
+    let n = to_number(s)?;
+    Ok(if n < 0.0 { 0 } else { n as usize })
+}
+
+fn is_truthy(s: &str) -> bool {
+    !matches!(s.trim(), "" | "false" | "0")
+}
+
+impl Expr {
+    /// 求值；lookup 返回引用的值，None 表示不可用
+    pub fn eval(&self, lookup: &dyn Fn(&Reference) -> Option<String>) -> Result<String, EvalError> {
+        match self {
+            Expr::Literal(s) => Ok(s.clone()),
+            Expr::Ref(r) => lookup(r).ok_or(EvalError::Unavailable),
+            Expr::Concat(a, b) => Ok(format!("{}{}", a.eval(lookup)?, b.eval(lookup)?)),
+            Expr::Negate(a) => {
+                let v = a.eval(lookup)?;
+                if v.trim().is_empty() {
+                    return Ok(String::new());
+                }
+                Ok(format_number(-to_number(&v)?))
+            }
+            Expr::Arith(op, a, b) => {
+                let (a, b) = (a.eval(lookup)?, b.eval(lookup)?);
+                if a.trim().is_empty() || b.trim().is_empty() {
+                    return Ok(String::new());
+                }
+                let (x, y) = (to_number(&a)?, to_number(&b)?);
+                let result = match op {
+                    '+' => x + y,
+                    '-' => x - y,
+                    '*' => x * y,
+                    _ if y == 0.0 => return Err(EvalError::Failed("除数为 0".to_string())),
+                    _ => x / y,
+                };
+                Ok(format_number(result))
+            }
+            Expr::Compare(op, a, b) => {
+                let (a, b) = (a.eval(lookup)?, b.eval(lookup)?);
+                let ordering = match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+                    (Ok(x), Ok(y)) => x.partial_cmp(&y),
+                    _ => Some(a.cmp(&b)),
+                };
+                let result = ordering.is_some_and(|o| match op {
+                    CompareOp::Eq => o.is_eq(),
+                    CompareOp::Ne => o.is_ne(),
+                    CompareOp::Gt => o.is_gt(),
+                    CompareOp::Lt => o.is_lt(),
+                    CompareOp::Ge => o.is_ge(),
+                    CompareOp::Le => o.is_le(),
+                });
+                Ok(result.to_string())
+            }
+            Expr::Call(name, args) => call_function(name, args, lookup),
+        }
+    }
+
+    /// 表达式中引用的所有字段 / 列
+    pub fn references(&self) -> Vec<&Reference> {
+        match self {
+            Expr::Literal(_) => Vec::new(),
+            Expr::Ref(r) => vec![r],
+            Expr::Negate(a) => a.references(),
+            Expr::Concat(a, b) | Expr::Arith(_, a, b) | Expr::Compare(_, a, b) => {
+                let mut refs = a.references();
+                refs.extend(b.references());
+                refs
+            }
+            Expr::Call(_, args) => args.iter().flat_map(Expr::references).collect(),
+        }
+    }
+}
+
+fn call_function(
+    name: &str,
+    args: &[Expr],
+    lookup: &dyn Fn(&Reference) -> Option<String>,
+) -> Result<String, EvalError> {
+    // if / coalesce 按需求值，其余函数先对全部参数求值
+    match name {
+        "if" => {
+            let cond = args[0].eval(lookup)?;
+            return if is_truthy(&cond) {
+                args[1].eval(lookup)
+            } else {
+                args.get(2).map_or(Ok(String::new()), |e| e.eval(lookup))
+            };
+        }
+        "coalesce" => {
+            for arg in args {
+                let v = arg.eval(lookup)?;
+                if !v.trim().is_empty() {
+                    return Ok(v);
+                }
+            }
+            return Ok(String::new());
+        }
+        _ => {}
+    }
+
+    let values = args.iter().map(|a| a.eval(lookup)).collect::<Result<Vec<_>, _>>()?;
+    let s = values[0].as_str();
+    Ok(match name {
+        "concat" => values.concat(),
+        "upper" => s.to_uppercase(),
+        "lower" => s.to_lowercase(),
+        "trim" => s.trim().to_string(),
+        "len" => s.chars().count().to_string(),
+        "isempty" => s.trim().is_empty().to_string(),
+        "left" => s.chars().take(to_count(&values[1])?).collect(),
+        "right" => {
+            let n = to_count(&values[1])?;
+            let count = s.chars().count();
+            s.chars().skip(count.saturating_sub(n)).collect()
+        }
+        "mid" => {
+            let start = to_count(&values[1])?.max(1) - 1;
+            s.chars().skip(start).take(to_count(&values[2])?).collect()
+        }
+        "replace" => s.replace(values[1].as_str(), &values[2]),
+        "regex" => {
+            let re = Regex::new(&values[1])
+                .map_err(|e| EvalError::Failed(format!("正则表达式无效: {}", e)))?;
+            let group = match values.get(2) {
+                Some(g) => to_count(g)?,
+                None => 0,
+            };
+            re.captures(s)
+                .and_then(|c| c.get(group))
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default()
+        }
+        _ => return Err(EvalError::Failed(format!("未知函数 {}", name))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn eval(src: &str, values: &[(&str, &str)]) -> Result<String, EvalError> {
+        let values: HashMap<String, String> = values.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let lookup = |r: &Reference| match r {
+            Reference::Field(name) => values.get(name).cloned().or(Some(String::new())),
+            Reference::Column(name) => values.get(&format!("[{}]", name)).cloned(),
+        };
+        parse_expression(src).unwrap().eval(&lookup)
+    }
+
+    #[test]
+    fn test_text_expressions() {
+        assert_eq!(eval("{姓} & {名}", &[("姓", "张"), ("名", "三")]).unwrap(), "张三");
+        assert_eq!(eval(r#"concat({姓}, " ", [名])"#, &[("姓", "张"), ("[名]", "三")]).unwrap(), "张 三");
+        assert_eq!(
+            eval(r#"regex({电话}, "^(0\d{2,3})-", 1)"#, &[("电话", "0755-88886666")]).unwrap(),
+            "0755"
+        );
+        assert_eq!(eval(r#"left({号码}, 3) & "-" & right({号码}, 4)"#, &[("号码", "13800138000")]).unwrap(), "138-8000");
+        assert_eq!(eval(r#"mid({id}, 7, 8)"#, &[("id", "11010519491231002X")]).unwrap(), "19491231");
+        assert_eq!(eval(r#"upper(trim(" ab "))"#, &[]).unwrap(), "AB");
+        assert_eq!(eval(r#"coalesce({手机}, {座机}, "无")"#, &[("座机", "010-62345678")]).unwrap(), "010-62345678");
+    }
+
+    #[test]
+    fn test_arithmetic_and_conditions() {
+        assert_eq!(eval("{单价} * {数量}", &[("单价", "12.5"), ("数量", "4")]).unwrap(), "50");
+        assert_eq!(eval("({a} + 1) / 4", &[("a", "1")]).unwrap(), "0.5");
+        assert_eq!(eval("-{a} - 2", &[("a", "3")]).unwrap(), "-5");
+        assert_eq!(eval("{单价} * {数量}", &[("单价", "12.5")]).unwrap(), "");
+        assert_eq!(eval(r#"if({金额} > 10000, "大额", "普通")"#, &[("金额", "9000")]).unwrap(), "普通");
+        assert_eq!(eval(r#"if({金额} >= 10000, "大额")"#, &[("金额", "10000")]).unwrap(), "大额");
+        assert_eq!(eval(r#"if(isempty({备注}), "无", {备注})"#, &[]).unwrap(), "无");
+        assert!(matches!(eval("{a} * 2", &[("a", "abc")]), Err(EvalError::Failed(_))));
+        assert_eq!(eval("[原始列] & {a}", &[]), Err(EvalError::Unavailable));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_expression("{a} &").is_err());
+        assert!(parse_expression("unknown({a})").is_err());
+        assert!(parse_expression("left({a})").is_err());
+        assert!(parse_expression("({a} + 1").is_err());
+        assert!(parse_expression("{a").is_err());
+        assert!(parse_expression("{a} {b}").is_err());
+        let refs = parse_expression("{姓} & [名]").unwrap();
+        assert_eq!(
+            refs.references(),
+            vec![&Reference::Field("姓".to_string()), &Reference::Column("名".to_string())]
+        );
+    }
+}
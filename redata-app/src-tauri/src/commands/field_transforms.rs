@@ -0,0 +1,258 @@
+// 字段转换管道（project_fields.transforms，JSON 数组）
+//
+// 导入时在字段类型清洗（clean_value）之前，按顺序对单元格原值执行声明式转换，
+// 用于处理各来源表格的特殊写法，无需修改代码：
+//   [{"type": "trim"},
+//    {"type": "regex_replace", "pattern": "^No\\.", "replacement": ""},
+//    {"type": "lookup", "table": {"M": "男", "F": "女"}},
+//    {"type": "default", "value": "未知"}]
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::backend::infrastructure::persistence::models::field::Model as FieldModel;
+
+/// 单个转换步骤
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Transform {
+    /// 去除首尾空白
+    Trim,
+    /// 正则替换（replacement 支持 $1 等分组引用）
+    RegexReplace {
+        pattern: String,
+        #[serde(default)]
+        replacement: String,
+    },
+    /// 大小写转换
+    Case { to: CaseMode },
+    /// 查找表映射：命中时替换；未命中时使用 fallback（未配置则保留原值）
+    Lookup {
+        table: HashMap<String, String>,
+        #[serde(default)]
+        fallback: Option<String>,
+    },
+    /// 值为空时使用默认值
+    Default { value: String },
+    /// 按字符截取：start 从 0 开始，length 缺省时截到末尾
+    Substring {
+        start: usize,
+        #[serde(default)]
+        length: Option<usize>,
+    },
+    /// 拼接其他列：当前值在前，随后依次为指定列的值（空值跳过）
+    Concat {
+        columns: Vec<ColumnRef>,
+        #[serde(default)]
+        separator: String,
+    },
+}
+
+/// 大小写转换方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseMode {
+    Upper,
+    Lower,
+    /// 每个英文单词首字母大写
+    Title,
+}
+
+/// 列引用：列下标（从 0 计数）或表头名称
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ColumnRef {
+    Index(usize),
+    Header(String),
+}
+
+/// 预编译的转换步骤（正则已编译、列引用已解析为下标）
+#[derive(Debug, Clone)]
+enum Step {
+    Trim,
+    RegexReplace(Regex, String),
+    Case(CaseMode),
+    Lookup(HashMap<String, String>, Option<String>),
+    Default(String),
+    Substring(usize, Option<usize>),
+    Concat(Vec<usize>, String),
+}
+
+/// 字段的转换管道（按 Sheet 编译一次，逐行执行）
+#[derive(Debug, Clone, Default)]
+pub struct TransformPipeline {
+    steps: Vec<Step>,
+}
+
+/// 解析字段的转换配置（未配置时为空列表）
+pub fn parse_transforms(raw: Option<&str>) -> Result<Vec<Transform>, String> {
+    match raw.filter(|s| !s.trim().is_empty()) {
+        Some(s) => serde_json::from_str(s).map_err(|e| format!("字段转换配置格式错误: {}", e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 读取并编译字段的转换管道；配置无效时记录警告并跳过转换
+pub fn field_pipeline(field: &FieldModel, headers: &[String]) -> TransformPipeline {
+    parse_transforms(field.transforms.as_deref())
+        .and_then(|transforms| TransformPipeline::compile(&transforms, headers))
+        .unwrap_or_else(|e| {
+            tracing::warn!("字段 {} 的转换配置无效，已跳过: {}", field.field_name, e);
+            TransformPipeline::default()
+        })
+}
+
+/// 英文单词首字母大写，其余小写
+fn title_case(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut word_start = true;
+    for c in value.chars() {
+        if c.is_alphanumeric() {
+            if word_start {
+                result.extend(c.to_uppercase());
+            } else {
+                result.extend(c.to_lowercase());
+            }
+            word_start = false;
+        } else {
+            result.push(c);
+            word_start = true;
+        }
+    }
+    result
+}
+
+impl TransformPipeline {
+    /// 编译转换配置：正则无效时返回错误；表头中找不到的拼接列记录警告后忽略（headers 为空时仅校验）
+    pub fn compile(transforms: &[Transform], headers: &[String]) -> Result<Self, String> {
+        let mut steps = Vec::with_capacity(transforms.len());
+        for transform in transforms {
+            steps.push(match transform {
+                Transform::Trim => Step::Trim,
+                Transform::RegexReplace { pattern, replacement } => {
+                    let re = Regex::new(pattern)
+                        .map_err(|e| format!("正则表达式「{}」无效: {}", pattern, e))?;
+                    Step::RegexReplace(re, replacement.clone())
+                }
+                Transform::Case { to } => Step::Case(*to),
+                Transform::Lookup { table, fallback } => Step::Lookup(table.clone(), fallback.clone()),
+                Transform::Default { value } => Step::Default(value.clone()),
+                Transform::Substring { start, length } => Step::Substring(*start, *length),
+                Transform::Concat { columns, separator } => {
+                    let indices = columns
+                        .iter()
+                        .filter_map(|column| match column {
+                            ColumnRef::Index(i) => Some(*i),
+                            ColumnRef::Header(name) => {
+                                let idx = headers.iter().position(|h| h.trim() == name.trim());
+                                if idx.is_none() && !headers.is_empty() {
+                                    tracing::warn!("拼接列「{}」不在表头中，已忽略", name);
+                                }
+                                idx
+                            }
+                        })
+                        .collect();
+                    Step::Concat(indices, separator.clone())
+                }
+            });
+        }
+        Ok(Self { steps })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// 对单元格值依次执行转换（row 为当前整行，供拼接使用）
+    pub fn apply(&self, value: &str, row: &[String]) -> String {
+        let mut value = value.to_string();
+        for step in &self.steps {
+            value = match step {
+                Step::Trim => value.trim().to_string(),
+                Step::RegexReplace(re, replacement) => re.replace_all(&value, replacement.as_str()).into_owned(),
+                Step::Case(CaseMode::Upper) => value.to_uppercase(),
+                Step::Case(CaseMode::Lower) => value.to_lowercase(),
+                Step::Case(CaseMode::Title) => title_case(&value),
+                Step::Lookup(table, fallback) => match table.get(value.trim()) {
+                    Some(mapped) => mapped.clone(),
+                    None => fallback.clone().unwrap_or(value),
+                },
+                Step::Default(default) if value.trim().is_empty() => default.clone(),
+                Step::Default(_) => value,
+                Step::Substring(start, length) => {
+                    let chars = value.chars().skip(*start);
+                    match length {
+                        Some(len) => chars.take(*len).collect(),
+                        None => chars.collect(),
+                    }
+                }
+                Step::Concat(indices, separator) => {
+                    let parts: Vec<&str> = std::iter::once(value.as_str())
+                        .chain(indices.iter().filter_map(|&i| row.get(i).map(String::as_str)))
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                    parts.join(separator)
+                }
+            };
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipeline(json: &str, headers: &[&str]) -> TransformPipeline {
+        let transforms = parse_transforms(Some(json)).unwrap();
+        let headers: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
+        TransformPipeline::compile(&transforms, &headers).unwrap()
+    }
+
+    #[test]
+    fn test_text_transforms() {
+        let p = pipeline(
+            r#"[{"type": "trim"}, {"type": "regex_replace", "pattern": "^No\\.\\s*", "replacement": ""},
+                {"type": "case", "to": "upper"}, {"type": "substring", "start": 0, "length": 6}]"#,
+            &[],
+        );
+        assert_eq!(p.apply("  No. ab12cd34 ", &[]), "AB12CD");
+
+        let p = pipeline(r#"[{"type": "case", "to": "title"}]"#, &[]);
+        assert_eq!(p.apply("JOHN o'neil-smith", &[]), "John O'Neil-Smith");
+    }
+
+    #[test]
+    fn test_lookup_and_default() {
+        let p = pipeline(
+            r#"[{"type": "lookup", "table": {"M": "男", "F": "女"}, "fallback": ""},
+                {"type": "default", "value": "未知"}]"#,
+            &[],
+        );
+        assert_eq!(p.apply(" M ", &[]), "男");
+        assert_eq!(p.apply("F", &[]), "女");
+        assert_eq!(p.apply("X", &[]), "未知");
+        assert_eq!(p.apply("", &[]), "未知");
+    }
+
+    #[test]
+    fn test_concat_columns() {
+        let p = pipeline(r#"[{"type": "concat", "columns": ["名", 2], "separator": " "}]"#, &["姓", "名", "后缀"]);
+        let row: Vec<String> = ["张", "三", ""].iter().map(|s| s.to_string()).collect();
+        assert_eq!(p.apply(&row[0], &row), "张 三");
+
+        // 表头中不存在的列被忽略
+        let p = pipeline(r#"[{"type": "concat", "columns": ["不存在"]}]"#, &["姓"]);
+        assert_eq!(p.apply("张", &row), "张");
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(parse_transforms(Some(r#"[{"type": "unknown"}]"#)).is_err());
+        let transforms = parse_transforms(Some(r#"[{"type": "regex_replace", "pattern": "("}]"#)).unwrap();
+        assert!(TransformPipeline::compile(&transforms, &[]).is_err());
+        assert!(parse_transforms(None).unwrap().is_empty());
+    }
+}
@@ -11,6 +11,10 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::backend::infrastructure::persistence::models::{field, ProjectField};
+use super::data_utils::FieldTypeOptions;
+use super::field_transforms::{parse_transforms, TransformPipeline};
+use super::expression::{parse_expression, Reference};
+use super::phone_utils::{PHONE_JOIN_SEPARATOR, PHONE_PATTERN};
 
 // ============ 请求/响应结构 ============
 
@@ -28,6 +32,10 @@ pub struct CreateFieldRequest {
     pub additional_requirement: Option<String>,
     pub validation_rule: Option<String>,
     pub extraction_hint: Option<String>,
+    pub type_options: Option<String>,
+    pub transforms: Option<String>,
+    pub expression: Option<String>,
+    pub ai_extract_source: Option<String>,
 }
 
 /// 字段更新请求
@@ -41,6 +49,10 @@ pub struct UpdateFieldRequest {
     pub additional_requirement: Option<String>,
     pub validation_rule: Option<String>,
     pub extraction_hint: Option<String>,
+    pub type_options: Option<String>,
+    pub transforms: Option<String>,
+    pub expression: Option<String>,
+    pub ai_extract_source: Option<String>,
     pub display_order: Option<i32>,
 }
 
@@ -58,6 +70,10 @@ pub struct FieldResponse {
     pub additional_requirement: Option<String>,
     pub validation_rule: Option<String>,
     pub extraction_hint: Option<String>,
+    pub type_options: Option<String>,
+    pub transforms: Option<String>,
+    pub expression: Option<String>,
+    pub ai_extract_source: Option<String>,
     pub display_order: i32,
     pub created_at: String,
     pub deleted_at: Option<String>,
@@ -95,6 +111,10 @@ impl From<field::Model> for FieldResponse {
             additional_requirement: model.additional_requirement,
             validation_rule: model.validation_rule,
             extraction_hint: model.extraction_hint,
+            type_options: model.type_options,
+            transforms: model.transforms,
+            expression: model.expression,
+            ai_extract_source: model.ai_extract_source,
             display_order: model.display_order,
             created_at: model.created_at.to_rfc3339(),
             deleted_at: model.deleted_at.map(|dt| dt.to_rfc3339()),
@@ -104,18 +124,80 @@ impl From<field::Model> for FieldResponse {
 
 // ============ 辅助函数 ============
 
+/// 电话字段的验证规则：一个或多个（「; 」分隔）规范化后的手机号 / 固话 / 服务号码
+pub(crate) fn phone_validation_rule() -> String {
+    format!("^{p}({sep}{p})*$", p = PHONE_PATTERN, sep = PHONE_JOIN_SEPARATOR)
+}
+
 /// 根据字段类型生成本地验证规则（正则表达式）
-fn get_validation_rule(field_type: &str) -> Option<String> {
+pub(crate) fn get_validation_rule(field_type: &str) -> Option<String> {
     match field_type {
-        "phone" => Some(r"^1[3-9]\d{9}$".to_string()),
+        "phone" => Some(phone_validation_rule()),
         "email" => Some(r"^[\w\.-]+@[\w\.-]+\.\w+$".to_string()),
         "url" => Some(r"^https?://".to_string()),
         "date" => Some(r"^\d{4}[-/]\d{1,2}[-/]\d{1,2}$".to_string()),
-        "number" => Some(r"^-?\d+(\.\d+)?$".to_string()),
+        "number" | "currency" => Some(r"^-?\d+(\.\d+)?$".to_string()),
+        "boolean" => Some(r"^(true|false)$".to_string()),
         _ => None, // text 类型无需验证规则
     }
 }
 
+/// 校验字段类型配置（JSON），空字符串转为 None
+fn normalize_type_options(type_options: Option<String>) -> Result<Option<String>, String> {
+    let Some(raw) = type_options.filter(|s| !s.trim().is_empty()) else {
+        return Ok(None);
+    };
+    let options: FieldTypeOptions = serde_json::from_str(&raw)
+        .map_err(|e| format!("字段类型配置格式错误: {}", e))?;
+    serde_json::to_string(&options)
+        .map(Some)
+        .map_err(|e| format!("JSON 序列化错误: {}", e))
+}
+
+/// 校验字段转换配置（JSON 数组，含正则编译），空字符串或空数组转为 None
+fn normalize_transforms(transforms: Option<String>) -> Result<Option<String>, String> {
+    let transforms = parse_transforms(transforms.as_deref())?;
+    if transforms.is_empty() {
+        return Ok(None);
+    }
+    TransformPipeline::compile(&transforms, &[])?;
+    serde_json::to_string(&transforms)
+        .map(Some)
+        .map_err(|e| format!("JSON 序列化错误: {}", e))
+}
+
+/// 校验计算字段表达式（语法及引用的字段是否存在），空字符串转为 None
+async fn normalize_expression(
+    db: &DatabaseConnection,
+    project_id: i32,
+    field_name: &str,
+    expression: Option<String>,
+) -> Result<Option<String>, String> {
+    let Some(src) = expression.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let expr = parse_expression(&src).map_err(|e| format!("计算表达式无效: {}", e))?;
+
+    let fields = ProjectField::find()
+        .filter(field::Column::ProjectId.eq(project_id))
+        .filter(field::Column::IsDeleted.eq(false))
+        .all(db)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+    for reference in expr.references() {
+        let Reference::Field(name) = reference else {
+            continue;
+        };
+        let target = fields.iter().find(|f| f.field_name == *name || f.field_label == *name);
+        match target {
+            None => return Err(format!("计算表达式引用的字段「{}」不存在", name)),
+            Some(f) if f.field_name == field_name => return Err("计算表达式不能引用字段自身".to_string()),
+            Some(_) => {}
+        }
+    }
+    Ok(Some(src))
+}
+
 /// 根据字段标签生成英文字段名（常见中文词汇映射）
 fn generate_field_name(label: &str) -> String {
     use std::collections::HashMap;
@@ -221,6 +303,10 @@ fn generate_extraction_hint(field_label: &str, field_type: &str, additional: Opt
         "url" => "网址链接",
         "date" => "日期",
         "number" => "数字",
+        "enum" => "枚举选项",
+        "boolean" => "是/否",
+        "currency" => "金额",
+        "list" => "多值列表",
         _ => "文本",
     };
 
@@ -286,6 +372,10 @@ pub async fn create_field(
     additional_requirement: Option<String>,
     validation_rule: Option<String>,
     extraction_hint: Option<String>,
+    type_options: Option<String>,
+    transforms: Option<String>,
+    expression: Option<String>,
+    ai_extract_source: Option<String>,
 ) -> Result<FieldResponse, String> {
     tracing::debug!("create_field called: project_id={}, field_name={}, field_label={}, field_type={}",
         project_id, field_name, field_label, field_type);
@@ -311,6 +401,10 @@ pub async fn create_field(
     let additional_requirement = additional_requirement.and_then(|s| if s.trim().is_empty() { None } else { Some(s.trim().to_string()) });
     let validation_rule = validation_rule.and_then(|s| if s.trim().is_empty() { None } else { Some(s) });
     let extraction_hint = extraction_hint.and_then(|s| if s.trim().is_empty() { None } else { Some(s.trim().to_string()) });
+    let type_options = normalize_type_options(type_options)?;
+    let transforms = normalize_transforms(transforms)?;
+    let expression = normalize_expression(db.inner().as_ref(), project_id, &field_name, expression).await?;
+    let ai_extract_source = ai_extract_source.and_then(|s| if s.trim().is_empty() { None } else { Some(s.trim().to_string()) });
 
     tracing::info!("create_field: processed values - additional_requirement={:?}, validation_rule={:?}, extraction_hint={:?}",
         additional_requirement, validation_rule, extraction_hint);
@@ -340,6 +434,10 @@ pub async fn create_field(
         active.additional_requirement = Set(additional_requirement);
         active.validation_rule = Set(validation_rule);
         active.extraction_hint = Set(extraction_hint);
+        active.type_options = Set(type_options);
+        active.transforms = Set(transforms);
+        active.expression = Set(expression);
+        active.ai_extract_source = Set(ai_extract_source);
 
         tracing::info!("create_field: updating restored field...");
         let result = active
@@ -403,6 +501,10 @@ pub async fn create_field(
         additional_requirement: Set(additional_requirement.clone()),
         validation_rule: Set(validation_rule.clone()),
         extraction_hint: Set(extraction_hint.clone()),
+        type_options: Set(type_options.clone()),
+        transforms: Set(transforms.clone()),
+        expression: Set(expression.clone()),
+        ai_extract_source: Set(ai_extract_source.clone()),
         display_order: Set(max_order + 1),
         created_at: Set(now),
         deleted_at: Set(None),
@@ -435,6 +537,10 @@ pub async fn update_field(
     additional_requirement: Option<String>,
     validation_rule: Option<String>,
     extraction_hint: Option<String>,
+    type_options: Option<String>,
+    transforms: Option<String>,
+    expression: Option<String>,
+    ai_extract_source: Option<String>,
     display_order: Option<i32>,
 ) -> Result<FieldResponse, String> {
     tracing::info!("update_field called: id={}, field_name={:?}, field_label={:?}, field_type={:?}",
@@ -459,6 +565,11 @@ pub async fn update_field(
             format!("字段 {} 不存在", id)
         })?;
 
+    let expression = match expression {
+        Some(src) => Some(normalize_expression(db.inner().as_ref(), field.project_id, &field.field_name, Some(src)).await?),
+        None => None,
+    };
+
     tracing::info!("update_field: found field, converting to active model...");
     let mut active: field::ActiveModel = field.into();
 
@@ -502,6 +613,19 @@ pub async fn update_field(
     active.additional_requirement = Set(processed_additional);
     active.validation_rule = Set(processed_validation);
     active.extraction_hint = Set(processed_extraction);
+    // 类型配置、转换、表达式、AI 提取来源只在传入时覆盖（空字符串表示清除），未传入时保留原配置
+    if type_options.is_some() {
+        active.type_options = Set(normalize_type_options(type_options)?);
+    }
+    if transforms.is_some() {
+        active.transforms = Set(normalize_transforms(transforms)?);
+    }
+    if let Some(expression) = expression {
+        active.expression = Set(expression);
+    }
+    if let Some(source) = ai_extract_source {
+        active.ai_extract_source = Set(Some(source.trim().to_string()).filter(|s| !s.is_empty()));
+    }
 
     if let Some(order) = display_order {
         active.display_order = Set(order);
@@ -0,0 +1,235 @@
+// 多级表头识别
+//
+// 报表常见两级表头（如「联系人」横跨「姓名 / 电话」两列）或合并单元格表头。
+// 先按合并区域把左上角的值填充到整个区域，再识别连续的多行表头，
+// 逐列自上而下拼接为「联系人/姓名」形式的组合列名，供 AI 提示与规则映射使用。
+
+use super::sheet_reader::MergedRange;
+
+/// 在前几行中查找表头起始行
+const HEADER_SCAN_ROWS: usize = 5;
+
+/// 表头最多占用的行数
+const MAX_HEADER_DEPTH: usize = 3;
+
+/// 表头可能出现的区域（合并单元格只在该区域内填充，数据行保持原样）
+pub const HEADER_AREA_ROWS: usize = HEADER_SCAN_ROWS + MAX_HEADER_DEPTH;
+
+/// 组合列名中上下级之间的分隔符
+pub const HEADER_SEPARATOR: &str = "/";
+
+/// 识别出的多行表头
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderBlock {
+    /// 表头起始行
+    pub start: usize,
+    /// 表头行数（>= 2）
+    pub depth: usize,
+    /// 逐列拼接后的组合列名
+    pub headers: Vec<String>,
+}
+
+impl HeaderBlock {
+    /// 表头最后一行（其后为数据行）
+    pub fn last_row(&self) -> usize {
+        self.start + self.depth - 1
+    }
+}
+
+/// 将合并区域左上角的值填充到区域内的其他单元格（只处理前 max_rows 行）
+pub fn fill_merged_cells(rows: &mut [Vec<String>], ranges: &[MergedRange], max_rows: usize) {
+    let limit = rows.len().min(max_rows);
+    for range in ranges {
+        if range.first_row >= limit {
+            continue;
+        }
+        let Some(value) = rows[range.first_row].get(range.first_col).cloned() else {
+            continue;
+        };
+        if value.trim().is_empty() {
+            continue;
+        }
+        for row in rows.iter_mut().take(limit.min(range.last_row + 1)).skip(range.first_row) {
+            for col in range.first_col..=range.last_col {
+                if let Some(cell) = row.get_mut(col) {
+                    if cell.trim().is_empty() {
+                        *cell = value.clone();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 是否像数据值（数字、日期、金额等），表头单元格不应是这类值
+fn looks_like_value(cell: &str) -> bool {
+    let s = cell.trim();
+    if s.is_empty() {
+        return false;
+    }
+    let digits = s.chars().filter(|c| c.is_ascii_digit()).count();
+    digits * 2 >= s.chars().count() || s.contains('@')
+}
+
+fn non_empty(row: &[String]) -> Vec<(usize, &str)> {
+    row.iter()
+        .enumerate()
+        .map(|(i, c)| (i, c.trim()))
+        .filter(|(_, c)| !c.is_empty())
+        .collect()
+}
+
+/// 是否像表头行：至少两个不同的非空单元格，且都不像数据值
+fn is_label_row(row: &[String]) -> bool {
+    let cells = non_empty(row);
+    let mut distinct: Vec<&str> = cells.iter().map(|(_, c)| *c).collect();
+    distinct.sort_unstable();
+    distinct.dedup();
+    distinct.len() >= 2 && cells.iter().all(|(_, c)| !looks_like_value(c))
+}
+
+/// 下一行是否为上一行的子表头：
+/// - 上一行某个值横跨相邻多列（合并单元格填充后），而下一行在这些列中给出了不同的名称；或
+/// - 上一行有空单元格紧跟在非空单元格之后（未填充的合并区域），而下一行在此处有名称
+fn is_sub_header(upper: &[String], lower: &[String]) -> bool {
+    let lower_cells = non_empty(lower);
+    if lower_cells.is_empty() || lower_cells.iter().any(|(_, c)| looks_like_value(c)) {
+        return false;
+    }
+
+    let cell = |row: &[String], i: usize| row.get(i).map(|s| s.trim().to_string()).unwrap_or_default();
+    let width = upper.len().max(lower.len());
+    (1..width).any(|i| {
+        let (up_prev, up) = (cell(upper, i - 1), cell(upper, i));
+        let (low_prev, low) = (cell(lower, i - 1), cell(lower, i));
+        if up_prev.is_empty() || low_prev.is_empty() || low.is_empty() {
+            return false;
+        }
+        let spans = up == up_prev && low != low_prev;
+        let continues = up.is_empty();
+        spans || continues
+    })
+}
+
+/// 逐列自上而下拼接表头（跳过空值和与上一级相同的值）
+///
+/// 上级表头的空单元格若下方有名称，视为左侧分组（未填充的合并区域）的延续
+pub fn composite_headers(rows: &[Vec<String>], start: usize, depth: usize) -> Vec<String> {
+    let header_rows = &rows[start..start + depth];
+    let width = header_rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let cell = |r: usize, col: usize| header_rows[r].get(col).map(|s| s.trim()).unwrap_or("");
+
+    let mut grid: Vec<Vec<&str>> = Vec::with_capacity(depth);
+    for r in 0..depth {
+        let mut line: Vec<&str> = Vec::with_capacity(width);
+        for col in 0..width {
+            let mut value = cell(r, col);
+            let has_child = (r + 1..depth).any(|below| !cell(below, col).is_empty());
+            if value.is_empty() && col > 0 && r + 1 < depth && has_child {
+                value = line[col - 1];
+            }
+            line.push(value);
+        }
+        grid.push(line);
+    }
+
+    (0..width)
+        .map(|col| {
+            let mut parts: Vec<&str> = Vec::new();
+            for line in &grid {
+                let value = line[col];
+                if !value.is_empty() && parts.last() != Some(&value) {
+                    parts.push(value);
+                }
+            }
+            parts.join(HEADER_SEPARATOR)
+        })
+        .collect()
+}
+
+/// 识别多行表头；只有一行表头（或无法识别）时返回 None
+pub fn detect_header_block(rows: &[Vec<String>]) -> Option<HeaderBlock> {
+    let start = (0..rows.len().min(HEADER_SCAN_ROWS)).find(|&i| is_label_row(&rows[i]))?;
+
+    let mut depth = 1;
+    while depth < MAX_HEADER_DEPTH
+        && start + depth + 1 < rows.len()
+        && is_sub_header(&rows[start + depth - 1], &rows[start + depth])
+    {
+        depth += 1;
+    }
+
+    if depth < 2 {
+        return None;
+    }
+    Some(HeaderBlock {
+        start,
+        depth,
+        headers: composite_headers(rows, start, depth),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(data: &[&[&str]]) -> Vec<Vec<String>> {
+        data.iter()
+            .map(|r| r.iter().map(|s| s.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_merged_two_level_header() {
+        let mut data = rows(&[
+            &["2024 年客户名单", "", "", ""],
+            &["序号", "联系人", "", "地址"],
+            &["", "姓名", "电话", ""],
+            &["1", "张三", "13800138000", "北京市"],
+        ]);
+        let ranges = vec![
+            MergedRange { first_row: 0, first_col: 0, last_row: 0, last_col: 3 },
+            MergedRange { first_row: 1, first_col: 0, last_row: 2, last_col: 0 },
+            MergedRange { first_row: 1, first_col: 1, last_row: 1, last_col: 2 },
+            MergedRange { first_row: 1, first_col: 3, last_row: 2, last_col: 3 },
+        ];
+        fill_merged_cells(&mut data, &ranges, 5);
+        assert_eq!(data[0], vec!["2024 年客户名单"; 4]);
+        assert_eq!(data[2], vec!["序号", "姓名", "电话", "地址"]);
+
+        let block = detect_header_block(&data).unwrap();
+        assert_eq!(block.start, 1);
+        assert_eq!(block.last_row(), 2);
+        assert_eq!(block.headers, vec!["序号", "联系人/姓名", "联系人/电话", "地址"]);
+    }
+
+    #[test]
+    fn test_unmerged_two_level_header() {
+        let data = rows(&[
+            &["序号", "联系人", "", "备注"],
+            &["", "姓名", "电话", ""],
+            &["1", "张三", "13800138000", "老客户"],
+        ]);
+
+        let block = detect_header_block(&data).unwrap();
+        assert_eq!(block.start, 0);
+        assert_eq!(block.depth, 2);
+        assert_eq!(block.headers, vec!["序号", "联系人/姓名", "联系人/电话", "备注"]);
+    }
+
+    #[test]
+    fn test_single_header_row() {
+        let data = rows(&[
+            &["姓名", "城市", "备注"],
+            &["张三", "北京", ""],
+            &["李四", "上海", "老客户"],
+        ]);
+        assert_eq!(detect_header_block(&data), None);
+
+        let data = rows(&[
+            &["姓名", "电话"],
+            &["张三", "13800138000"],
+        ]);
+        assert_eq!(detect_header_block(&data), None);
+    }
+}
@@ -0,0 +1,136 @@
+// 居民身份证号码校验（GB 11643）
+//
+// - 18 位：校验码（ISO 7064 MOD 11-2）、出生日期、行政区划代码
+// - 15 位（1999 年前签发）：补全世纪并计算校验码，升级为 18 位
+// - 从号码中解析出生日期、年龄、性别、省份，可写入同一记录的其他字段
+
+use chrono::{Datelike, NaiveDate};
+
+use super::regions::province_by_code;
+
+/// 前 17 位的加权因子
+const WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+
+/// 加权和模 11 的余数对应的校验码
+const CHECK_CODES: [char; 11] = ['1', '0', 'X', '9', '8', '7', '6', '5', '4', '3', '2'];
+
+/// 从身份证号码解析出的信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdCardInfo {
+    pub birth_date: NaiveDate,
+    /// 男 / 女（第 17 位奇数为男）
+    pub gender: &'static str,
+    pub province: &'static str,
+}
+
+impl IdCardInfo {
+    /// 截至指定日期的周岁
+    pub fn age_on(&self, today: NaiveDate) -> i32 {
+        let mut age = today.year() - self.birth_date.year();
+        if (today.month(), today.day()) < (self.birth_date.month(), self.birth_date.day()) {
+            age -= 1;
+        }
+        age
+    }
+}
+
+/// 计算前 17 位的校验码
+fn check_code(first17: &str) -> Option<char> {
+    let mut sum = 0;
+    for (c, w) in first17.chars().zip(WEIGHTS) {
+        sum += c.to_digit(10)? * w;
+    }
+    Some(CHECK_CODES[(sum % 11) as usize])
+}
+
+/// 规范化身份证号码：去除空白与分隔符、末位 x 转大写，15 位升级为 18 位。
+/// 不做校验，无法识别的号码原样返回（去除空白后）
+pub fn normalize_id_card(value: &str) -> String {
+    let id: String = value
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_uppercase();
+
+    if id.len() == 15 && id.chars().all(|c| c.is_ascii_digit()) {
+        let first17 = format!("{}19{}", &id[..6], &id[6..]);
+        if let Some(code) = check_code(&first17) {
+            return format!("{}{}", first17, code);
+        }
+    }
+    id
+}
+
+/// 校验 18 位身份证号码，通过时返回解析出的信息，否则返回错误原因
+pub fn parse_id_card(id: &str, today: NaiveDate) -> Result<IdCardInfo, String> {
+    if id.len() != 18
+        || !id[..17].chars().all(|c| c.is_ascii_digit())
+        || !id[17..].chars().all(|c| c.is_ascii_digit() || c == 'X')
+    {
+        return Err("应为 18 位号码（末位可为 X）".to_string());
+    }
+
+    let province = province_by_code(&id[..2]).ok_or_else(|| "行政区划代码无效".to_string())?;
+    if &id[2..4] == "00" {
+        return Err("行政区划代码无效".to_string());
+    }
+
+    let birth_date = NaiveDate::parse_from_str(&id[6..14], "%Y%m%d")
+        .ok()
+        .filter(|d| d.year() >= 1800 && *d <= today)
+        .ok_or_else(|| "出生日期无效".to_string())?;
+
+    if check_code(&id[..17]) != id.chars().nth(17) {
+        return Err("校验码错误".to_string());
+    }
+
+    let gender_digit = id[16..17].parse::<u32>().unwrap_or(0);
+    Ok(IdCardInfo {
+        birth_date,
+        gender: if gender_digit % 2 == 1 { "男" } else { "女" },
+        province,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()
+    }
+
+    #[test]
+    fn test_valid_id_card() {
+        let info = parse_id_card("11010519491231002X", today()).unwrap();
+        assert_eq!(info.birth_date, NaiveDate::from_ymd_opt(1949, 12, 31).unwrap());
+        assert_eq!(info.gender, "女");
+        assert_eq!(info.province, "北京市");
+        assert_eq!(info.age_on(today()), 74);
+
+        let info = parse_id_card("440524188001010014", today()).unwrap();
+        assert_eq!(info.gender, "男");
+        assert_eq!(info.province, "广东省");
+    }
+
+    #[test]
+    fn test_invalid_id_card() {
+        assert_eq!(parse_id_card("110105194912310021", today()).unwrap_err(), "校验码错误");
+        assert_eq!(parse_id_card("990105194912310021", today()).unwrap_err(), "行政区划代码无效");
+        assert_eq!(parse_id_card("110105194913310021", today()).unwrap_err(), "出生日期无效");
+        assert!(parse_id_card("1101051949123100", today()).is_err());
+    }
+
+    #[test]
+    fn test_normalize_upgrades_15_digits() {
+        assert_eq!(normalize_id_card("11010519491231002x"), "11010519491231002X");
+        assert_eq!(normalize_id_card(" 110105 1949 1231 002X "), "11010519491231002X");
+
+        let upgraded = normalize_id_card("130503670401001");
+        assert_eq!(upgraded.len(), 18);
+        assert_eq!(&upgraded[..17], "13050319670401001");
+        let info = parse_id_card(&upgraded, today()).unwrap();
+        assert_eq!(info.gender, "男");
+        assert_eq!(info.province, "河北省");
+    }
+}
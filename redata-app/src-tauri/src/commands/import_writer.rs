@@ -0,0 +1,718 @@
+// 导入记录写入器
+//
+// 导入时逐行 INSERT + 逐行 json_extract 去重查询在大文件上非常慢。
+// 这里把待写入的记录缓冲起来按批在事务中插入（与 insert_records_batch 相同的多值 INSERT），
+// 并在任务开始时一次性加载项目的去重键到内存索引，之后的去重判断不再查询数据库。
+//
+// 任务的行级检查点与记录在同一事务中保存，中断后可从检查点继续（见 TaskCheckpoint）。
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, Statement, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::backend::infrastructure::persistence::models::ProcessingTask;
+use super::ai_service::ColumnMappingResponse;
+use super::record_history::{append_history, RecordVersion, OP_IMPORT_UPDATE};
+
+/// 每批写入的记录数（每条 10 个参数，远低于 SQLite 参数上限）
+const BATCH_SIZE: usize = 500;
+
+/// 待写入的记录
+pub struct PendingRecord {
+    pub data: serde_json::Value,
+    pub raw_data: Option<String>,
+    pub source_file: Option<String>,
+    pub source_sheet: Option<String>,
+    pub row_number: Option<i32>,
+    pub status: String,
+    pub error_message: Option<String>,
+}
+
+/// 索引中的记录位置：已落库（记录 ID）或仍在缓冲区（缓冲区下标）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Slot {
+    Stored(i32),
+    Pending(usize),
+}
+
+// ============ 去重索引 ============
+
+/// 内存去重索引
+///
+/// 与原 SQL 语义一致：空的去重字段不参与比较，仅比较行中非空的去重字段，
+/// 命中多条时取 ID 最小的记录。每个去重字段单独建立「值 → 记录」索引，
+/// 查询时从最短的候选列表中按 ID 顺序逐条比对其余字段，
+/// 索引大小与去重字段数成线性关系。
+pub struct DedupIndex {
+    field_ids: Vec<i32>,
+    /// 每个去重字段：值 → 含该值的记录（ID 升序，缓冲区中的记录排在最后）
+    postings: Vec<HashMap<String, Vec<Slot>>>,
+    /// 已索引记录的去重字段值
+    values: HashMap<Slot, Vec<String>>,
+}
+
+impl DedupIndex {
+    pub fn new(field_ids: Vec<i32>) -> Self {
+        let postings = vec![HashMap::new(); field_ids.len()];
+        Self { field_ids, postings, values: HashMap::new() }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.field_ids.is_empty()
+    }
+
+    /// 从项目已导入成功的记录加载索引
+    pub async fn load(&mut self, db: &DatabaseConnection, project_id: i32) -> Result<(), String> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                db.get_database_backend(),
+                "SELECT id, data FROM project_records WHERE project_id = ? AND status = 'success' ORDER BY id",
+                [project_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("数据库错误: {}", e))?;
+
+        for row in rows {
+            let id: i32 = row.try_get_by("id").map_err(|e| format!("数据库错误: {}", e))?;
+            let data_str: String = row.try_get_by("data").unwrap_or_default();
+            let data: serde_json::Map<String, serde_json::Value> =
+                serde_json::from_str(&data_str).unwrap_or_default();
+            let values = self.values_of(&data);
+            self.add(Slot::Stored(id), &values);
+        }
+
+        Ok(())
+    }
+
+    /// 提取去重字段的值（缺失或空值为空字符串）
+    fn values_of(&self, data: &serde_json::Map<String, serde_json::Value>) -> Vec<String> {
+        self.field_ids
+            .iter()
+            .map(|id| match data.get(&id.to_string()) {
+                Some(serde_json::Value::String(s)) => s.trim().to_string(),
+                Some(serde_json::Value::Null) | None => String::new(),
+                Some(v) => v.to_string(),
+            })
+            .collect()
+    }
+
+    /// 非空去重字段的下标
+    fn non_empty(values: &[String]) -> impl Iterator<Item = usize> + '_ {
+        (0..values.len()).filter(|&i| !values[i].is_empty())
+    }
+
+    fn add(&mut self, slot: Slot, values: &[String]) {
+        for i in Self::non_empty(values) {
+            let slots = self.postings[i].entry(values[i].clone()).or_default();
+            // 保持 ID 升序（缓冲区中的记录总是排在已落库记录之后）
+            let pos = match slot {
+                Slot::Stored(id) => slots
+                    .iter()
+                    .position(|s| match s {
+                        Slot::Stored(other) => *other > id,
+                        Slot::Pending(_) => true,
+                    })
+                    .unwrap_or(slots.len()),
+                Slot::Pending(_) => slots.len(),
+            };
+            slots.insert(pos, slot);
+        }
+        self.values.insert(slot, values.to_vec());
+    }
+
+    fn remove(&mut self, slot: Slot, values: &[String]) {
+        for i in Self::non_empty(values) {
+            if let Some(slots) = self.postings[i].get_mut(&values[i]) {
+                slots.retain(|s| *s != slot);
+                if slots.is_empty() {
+                    self.postings[i].remove(&values[i]);
+                }
+            }
+        }
+        self.values.remove(&slot);
+    }
+
+    fn replace(&mut self, old: Slot, new: Slot, values: &[String]) {
+        for i in Self::non_empty(values) {
+            if let Some(slots) = self.postings[i].get_mut(&values[i]) {
+                for s in slots.iter_mut().filter(|s| **s == old) {
+                    *s = new;
+                }
+            }
+        }
+        if let Some(v) = self.values.remove(&old) {
+            self.values.insert(new, v);
+        }
+    }
+
+    /// 查找非空去重字段全部相同的首条记录；行中去重字段全部为空时不参与去重
+    fn find(&self, values: &[String]) -> Option<Slot> {
+        let indices: Vec<usize> = Self::non_empty(values).collect();
+        let mut candidates = Vec::with_capacity(indices.len());
+        for &i in &indices {
+            // 任一字段没有相同值的记录即不重复
+            candidates.push(self.postings[i].get(&values[i])?);
+        }
+        let shortest = candidates.into_iter().min_by_key(|slots| slots.len())?;
+        shortest.iter().copied().find(|slot| {
+            self.values
+                .get(slot)
+                .is_some_and(|stored| indices.iter().all(|&i| stored[i] == values[i]))
+        })
+    }
+}
+
+// ============ 检查点 ============
+
+/// 导入计数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportCounts {
+    pub rows: i32,
+    pub success: i32,
+    pub errors: i32,
+    pub updated: i32,
+    pub merged: i32,
+}
+
+/// 行级检查点（processing_tasks.checkpoint）
+///
+/// 与行的处理结果在同一事务中保存：last_row 及之前的行都已落库，之后的行都未落库，
+/// 因此从 last_row 的下一行继续既不会重复写入，也不会遗漏。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskCheckpoint {
+    /// 当前文件在任务文件列表中的下标
+    pub file_index: usize,
+    pub file_name: String,
+    pub sheet_name: String,
+    /// 最后一个已落库的数据行（行下标从 0 计数）；None 表示列映射已确定、尚未导入任何行
+    pub last_row: Option<usize>,
+    /// 当前 Sheet 使用的列映射及其来源（继续时直接复用，不再调用 AI）
+    pub mapping: ColumnMappingResponse,
+    pub mapping_source: String,
+    /// 当前 Sheet 截至 last_row 的计数
+    pub sheet_counts: ImportCounts,
+}
+
+// ============ 写入器 ============
+
+pub struct ImportWriter {
+    task_id: String,
+    project_id: i32,
+    batch_number: Option<String>,
+    buffer: Vec<PendingRecord>,
+    /// 缓冲区中成功记录的去重字段值（落库后用于把索引中的缓冲区下标替换为记录 ID）
+    pending_values: Vec<(usize, Vec<String>)>,
+    dedup: DedupIndex,
+    /// 当前检查点；有未保存的变化时随下一次写入一起落库
+    checkpoint: Option<TaskCheckpoint>,
+    checkpoint_dirty: bool,
+}
+
+impl ImportWriter {
+    /// 创建写入器：一次性读取任务信息并加载去重索引
+    pub async fn new(
+        db: &DatabaseConnection,
+        task_id: &str,
+        dedup_field_ids: Vec<i32>,
+    ) -> Result<Self, String> {
+        let task = ProcessingTask::find_by_id(task_id)
+            .one(db)
+            .await
+            .map_err(|e| format!("数据库错误: {}", e))?
+            .ok_or_else(|| format!("任务 {} 不存在", task_id))?;
+
+        let mut dedup = DedupIndex::new(dedup_field_ids);
+        dedup.load(db, task.project_id).await?;
+
+        Ok(Self {
+            task_id: task_id.to_string(),
+            project_id: task.project_id,
+            batch_number: task.batch_number,
+            buffer: Vec::with_capacity(BATCH_SIZE),
+            pending_values: Vec::new(),
+            dedup,
+            checkpoint: None,
+            checkpoint_dirty: false,
+        })
+    }
+
+    /// 设置检查点（Sheet 列映射确定后、或从检查点继续时调用）
+    pub fn set_checkpoint(&mut self, checkpoint: TaskCheckpoint) {
+        self.checkpoint = Some(checkpoint);
+        self.checkpoint_dirty = true;
+    }
+
+    /// 将检查点推进到当前行：须在写入该行结果之前调用，counts 为计入该行后的 Sheet 计数
+    pub fn checkpoint_row(&mut self, row: usize, counts: ImportCounts) {
+        if let Some(checkpoint) = &mut self.checkpoint {
+            checkpoint.last_row = Some(row);
+            checkpoint.sheet_counts = counts;
+            self.checkpoint_dirty = true;
+        }
+    }
+
+    /// 查找重复记录，返回已导入成功的记录 ID；命中仍在缓冲区的记录时先落库
+    pub async fn find_duplicate(
+        &mut self,
+        db: &DatabaseConnection,
+        data: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Option<i32>, String> {
+        if !self.dedup.is_enabled() {
+            return Ok(None);
+        }
+        let values = self.dedup.values_of(data);
+        match self.dedup.find(&values) {
+            Some(Slot::Stored(id)) => Ok(Some(id)),
+            Some(Slot::Pending(_)) => {
+                self.flush(db).await?;
+                match self.dedup.find(&values) {
+                    Some(Slot::Stored(id)) => Ok(Some(id)),
+                    _ => Ok(None),
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 读取已有记录的 data
+    pub async fn fetch_data(
+        &self,
+        db: &DatabaseConnection,
+        record_id: i32,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                db.get_database_backend(),
+                "SELECT data FROM project_records WHERE id = ?",
+                [record_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("数据库错误: {}", e))?
+            .ok_or_else(|| format!("记录 {} 不存在", record_id))?;
+
+        let data_str: String = row.try_get_by("data").unwrap_or_default();
+        Ok(serde_json::from_str(&data_str).unwrap_or_default())
+    }
+
+    /// 更新已有记录的 data（去重策略 update / merge），记入修改历史并同步去重索引
+    pub async fn update_record(
+        &mut self,
+        db: &DatabaseConnection,
+        record_id: i32,
+        old_data: &serde_json::Map<String, serde_json::Value>,
+        new_data: &serde_json::Value,
+        raw_data: Option<String>,
+    ) -> Result<(), String> {
+        let data_str = serde_json::to_string(new_data)
+            .map_err(|e| format!("JSON 序列化错误: {}", e))?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let (sql, params): (&str, Vec<sea_orm::Value>) = match raw_data {
+            Some(raw) => (
+                "UPDATE project_records SET data = ?, raw_data = ?, updated_at = ? WHERE id = ?",
+                vec![data_str.clone().into(), raw.into(), now.into(), record_id.into()],
+            ),
+            None => (
+                "UPDATE project_records SET data = ?, updated_at = ? WHERE id = ?",
+                vec![data_str.clone().into(), now.into(), record_id.into()],
+            ),
+        };
+        let old_str = serde_json::to_string(old_data)
+            .map_err(|e| format!("JSON 序列化错误: {}", e))?;
+        let actor = format!("import:{}", self.batch_number.as_deref().unwrap_or("-"));
+
+        // 缓冲区中的记录、本次更新与检查点在同一事务中落库
+        let txn = db.begin().await.map_err(|e| format!("开启事务失败: {}", e))?;
+        let ids = self.write_buffer(&txn).await?;
+        append_history(
+            &txn,
+            record_id,
+            self.project_id,
+            OP_IMPORT_UPDATE,
+            Some(RecordVersion { data: &old_str, status: "success", error_message: None }),
+            Some(RecordVersion { data: &data_str, status: "success", error_message: None }),
+            &actor,
+        )
+        .await?;
+        txn.execute(Statement::from_sql_and_values(txn.get_database_backend(), sql, params))
+            .await
+            .map_err(|e| format!("数据库错误: {}", e))?;
+        self.write_checkpoint(&txn).await?;
+        txn.commit().await.map_err(|e| format!("提交事务失败: {}", e))?;
+        self.buffer_committed(ids);
+
+        if self.dedup.is_enabled() {
+            let old_values = self.dedup.values_of(old_data);
+            let new_values = new_data
+                .as_object()
+                .map(|m| self.dedup.values_of(m))
+                .unwrap_or_else(|| vec![String::new(); old_values.len()]);
+            if old_values != new_values {
+                self.dedup.remove(Slot::Stored(record_id), &old_values);
+                self.dedup.add(Slot::Stored(record_id), &new_values);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 追加一条记录到缓冲区，缓冲区满时自动落库
+    pub async fn push(&mut self, db: &DatabaseConnection, record: PendingRecord) -> Result<(), String> {
+        if record.status == "success" && self.dedup.is_enabled() {
+            if let Some(data) = record.data.as_object() {
+                let values = self.dedup.values_of(data);
+                let slot = Slot::Pending(self.buffer.len());
+                self.dedup.add(slot, &values);
+                self.pending_values.push((self.buffer.len(), values));
+            }
+        }
+        self.buffer.push(record);
+
+        if self.buffer.len() >= BATCH_SIZE {
+            self.flush(db).await?;
+        }
+        Ok(())
+    }
+
+    /// 将缓冲区中的记录与检查点在一个事务中批量写入
+    pub async fn flush(&mut self, db: &DatabaseConnection) -> Result<(), String> {
+        if self.buffer.is_empty() && !self.checkpoint_dirty {
+            return Ok(());
+        }
+
+        let txn = db.begin().await.map_err(|e| format!("开启事务失败: {}", e))?;
+        let ids = self.write_buffer(&txn).await?;
+        self.write_checkpoint(&txn).await?;
+        txn.commit().await.map_err(|e| format!("提交事务失败: {}", e))?;
+        self.buffer_committed(ids);
+
+        Ok(())
+    }
+
+    /// 在事务中插入缓冲区中的记录，返回与缓冲区一一对应的记录 ID
+    async fn write_buffer(&self, txn: &DatabaseTransaction) -> Result<Vec<i32>, String> {
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut ids: Vec<i32> = Vec::with_capacity(self.buffer.len());
+
+        for chunk in self.buffer.chunks(BATCH_SIZE) {
+            let mut params: Vec<sea_orm::Value> = Vec::with_capacity(chunk.len() * 10);
+            let mut placeholders: Vec<&str> = Vec::with_capacity(chunk.len());
+
+            for record in chunk {
+                let data_str = serde_json::to_string(&record.data)
+                    .map_err(|e| format!("JSON 序列化错误: {}", e))?;
+
+                placeholders.push("(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULL)");
+                params.push(self.project_id.into());
+                params.push(data_str.into());
+                params.push(record.raw_data.clone().into());
+                params.push(record.source_file.clone().into());
+                params.push(record.source_sheet.clone().into());
+                params.push(record.row_number.into());
+                params.push(self.batch_number.clone().into());
+                params.push(record.status.clone().into());
+                params.push(record.error_message.clone().into());
+                params.push(now.clone().into());
+            }
+
+            let insert_sql = format!(
+                "INSERT INTO project_records \
+                 (project_id, data, raw_data, source_file, source_sheet, row_number, batch_number, status, error_message, created_at, updated_at) \
+                 VALUES {}",
+                placeholders.join(", ")
+            );
+
+            txn.execute(Statement::from_sql_and_values(
+                txn.get_database_backend(),
+                &insert_sql,
+                params,
+            ))
+            .await
+            .map_err(|e| format!("批量插入失败: {}", e))?;
+
+            // 同一事务内单条多值 INSERT 分配的 ID 连续递增
+            let last_id = txn
+                .query_one(Statement::from_string(
+                    txn.get_database_backend(),
+                    "SELECT last_insert_rowid() AS id".to_string(),
+                ))
+                .await
+                .map_err(|e| format!("数据库错误: {}", e))?
+                .and_then(|r| r.try_get_by::<i64, _>("id").ok())
+                .ok_or_else(|| "获取插入记录 ID 失败".to_string())?;
+            let first_id = last_id - chunk.len() as i64 + 1;
+            ids.extend((0..chunk.len() as i64).map(|i| (first_id + i) as i32));
+        }
+
+        Ok(ids)
+    }
+
+    /// 在事务中保存未落库的检查点
+    async fn write_checkpoint(&self, txn: &DatabaseTransaction) -> Result<(), String> {
+        let Some(checkpoint) = self.checkpoint.as_ref().filter(|_| self.checkpoint_dirty) else {
+            return Ok(());
+        };
+        let json = serde_json::to_string(checkpoint).map_err(|e| format!("JSON 序列化错误: {}", e))?;
+        txn.execute(Statement::from_sql_and_values(
+            txn.get_database_backend(),
+            "UPDATE processing_tasks SET checkpoint = ? WHERE id = ?",
+            [json.into(), self.task_id.clone().into()],
+        ))
+        .await
+        .map_err(|e| format!("保存检查点失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 事务提交后：索引中的缓冲区下标替换为记录 ID，清空缓冲区
+    fn buffer_committed(&mut self, ids: Vec<i32>) {
+        for (idx, values) in std::mem::take(&mut self.pending_values) {
+            self.dedup.replace(Slot::Pending(idx), Slot::Stored(ids[idx]), &values);
+        }
+        self.buffer.clear();
+        self.checkpoint_dirty = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::infrastructure::persistence::migrations::run_migrations;
+    use sea_orm::{ActiveModelTrait, Database, Set};
+    use crate::backend::infrastructure::persistence::models::task;
+
+    async fn setup(task_id: &str) -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&db).await.unwrap();
+        task::ActiveModel {
+            id: Set(task_id.to_string()),
+            project_id: Set(1),
+            status: Set("processing".to_string()),
+            total_files: Set(1),
+            processed_files: Set(0),
+            total_rows: Set(0),
+            processed_rows: Set(0),
+            success_count: Set(0),
+            error_count: Set(0),
+            batch_number: Set(Some("BATCH_TEST".to_string())),
+            source_files: Set(None),
+            options: Set(None),
+            checkpoint: Set(None),
+            created_at: Set(chrono::Utc::now()),
+            updated_at: Set(None),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+        db
+    }
+
+    fn row(phone: &str, email: &str, name: &str) -> serde_json::Map<String, serde_json::Value> {
+        let mut data = serde_json::Map::new();
+        data.insert("1".to_string(), serde_json::Value::String(phone.to_string()));
+        data.insert("2".to_string(), serde_json::Value::String(email.to_string()));
+        data.insert("3".to_string(), serde_json::Value::String(name.to_string()));
+        data
+    }
+
+    fn pending(data: serde_json::Map<String, serde_json::Value>, row_number: i32) -> PendingRecord {
+        PendingRecord {
+            data: serde_json::Value::Object(data),
+            raw_data: None,
+            source_file: Some("test.xlsx".to_string()),
+            source_sheet: Some("Sheet1".to_string()),
+            row_number: Some(row_number),
+            status: "success".to_string(),
+            error_message: None,
+        }
+    }
+
+    /// 原逐行 SQL 去重查询（对照实现）
+    async fn sql_duplicate(
+        db: &DatabaseConnection,
+        data: &serde_json::Map<String, serde_json::Value>,
+    ) -> Option<i32> {
+        let mut conditions = vec!["project_id = 1".to_string()];
+        let mut params: Vec<sea_orm::Value> = vec![];
+        for field_id in ["1", "2"] {
+            let value = data[field_id].as_str().unwrap();
+            if !value.is_empty() {
+                conditions.push(format!("json_extract(data, '$.{}') = ?", field_id));
+                params.push(value.into());
+            }
+        }
+        if conditions.len() == 1 {
+            return None;
+        }
+        let sql = format!(
+            "SELECT id FROM project_records WHERE {} AND status = 'success' ORDER BY id LIMIT 1",
+            conditions.join(" AND ")
+        );
+        db.query_one(Statement::from_sql_and_values(db.get_database_backend(), &sql, params))
+            .await
+            .unwrap()
+            .and_then(|r| r.try_get_by::<i32, _>("id").ok())
+    }
+
+    #[tokio::test]
+    async fn test_dedup_index_matches_sql() {
+        let db = setup("t1").await;
+        let mut writer = ImportWriter::new(&db, "t1", vec![1, 2]).await.unwrap();
+
+        // 含部分为空的去重字段，覆盖「空值不参与比较」的语义
+        let rows: Vec<_> = (0..400)
+            .map(|i| {
+                let phone = if i % 7 == 0 { String::new() } else { format!("138{:08}", i % 90) };
+                let email = if i % 5 == 0 { String::new() } else { format!("u{}@example.com", i % 60) };
+                row(&phone, &email, &format!("name{}", i))
+            })
+            .collect();
+
+        for (i, data) in rows.into_iter().enumerate() {
+            let found = writer.find_duplicate(&db, &data).await.unwrap();
+            writer.flush(&db).await.unwrap();
+            assert_eq!(found, sql_duplicate(&db, &data).await, "row {}", i);
+            if found.is_none() {
+                writer.push(&db, pending(data, i as i32)).await.unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_dedup_index_with_many_fields() {
+        // 24 个去重字段：按字段索引，不会为 2^24 个字段组合建键
+        let mut index = DedupIndex::new((1..=24).collect());
+        let values = |seed: usize| -> Vec<String> {
+            (0..24).map(|i| if i % 3 == 0 { String::new() } else { format!("v{}-{}", i, seed) }).collect()
+        };
+        index.add(Slot::Stored(2), &values(1));
+        index.add(Slot::Stored(1), &values(1));
+        index.add(Slot::Pending(0), &values(2));
+
+        assert_eq!(index.find(&values(1)), Some(Slot::Stored(1)));
+        assert_eq!(index.postings.iter().map(HashMap::len).sum::<usize>(), 32);
+
+        // 行中为空的字段不参与比较；任一非空字段不同即不重复
+        let mut partial = vec![String::new(); 24];
+        partial[5] = "v5-2".to_string();
+        assert_eq!(index.find(&partial), Some(Slot::Pending(0)));
+        partial[7] = "v7-1".to_string();
+        assert_eq!(index.find(&partial), None);
+        assert_eq!(index.find(&vec![String::new(); 24]), None);
+
+        index.replace(Slot::Pending(0), Slot::Stored(3), &values(2));
+        index.remove(Slot::Stored(1), &values(1));
+        assert_eq!(index.find(&values(2)), Some(Slot::Stored(3)));
+        assert_eq!(index.find(&values(1)), Some(Slot::Stored(2)));
+    }
+
+    #[tokio::test]
+    async fn test_update_reindexes_record() {
+        let db = setup("t2").await;
+        let mut writer = ImportWriter::new(&db, "t2", vec![1, 2]).await.unwrap();
+
+        writer.push(&db, pending(row("13800000001", "a@example.com", "张三"), 1)).await.unwrap();
+        let id = writer.find_duplicate(&db, &row("13800000001", "", "")).await.unwrap().unwrap();
+
+        // 覆盖后邮箱变化，旧邮箱不应再命中
+        let old = writer.fetch_data(&db, id).await.unwrap();
+        let new = serde_json::Value::Object(row("13800000001", "b@example.com", "张三"));
+        writer.update_record(&db, id, &old, &new, None).await.unwrap();
+
+        assert_eq!(writer.find_duplicate(&db, &row("", "a@example.com", "")).await.unwrap(), None);
+        assert_eq!(writer.find_duplicate(&db, &row("", "b@example.com", "")).await.unwrap(), Some(id));
+
+        // 覆盖记入历史
+        let history = db
+            .query_one(Statement::from_sql_and_values(
+                db.get_database_backend(),
+                "SELECT operation, before_data, actor FROM record_history WHERE record_id = ?",
+                [id.into()],
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(history.try_get_by::<String, _>("operation").unwrap(), "import_update");
+        assert!(history.try_get_by::<String, _>("before_data").unwrap().contains("a@example.com"));
+        assert_eq!(history.try_get_by::<String, _>("actor").unwrap(), "import:BATCH_TEST");
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_commits_with_rows() {
+        let db = setup("t4").await;
+        let mut writer = ImportWriter::new(&db, "t4", vec![1]).await.unwrap();
+        let checkpoint = || async {
+            db.query_one(Statement::from_string(
+                db.get_database_backend(),
+                "SELECT checkpoint FROM processing_tasks WHERE id = 't4'".to_string(),
+            ))
+            .await
+            .unwrap()
+            .and_then(|r| r.try_get_by::<Option<String>, _>("checkpoint").unwrap())
+            .map(|s| serde_json::from_str::<TaskCheckpoint>(&s).unwrap())
+        };
+
+        writer.set_checkpoint(TaskCheckpoint {
+            file_index: 0,
+            file_name: "test.xlsx".to_string(),
+            sheet_name: "Sheet1".to_string(),
+            last_row: None,
+            mapping: ColumnMappingResponse {
+                header_row: 0,
+                mappings: vec![],
+                confidence: 1.0,
+                unmatched_columns: vec![],
+            },
+            mapping_source: "rules".to_string(),
+            sheet_counts: ImportCounts::default(),
+        });
+        writer.checkpoint_row(1, ImportCounts { rows: 1, success: 1, ..Default::default() });
+        writer.push(&db, pending(row("13800000001", "", "张三"), 1)).await.unwrap();
+
+        // 缓冲区未写入时检查点不前进
+        assert!(checkpoint().await.is_none());
+
+        writer.flush(&db).await.unwrap();
+        let saved = checkpoint().await.unwrap();
+        assert_eq!(saved.last_row, Some(1));
+        assert_eq!(saved.sheet_counts.success, 1);
+        assert!(writer.find_duplicate(&db, &row("13800000001", "", "")).await.unwrap().is_some());
+    }
+
+    /// 基准：20000 行（一半为重复行）批量写入 + 内存去重
+    #[tokio::test]
+    #[ignore = "基准测试，手动运行：cargo test bench_batched_import -- --ignored"]
+    async fn bench_batched_import() {
+        const ROWS: usize = 20_000;
+        let db = setup("t3").await;
+        let mut writer = ImportWriter::new(&db, "t3", vec![1]).await.unwrap();
+
+        let start = std::time::Instant::now();
+        let mut inserted = 0;
+        for i in 0..ROWS {
+            let data = row(&format!("139{:08}", i % (ROWS / 2)), "", &format!("name{}", i));
+            if writer.find_duplicate(&db, &data).await.unwrap().is_none() {
+                writer.push(&db, pending(data, i as i32)).await.unwrap();
+                inserted += 1;
+            }
+        }
+        writer.flush(&db).await.unwrap();
+        let elapsed = start.elapsed();
+
+        let count = db
+            .query_one(Statement::from_string(
+                db.get_database_backend(),
+                "SELECT COUNT(*) AS c FROM project_records".to_string(),
+            ))
+            .await
+            .unwrap()
+            .and_then(|r| r.try_get_by::<i64, _>("c").ok())
+            .unwrap();
+        assert_eq!(inserted, ROWS / 2);
+        assert_eq!(count as usize, ROWS / 2);
+        assert!(elapsed.as_secs() < 30, "批量导入过慢: {:?}", elapsed);
+    }
+}
@@ -0,0 +1,241 @@
+// AI 列映射缓存 Tauri Commands
+//
+// AI 分析得到的列映射按「项目 + 归一化表头 + 字段定义哈希」缓存（表头取 AI 识别出的表头行），
+// 同一版式的周期性导入直接命中缓存，不再重复调用 AI
+
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+};
+use std::sync::Arc;
+
+use crate::backend::infrastructure::persistence::models::{mapping_cache, MappingCache};
+use super::ai_service::{ColumnMappingResponse, FieldDefinition};
+use super::mapping_templates::{header_fingerprint, stable_hash};
+
+// ============ 辅助函数 ============
+
+/// 计算字段定义哈希（字段名、标签、类型、补充要求、提取要求任一变化都会使缓存失效）
+pub fn field_definition_hash(field_defs: &[FieldDefinition]) -> String {
+    let joined = field_defs
+        .iter()
+        .map(|f| {
+            [
+                f.field_name.as_str(),
+                f.field_label.as_str(),
+                f.field_type.as_str(),
+                f.additional_requirement.as_deref().unwrap_or(""),
+                f.extraction_hint.as_deref().unwrap_or(""),
+            ]
+            .join("\u{1f}")
+        })
+        .collect::<Vec<_>>()
+        .join("\u{1e}");
+    stable_hash(&joined)
+}
+
+/// 缓存映射所识别的表头行（相对于传入的行；无表头时为 None）
+fn cached_header<'a>(rows: &'a [Vec<String>], mapping: &ColumnMappingResponse) -> Option<&'a [String]> {
+    usize::try_from(mapping.header_row)
+        .ok()
+        .and_then(|idx| rows.get(idx))
+        .map(|row| row.as_slice())
+}
+
+/// 查找缓存的 AI 列映射，命中时累加命中次数
+///
+/// 缓存以映射结果中识别出的表头行生成指纹（表头前可能有标题行），
+/// 查找时按每条缓存记录的表头行取对应行比对指纹
+pub async fn find_cached_mapping(
+    db: &DatabaseConnection,
+    project_id: i32,
+    rows: &[Vec<String>],
+    field_hash: &str,
+) -> Result<Option<ColumnMappingResponse>, String> {
+    let candidates = MappingCache::find()
+        .filter(mapping_cache::Column::ProjectId.eq(project_id))
+        .filter(mapping_cache::Column::FieldHash.eq(field_hash))
+        .all(db)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    let matched = candidates.into_iter().find_map(|model| {
+        // 缓存内容损坏时视为未命中，由 AI 重新分析后覆盖
+        let mapping: ColumnMappingResponse = serde_json::from_str(&model.mapping).ok()?;
+        let fingerprint = header_fingerprint(cached_header(rows, &mapping)?)?;
+        (fingerprint == model.header_fingerprint).then_some((model, mapping))
+    });
+    let Some((model, mapping)) = matched else {
+        return Ok(None);
+    };
+
+    let hit_count = model.hit_count;
+    let mut active: mapping_cache::ActiveModel = model.into();
+    active.hit_count = Set(hit_count + 1);
+    active.last_hit_at = Set(Some(chrono::Utc::now()));
+    active.update(db).await.map_err(|e| format!("数据库错误: {}", e))?;
+
+    Ok(Some(mapping))
+}
+
+/// 写入（或覆盖）AI 列映射缓存（以映射结果识别出的表头行生成指纹）
+pub async fn save_cached_mapping(
+    db: &DatabaseConnection,
+    project_id: i32,
+    rows: &[Vec<String>],
+    field_hash: &str,
+    mapping: &ColumnMappingResponse,
+) -> Result<(), String> {
+    let Some(fingerprint) = cached_header(rows, mapping).and_then(header_fingerprint) else {
+        // 无表头（header_row = -1）或表头行超出范围时无法生成指纹，不缓存
+        return Ok(());
+    };
+
+    let mapping_json = serde_json::to_string(mapping)
+        .map_err(|e| format!("JSON 序列化错误: {}", e))?;
+
+    let existing = MappingCache::find()
+        .filter(mapping_cache::Column::ProjectId.eq(project_id))
+        .filter(mapping_cache::Column::HeaderFingerprint.eq(&fingerprint))
+        .filter(mapping_cache::Column::FieldHash.eq(field_hash))
+        .one(db)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    if let Some(model) = existing {
+        let mut active: mapping_cache::ActiveModel = model.into();
+        active.mapping = Set(mapping_json);
+        active.update(db).await.map_err(|e| format!("数据库错误: {}", e))?;
+    } else {
+        let new_cache = mapping_cache::ActiveModel {
+            project_id: Set(project_id),
+            header_fingerprint: Set(fingerprint),
+            field_hash: Set(field_hash.to_string()),
+            mapping: Set(mapping_json),
+            hit_count: Set(0),
+            created_at: Set(chrono::Utc::now()),
+            last_hit_at: Set(None),
+            ..Default::default()
+        };
+        new_cache.insert(db).await.map_err(|e| format!("数据库错误: {}", e))?;
+    }
+
+    Ok(())
+}
+
+// ============ Tauri Commands ============
+
+/// 清空项目的 AI 列映射缓存，返回删除条数
+#[tauri::command]
+pub async fn clear_mapping_cache(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    project_id: i32,
+) -> Result<u64, String> {
+    delete_project_cache(db.inner().as_ref(), project_id).await
+}
+
+async fn delete_project_cache(db: &DatabaseConnection, project_id: i32) -> Result<u64, String> {
+    let result = MappingCache::delete_many()
+        .filter(mapping_cache::Column::ProjectId.eq(project_id))
+        .exec(db)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::infrastructure::persistence::migrations::run_migrations;
+    use super::super::ai_service::FieldMapping;
+    use sea_orm::{Database, PaginatorTrait};
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn field(name: &str, label: &str, hint: Option<&str>) -> FieldDefinition {
+        FieldDefinition {
+            field_name: name.to_string(),
+            field_label: label.to_string(),
+            field_type: "text".to_string(),
+            additional_requirement: None,
+            extraction_hint: hint.map(str::to_string),
+        }
+    }
+
+    fn mapping(header_row: i32) -> ColumnMappingResponse {
+        ColumnMappingResponse {
+            header_row,
+            mappings: vec![FieldMapping {
+                field_name: "phone".to_string(),
+                column_index: 1,
+                column_header: "手机号".to_string(),
+                confidence: 0.9,
+            }],
+            confidence: 0.9,
+            unmatched_columns: vec![],
+        }
+    }
+
+    #[test]
+    fn test_field_definition_hash() {
+        let base = vec![field("name", "姓名", None), field("phone", "手机号", None)];
+        assert_eq!(field_definition_hash(&base), field_definition_hash(&base.clone()));
+
+        // 标签、提取要求、字段顺序变化都会使缓存失效
+        let relabeled = vec![field("name", "名字", None), field("phone", "手机号", None)];
+        let hinted = vec![field("name", "姓名", Some("只取中文名")), field("phone", "手机号", None)];
+        let reordered = vec![field("phone", "手机号", None), field("name", "姓名", None)];
+        for changed in [relabeled, hinted, reordered] {
+            assert_ne!(field_definition_hash(&base), field_definition_hash(&changed));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_keyed_on_detected_header_row() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&db).await.unwrap();
+        let hash = field_definition_hash(&[field("phone", "手机号", None)]);
+
+        // AI 识别出标题行之下的第 2 行为表头
+        let march = vec![row(&["2024年3月客户名单"]), row(&["姓名", "手机号"]), row(&["张三", "13800138000"])];
+        save_cached_mapping(&db, 1, &march, &hash, &mapping(1)).await.unwrap();
+
+        // 标题不同、表头相同的下一期文件命中缓存
+        let april = vec![row(&["2024年4月客户名单"]), row(&["姓名", "手机号"]), row(&["李四", "13900139000"])];
+        let hit = find_cached_mapping(&db, 1, &april, &hash).await.unwrap().unwrap();
+        assert_eq!(hit.header_row, 1);
+        assert_eq!(hit.mappings[0].column_index, 1);
+
+        let model = MappingCache::find().one(&db).await.unwrap().unwrap();
+        assert_eq!(model.hit_count, 1);
+        assert!(model.last_hit_at.is_some());
+
+        // 表头位置不同、其他项目、字段定义变化均不命中
+        let shifted = vec![row(&["姓名", "手机号"]), row(&["张三", "13800138000"])];
+        assert!(find_cached_mapping(&db, 1, &shifted, &hash).await.unwrap().is_none());
+        assert!(find_cached_mapping(&db, 2, &april, &hash).await.unwrap().is_none());
+        let new_hash = field_definition_hash(&[field("phone", "电话", None)]);
+        assert!(find_cached_mapping(&db, 1, &april, &new_hash).await.unwrap().is_none());
+
+        // 再次保存同一版式时覆盖而非新增；无表头的映射不缓存
+        save_cached_mapping(&db, 1, &april, &hash, &mapping(1)).await.unwrap();
+        save_cached_mapping(&db, 1, &april, &hash, &mapping(-1)).await.unwrap();
+        assert_eq!(MappingCache::find().count(&db).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_clear_mapping_cache_only_touches_project() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&db).await.unwrap();
+        let hash = field_definition_hash(&[field("phone", "手机号", None)]);
+        let rows = vec![row(&["姓名", "手机号"])];
+        save_cached_mapping(&db, 1, &rows, &hash, &mapping(0)).await.unwrap();
+        save_cached_mapping(&db, 2, &rows, &hash, &mapping(0)).await.unwrap();
+
+        assert_eq!(delete_project_cache(&db, 1).await.unwrap(), 1);
+        assert!(find_cached_mapping(&db, 1, &rows, &hash).await.unwrap().is_none());
+        assert!(find_cached_mapping(&db, 2, &rows, &hash).await.unwrap().is_some());
+    }
+}
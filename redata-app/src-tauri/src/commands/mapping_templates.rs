@@ -0,0 +1,263 @@
+// 列映射模板 Tauri Commands
+//
+// 人工确认后的列映射按「项目 + 表头指纹」保存为模板，
+// 后续表头相同的 Sheet 直接复用，跳过 AI 分析
+
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::backend::infrastructure::persistence::models::{mapping_template, MappingTemplate};
+use super::ai_service::{ColumnMappingResponse, FieldMapping};
+
+// ============ 响应结构 ============
+
+#[derive(Debug, Serialize)]
+pub struct MappingTemplateResponse {
+    pub id: i32,
+    pub project_id: i32,
+    pub header_fingerprint: String,
+    pub headers: Vec<String>,
+    pub header_row: i32,
+    pub mappings: Vec<FieldMapping>,
+    pub created_at: String,
+    pub updated_at: Option<String>,
+}
+
+impl From<mapping_template::Model> for MappingTemplateResponse {
+    fn from(m: mapping_template::Model) -> Self {
+        Self {
+            id: m.id,
+            project_id: m.project_id,
+            header_fingerprint: m.header_fingerprint,
+            headers: serde_json::from_str(&m.headers).unwrap_or_default(),
+            header_row: m.header_row,
+            mappings: serde_json::from_str(&m.mappings).unwrap_or_default(),
+            created_at: m.created_at.to_rfc3339(),
+            updated_at: m.updated_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+// ============ 辅助函数 ============
+
+/// 归一化表头：去除空白、转小写，并忽略末尾的空列
+pub fn normalize_headers(headers: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = headers
+        .iter()
+        .map(|h| {
+            h.chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .collect();
+    while normalized.last().map(|h| h.is_empty()).unwrap_or(false) {
+        normalized.pop();
+    }
+    normalized
+}
+
+/// 稳定哈希（FNV-1a 64 位），用于持久化的指纹，不随 Rust 版本变化
+pub fn stable_hash(input: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// 计算表头指纹（全空表头返回 None）
+pub fn header_fingerprint(headers: &[String]) -> Option<String> {
+    let normalized = normalize_headers(headers);
+    if normalized.iter().all(|h| h.is_empty()) {
+        return None;
+    }
+    Some(stable_hash(&normalized.join("\u{1f}")))
+}
+
+/// 查找表头与模板一致的列映射：模板记录了表头所在行，只比较 Sheet 中该行的指纹
+/// （不限制表头行的位置，指定了靠后表头行的模板同样可以命中）
+pub async fn find_mapping_template(
+    db: &DatabaseConnection,
+    project_id: i32,
+    rows: &[Vec<String>],
+) -> Result<Option<ColumnMappingResponse>, String> {
+    let templates = MappingTemplate::find()
+        .filter(mapping_template::Column::ProjectId.eq(project_id))
+        .order_by_asc(mapping_template::Column::HeaderRow)
+        .all(db)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    let matched = templates.iter().find(|t| {
+        usize::try_from(t.header_row)
+            .ok()
+            .and_then(|idx| rows.get(idx))
+            .and_then(|row| header_fingerprint(row))
+            .is_some_and(|fp| fp == t.header_fingerprint)
+    });
+    let Some(t) = matched else {
+        return Ok(None);
+    };
+
+    let mappings: Vec<FieldMapping> = serde_json::from_str(&t.mappings)
+        .map_err(|e| format!("映射模板 {} 解析失败: {}", t.id, e))?;
+    Ok(Some(ColumnMappingResponse {
+        header_row: t.header_row,
+        mappings,
+        confidence: 1.0,
+        unmatched_columns: vec![],
+    }))
+}
+
+/// 保存（或覆盖）人工确认的列映射模板
+pub async fn save_mapping_template(
+    db: &DatabaseConnection,
+    project_id: i32,
+    headers: &[String],
+    mapping: &ColumnMappingResponse,
+) -> Result<(), String> {
+    let Some(fingerprint) = header_fingerprint(headers) else {
+        // 无表头的 Sheet 无法生成指纹，不保存模板
+        return Ok(());
+    };
+
+    let headers_json = serde_json::to_string(headers)
+        .map_err(|e| format!("JSON 序列化错误: {}", e))?;
+    let mappings_json = serde_json::to_string(&mapping.mappings)
+        .map_err(|e| format!("JSON 序列化错误: {}", e))?;
+    let now = chrono::Utc::now();
+
+    let existing = MappingTemplate::find()
+        .filter(mapping_template::Column::ProjectId.eq(project_id))
+        .filter(mapping_template::Column::HeaderFingerprint.eq(&fingerprint))
+        .one(db)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    if let Some(model) = existing {
+        let mut active: mapping_template::ActiveModel = model.into();
+        active.headers = Set(headers_json);
+        active.header_row = Set(mapping.header_row);
+        active.mappings = Set(mappings_json);
+        active.updated_at = Set(Some(now));
+        active.update(db).await.map_err(|e| format!("数据库错误: {}", e))?;
+    } else {
+        let new_template = mapping_template::ActiveModel {
+            project_id: Set(project_id),
+            header_fingerprint: Set(fingerprint),
+            headers: Set(headers_json),
+            header_row: Set(mapping.header_row),
+            mappings: Set(mappings_json),
+            created_at: Set(now),
+            updated_at: Set(None),
+            ..Default::default()
+        };
+        new_template.insert(db).await.map_err(|e| format!("数据库错误: {}", e))?;
+    }
+
+    Ok(())
+}
+
+// ============ Tauri Commands ============
+
+/// 获取项目的列映射模板列表
+#[tauri::command]
+pub async fn list_mapping_templates(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    project_id: i32,
+) -> Result<Vec<MappingTemplateResponse>, String> {
+    let templates = MappingTemplate::find()
+        .filter(mapping_template::Column::ProjectId.eq(project_id))
+        .order_by_desc(mapping_template::Column::CreatedAt)
+        .all(db.inner().as_ref())
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    Ok(templates.into_iter().map(Into::into).collect())
+}
+
+/// 删除列映射模板
+#[tauri::command]
+pub async fn delete_mapping_template(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    id: i32,
+) -> Result<(), String> {
+    MappingTemplate::delete_by_id(id)
+        .exec(db.inner().as_ref())
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::infrastructure::persistence::migrations::run_migrations;
+    use sea_orm::Database;
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn mapping(header_row: i32) -> ColumnMappingResponse {
+        ColumnMappingResponse {
+            header_row,
+            mappings: vec![FieldMapping {
+                field_name: "phone".to_string(),
+                column_index: 1,
+                column_header: "手机号".to_string(),
+                confidence: 1.0,
+            }],
+            confidence: 1.0,
+            unmatched_columns: vec![],
+        }
+    }
+
+    #[test]
+    fn test_normalize_headers() {
+        assert_eq!(normalize_headers(&row(&[" 姓 名 ", "Phone\n", "", ""])), row(&["姓名", "phone"]));
+        assert_eq!(normalize_headers(&row(&["", "", ""])), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_header_fingerprint() {
+        let fp = header_fingerprint(&row(&["姓名", "Phone"])).unwrap();
+        // 空白、大小写、末尾空列不影响指纹
+        assert_eq!(header_fingerprint(&row(&[" 姓名", "PHONE", ""])), Some(fp.clone()));
+        assert_ne!(header_fingerprint(&row(&["Phone", "姓名"])), Some(fp.clone()));
+        assert_ne!(header_fingerprint(&row(&["姓名", "", "Phone"])), Some(fp));
+        assert_eq!(header_fingerprint(&row(&["", " "])), None);
+        // 持久化的指纹不随版本变化
+        assert_eq!(stable_hash(""), "cbf29ce484222325");
+    }
+
+    #[tokio::test]
+    async fn test_find_template_at_stored_header_row() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&db).await.unwrap();
+
+        // 表头在第 13 行（前面是标题与说明行）
+        let mut rows: Vec<Vec<String>> = (0..12).map(|i| row(&[&format!("说明 {}", i)])).collect();
+        rows.push(row(&["姓名", "手机号"]));
+        rows.push(row(&["张三", "13800138000"]));
+        save_mapping_template(&db, 1, &rows[12], &mapping(12)).await.unwrap();
+
+        let found = find_mapping_template(&db, 1, &rows).await.unwrap().unwrap();
+        assert_eq!(found.header_row, 12);
+        assert_eq!(found.mappings[0].field_name, "phone");
+
+        // 表头不在模板记录的行、其他项目：不命中
+        assert!(find_mapping_template(&db, 1, &rows[1..]).await.unwrap().is_none());
+        assert!(find_mapping_template(&db, 2, &rows).await.unwrap().is_none());
+
+        // 相同表头再次保存时覆盖原模板
+        save_mapping_template(&db, 1, &rows[12], &mapping(12)).await.unwrap();
+        assert_eq!(MappingTemplate::find().all(&db).await.unwrap().len(), 1);
+    }
+}
@@ -9,10 +9,29 @@ pub mod fields;
 pub mod ai_configs;
 pub mod ai_service;
 pub mod ai_utils;
+pub mod ai_extractor;
+pub mod data_utils;
+pub mod date_utils;
+pub mod id_card_utils;
+pub mod regions;
+pub mod phone_utils;
+pub mod address_utils;
+pub mod choice_utils;
+pub mod currency_utils;
+pub mod field_transforms;
+pub mod expression;
 pub mod records;
+pub mod record_history;
 pub mod excel;
 pub mod tasks;
 pub mod processing;
+pub mod mapping_templates;
+pub mod mapping_cache;
+pub mod rule_mapper;
+pub mod header_detector;
+pub mod import_writer;
+pub mod sheet_reader;
+pub mod csv_reader;
 pub mod statistics;
 
 // 重新导出所有 commands，方便在 lib.rs 中注册
@@ -22,7 +41,10 @@ pub use fields::*;
 pub use ai_configs::*;
 pub use ai_service::*;
 pub use records::*;
+pub use record_history::{get_record_history, revert_record};
 pub use excel::*;
 pub use tasks::*;
 pub use processing::*;
+pub use mapping_templates::*;
+pub use mapping_cache::*;
 pub use statistics::*;
@@ -0,0 +1,219 @@
+// 电话号码规范化
+//
+// - 手机号：去除 +86 / 0086 / 86 前缀，统一为 11 位数字
+// - 固定电话：识别区号（010、02X 为 3 位，其余 4 位），统一为「区号-号码」，分机号统一为「转分机」
+// - 400 / 800 服务号码：统一为 10 位数字
+// - 一个单元格含多个号码（如「138xxx / 139xxx」）时拆分为多个号码
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::domain::services::text_utils::to_half_width;
+
+/// 一个单元格含多个号码时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhonePolicy {
+    /// 只保留第一个号码（其余号码可写入附加字段）
+    #[default]
+    First,
+    /// 保留全部号码（存储为数组）
+    All,
+    /// 全部号码拼接为一个字符串
+    Join,
+}
+
+/// 多个号码拼接时的分隔符
+pub const PHONE_JOIN_SEPARATOR: &str = "; ";
+
+/// 单个规范化号码的验证规则（get_validation_rule 使用）
+pub const PHONE_PATTERN: &str = r"(1[3-9]\d{9}|0\d{2,3}-\d{7,8}(转\d{1,6})?|[48]00\d{7}|\d{7,8}(转\d{1,6})?)";
+
+/// 多号码单元格的分隔符
+const NUMBER_SEPARATORS: &[char] = &['/', '、', ';', '；', ',', '，', '|', '\n'];
+
+/// 分机号标记（较长的写在前面）
+const EXTENSION_MARKERS: &[&str] = &["分机号", "分机", "ext.", "ext", "转", "#", "x"];
+
+/// 拆出分机号：「0755-88886666转123」「0755 88886666 ext.123」
+fn split_extension(s: &str) -> (String, Option<String>) {
+    let lower = s.to_ascii_lowercase();
+    for marker in EXTENSION_MARKERS {
+        if let Some(pos) = lower.find(marker) {
+            let ext: String = lower[pos + marker.len()..].chars().filter(|c| c.is_ascii_digit()).collect();
+            if !ext.is_empty() && ext.len() <= 6 {
+                return (s[..pos].to_string(), Some(ext));
+            }
+        }
+    }
+    (s.to_string(), None)
+}
+
+/// 去除国家代码（+86 / 0086 / 86），返回国内号码；其他国家代码返回 None
+fn strip_country_code(digits: &str) -> Option<String> {
+    if let Some(rest) = digits.strip_prefix("+86").or_else(|| digits.strip_prefix("0086")) {
+        // 国际格式的固话省略了区号前的 0
+        return Some(if rest.starts_with('1') || rest.starts_with('0') {
+            rest.to_string()
+        } else {
+            format!("0{}", rest)
+        });
+    }
+    if digits.starts_with('+') {
+        return None;
+    }
+    if digits.len() == 13 && digits.starts_with("861") {
+        return Some(digits[2..].to_string());
+    }
+    Some(digits.to_string())
+}
+
+/// 规范化号码主体（不含分机号）
+fn normalize_main(digits: &str, ext: Option<&str>) -> Option<String> {
+    let with_ext = |number: String| match ext {
+        Some(e) => format!("{}转{}", number, e),
+        None => number,
+    };
+
+    let all_digits = digits.chars().all(|c| c.is_ascii_digit());
+    if !all_digits {
+        return None;
+    }
+
+    // 手机号（不带分机）
+    if digits.len() == 11 && digits.starts_with('1') && matches!(digits.as_bytes()[1], b'3'..=b'9') {
+        return if ext.is_none() { Some(digits.to_string()) } else { None };
+    }
+
+    // 400 / 800 服务号码
+    if digits.len() == 10 && (digits.starts_with("400") || digits.starts_with("800")) {
+        return Some(with_ext(digits.to_string()));
+    }
+
+    // 带区号的固话
+    if let Some(rest) = digits.strip_prefix('0') {
+        let area_len = if rest.starts_with('1') || rest.starts_with('2') { 2 } else { 3 };
+        if rest.len() > area_len {
+            let (area, local) = rest.split_at(area_len);
+            if (7..=8).contains(&local.len()) && !local.starts_with('0') && !local.starts_with('1') {
+                return Some(with_ext(format!("0{}-{}", area, local)));
+            }
+        }
+        return None;
+    }
+
+    // 不带区号的固话
+    if (7..=8).contains(&digits.len()) && !digits.starts_with('1') {
+        return Some(with_ext(digits.to_string()));
+    }
+
+    None
+}
+
+/// 规范化单个电话号码；无法识别时返回 None
+pub fn normalize_phone(value: &str) -> Option<String> {
+    let s = to_half_width(value.trim());
+    if s.is_empty() {
+        return None;
+    }
+
+    let (main, ext) = split_extension(&s);
+    let digits: String = main.chars().filter(|c| c.is_ascii_digit() || *c == '+').collect();
+    let digits = strip_country_code(&digits)?;
+    if let Some(number) = normalize_main(&digits, ext.as_deref()) {
+        return Some(number);
+    }
+
+    // 「010-62345678-123」：最后一段视为分机号
+    if ext.is_none() {
+        if let Some((head, tail)) = main.trim().rsplit_once('-') {
+            let tail = tail.trim();
+            if !tail.is_empty() && tail.len() <= 6 && tail.chars().all(|c| c.is_ascii_digit()) {
+                let digits: String = head.chars().filter(|c| c.is_ascii_digit() || *c == '+').collect();
+                let digits = strip_country_code(&digits)?;
+                if digits.starts_with('0') {
+                    return normalize_main(&digits, Some(tail));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// 仅保留数字和 + 号（无法识别的号码按原规则清理，交由验证规则标记错误）
+fn strip_to_digits(value: &str) -> String {
+    to_half_width(value)
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '+')
+        .collect()
+}
+
+/// 拆分单元格中的多个号码并逐个规范化（无法识别的号码保留清理后的原值）
+pub fn split_phone_numbers(value: &str) -> Vec<String> {
+    let text = to_half_width(value).replace('或', "/");
+    let mut numbers = Vec::new();
+
+    for part in text.split(NUMBER_SEPARATORS).map(str::trim).filter(|p| !p.is_empty()) {
+        if let Some(number) = normalize_phone(part) {
+            numbers.push(number);
+            continue;
+        }
+        // 空格分隔的多个号码：每一段都能识别时才拆分，避免误拆「138 0013 8000」
+        let tokens: Vec<Option<String>> = part.split_whitespace().map(normalize_phone).collect();
+        if tokens.len() > 1 && tokens.iter().all(|t| t.is_some()) {
+            numbers.extend(tokens.into_iter().flatten());
+            continue;
+        }
+        let stripped = strip_to_digits(part);
+        if !stripped.is_empty() {
+            numbers.push(stripped);
+        }
+    }
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mobile_numbers() {
+        assert_eq!(normalize_phone("13800138000").as_deref(), Some("13800138000"));
+        assert_eq!(normalize_phone("+86 138-0013-8000").as_deref(), Some("13800138000"));
+        assert_eq!(normalize_phone("0086 13800138000").as_deref(), Some("13800138000"));
+        assert_eq!(normalize_phone("8613800138000").as_deref(), Some("13800138000"));
+        assert_eq!(normalize_phone("１３８ ００１３ ８０００").as_deref(), Some("13800138000"));
+        assert_eq!(normalize_phone("+1 650 253 0000"), None);
+    }
+
+    #[test]
+    fn test_landlines_and_extensions() {
+        assert_eq!(normalize_phone("010-62345678").as_deref(), Some("010-62345678"));
+        assert_eq!(normalize_phone("(0755) 8888 6666").as_deref(), Some("0755-88886666"));
+        assert_eq!(normalize_phone("+86 755 88886666").as_deref(), Some("0755-88886666"));
+        assert_eq!(normalize_phone("0755-88886666转123").as_deref(), Some("0755-88886666转123"));
+        assert_eq!(normalize_phone("0755 88886666 ext.123").as_deref(), Some("0755-88886666转123"));
+        assert_eq!(normalize_phone("010-62345678-8001").as_deref(), Some("010-62345678转8001"));
+        assert_eq!(normalize_phone("88886666").as_deref(), Some("88886666"));
+        assert_eq!(normalize_phone("400-800-8888").as_deref(), Some("4008008888"));
+        assert_eq!(normalize_phone("12345"), None);
+    }
+
+    #[test]
+    fn test_split_multiple_numbers() {
+        assert_eq!(
+            split_phone_numbers("13800138000 / 13900139000"),
+            vec!["13800138000", "13900139000"]
+        );
+        assert_eq!(
+            split_phone_numbers("13800138000、0755-88886666"),
+            vec!["13800138000", "0755-88886666"]
+        );
+        assert_eq!(
+            split_phone_numbers("13800138000 13900139000"),
+            vec!["13800138000", "13900139000"]
+        );
+        assert_eq!(split_phone_numbers("138 0013 8000"), vec!["13800138000"]);
+        assert_eq!(split_phone_numbers("电话：abc123"), vec!["123"]);
+        assert!(split_phone_numbers("  ").is_empty());
+    }
+}
@@ -1,20 +1,19 @@
 // 数据处理 Tauri Commands
 //
-// 核心处理流程：AI 列映射 + 本地验证导入
+// 核心处理流程：AI / 规则列映射 + 本地验证导入
 // 使用 Tauri 事件系统推送进度
 
-use calamine::{open_workbook_auto, Reader, Data};
-use regex::Regex;
+use calamine::Data;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection,
-    EntityTrait, QueryFilter, QueryOrder, Set, Statement,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection,
+    EntityTrait, QueryFilter, QueryOrder, Set,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, LazyLock};
 
-use super::tasks::upsert_file_progress;
+use super::tasks::{upsert_file_progress, FileProgressUpdate};
 
 /// 将一行数据格式化为索引字符串，格式：1:列1内容;2:列2内容;...n:列n内容;
 fn format_row_indexed(row: &[String]) -> String {
@@ -36,31 +35,290 @@ fn get_field_type_rules(field_type: &str) -> &'static str {
         "number" => "数据应为纯数字、整数或小数；含字母或特殊符号的编号不是数字字段",
         "id_card" => "数据应为15位纯数字或18位（前17位数字+最后1位数字或X）的身份证号格式",
         "url" => "数据必须以 http:// 或 https:// 开头",
+        "enum" => "数据取值应为少数固定选项（或其同义写法，如 男/M/male）；取值各不相同的自由文本不是枚举",
+        "boolean" => "数据应为是/否、Y/N、true/false、1/0、✓/✗ 等二值写法",
+        "currency" => "数据应为金额，可带 ¥、$、元、USD 等币种符号或万/亿单位；编号、数量不是金额",
+        "list" => "一个单元格包含多个值，以逗号、顿号、分号、斜杠等分隔",
         "text" => "通用文本字段，列名语义匹配即可，但不应映射已被其他类型明确拒绝的列",
         _ => "根据列名语义和样本数据内容综合判断"
     }
 }
 use tauri::{AppHandle, Emitter};
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
 
 use crate::backend::infrastructure::{
     config::decrypt,
     persistence::models::{
-        task, ProcessingTask, field,
-        AiConfig as AiConfigModel, Project, record,
+        task, ProcessingTask, field, ai_config, task_file_progress, TaskFileProgress,
+        AiConfig as AiConfigModel, Project,
     },
 };
 use field::Model as FieldModel;
-use super::ai_utils::{call_ai_stream, extract_json};
-use super::ai_service::FieldDefinition;
+use super::ai_utils::{call_ai_stream, extract_json, AiSettings};
+use super::ai_extractor::{build_extraction_groups, AiUsageCounter, AiUsageStats};
+use super::data_utils::{compile_computed_fields, FieldTypeOptions, RowCleaner};
+use super::field_transforms::{field_pipeline, TransformPipeline};
+use super::ai_service::{ColumnMappingResponse, FieldDefinition, FieldMapping};
+use super::mapping_templates::{find_mapping_template, save_mapping_template};
+use super::mapping_cache::{field_definition_hash, find_cached_mapping, save_cached_mapping};
+use super::rule_mapper::map_columns_by_rules;
+use super::import_writer::{ImportCounts, ImportWriter, PendingRecord, TaskCheckpoint};
+use super::sheet_reader::{list_sheet_names, SheetStream};
+use super::header_detector::{detect_header_block, fill_merged_cells, HEADER_AREA_ROWS};
 
 // ============ 任务控制 ============
 
+#[derive(Default)]
 struct TaskControl {
     paused: AtomicBool,
     cancelled: AtomicBool,
+    /// 是否正在等待人工确认列映射
+    awaiting_review: AtomicBool,
+    /// 人工确认后的列映射（由 confirm_column_mapping 写入）
+    reviewed_mapping: std::sync::Mutex<Option<ColumnMappingResponse>>,
+    /// 确认映射或取消时唤醒等待确认的导入
+    review_notify: Notify,
+    /// AI 逐行提取的调用用量
+    ai_usage: AiUsageCounter,
 }
 
+impl TaskControl {
+    /// 取消任务（等待映射确认的导入立即结束）
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.review_notify.notify_one();
+    }
+
+    /// 提交人工确认的列映射并唤醒导入
+    fn submit_review(&self, mapping: ColumnMappingResponse) {
+        *self.reviewed_mapping.lock().unwrap() = Some(mapping);
+        self.review_notify.notify_one();
+    }
+
+    /// 等待人工确认的列映射，任务取消时返回 None
+    async fn wait_for_review(&self) -> Option<ColumnMappingResponse> {
+        loop {
+            if self.cancelled.load(Ordering::SeqCst) {
+                return None;
+            }
+            if let Some(mapping) = self.reviewed_mapping.lock().unwrap().take() {
+                return Some(mapping);
+            }
+            self.review_notify.notified().await;
+        }
+    }
+}
+
+/// 列映射模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum MappingMode {
+    /// 仅使用 AI 分析
+    #[default]
+    Ai,
+    /// 仅使用本地规则匹配（无需 AI 配置）
+    Rules,
+    /// 优先 AI，AI 不可用或调用失败时回退到规则匹配
+    AiWithRulesFallback,
+}
+
+impl MappingMode {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "ai" => Ok(Self::Ai),
+            "rules" => Ok(Self::Rules),
+            "ai_with_rules_fallback" => Ok(Self::AiWithRulesFallback),
+            _ => Err(format!("未知的列映射模式: {}", s)),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ai => "ai",
+            Self::Rules => "rules",
+            Self::AiWithRulesFallback => "ai_with_rules_fallback",
+        }
+    }
+}
+
+/// 处理选项（start_processing 传入）
+#[derive(Debug, Clone, Default)]
+struct ProcessingOptions {
+    /// 列映射完成后暂停，等待人工确认/修改映射
+    review_mapping: bool,
+    /// 列映射模式
+    mapping_mode: MappingMode,
+}
+
+/// 单个文件的处理范围（start_processing 传入，按 file_path 匹配）
+///
+/// 行号均从 0 计数，与 preview_excel 返回的行下标一致
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileSpec {
+    pub file_path: String,
+    /// 仅处理这些 Sheet（为空表示全部）
+    #[serde(default)]
+    pub include_sheets: Vec<String>,
+    /// 跳过这些 Sheet（如汇总页、透视表）
+    #[serde(default)]
+    pub exclude_sheets: Vec<String>,
+    /// 指定表头行（不指定时由列映射自动识别）
+    pub header_row: Option<usize>,
+    /// 数据起始行（含）
+    pub start_row: Option<usize>,
+    /// 数据结束行（含），之后的行（如表尾备注）不导入
+    pub end_row: Option<usize>,
+    /// 连续空行达到该数量时结束当前 Sheet（默认 10，0 表示不因空行结束）
+    pub empty_row_threshold: Option<usize>,
+}
+
+impl FileSpec {
+    fn includes_sheet(&self, sheet_name: &str) -> bool {
+        (self.include_sheets.is_empty() || self.include_sheets.iter().any(|s| s == sheet_name))
+            && !self.exclude_sheets.iter().any(|s| s == sheet_name)
+    }
+}
+
+/// 开始处理请求
+#[derive(Debug, Deserialize)]
+pub struct StartProcessingRequest {
+    pub project_id: i32,
+    pub file_paths: Vec<String>,
+    /// 不指定时使用默认 AI 配置
+    pub ai_config_id: Option<i32>,
+    /// 映射完成后暂停，等待人工确认
+    #[serde(default)]
+    pub review_mapping: bool,
+    /// ai / rules / ai_with_rules_fallback；不指定时有 AI 配置用 AI，否则用规则匹配
+    pub mapping_mode: Option<String>,
+    #[serde(default)]
+    pub file_specs: Vec<FileSpec>,
+}
+
+/// 任务的处理参数（processing_tasks.options），中断后按原参数继续
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TaskOptions {
+    file_paths: Vec<String>,
+    #[serde(default)]
+    file_specs: Vec<FileSpec>,
+    /// 实际使用的 AI 配置（不需要 AI 时为空）
+    ai_config_id: Option<i32>,
+    review_mapping: bool,
+    mapping_mode: String,
+}
+
+/// 从检查点继续时已完成的进度（由 task_file_progress 与检查点构建）
+#[derive(Debug, Default)]
+struct ResumeState {
+    checkpoint: Option<TaskCheckpoint>,
+    /// 已完成的文件（完整路径）及其计数
+    done_files: HashMap<String, ImportCounts>,
+    /// 已完成的 Sheet（文件完整路径, Sheet 名）及其计数
+    done_sheets: HashMap<(String, String), ImportCounts>,
+}
+
+impl ResumeState {
+    /// 由任务的检查点与文件进度构建
+    async fn load(db: &DatabaseConnection, task_model: &task::Model) -> Result<Self, String> {
+        let checkpoint = task_model.checkpoint.as_deref()
+            .map(serde_json::from_str::<TaskCheckpoint>)
+            .transpose()
+            .map_err(|e| format!("检查点解析失败: {}", e))?;
+
+        let progress = TaskFileProgress::find()
+            .filter(task_file_progress::Column::TaskId.eq(&task_model.id))
+            .all(db)
+            .await
+            .map_err(|e| format!("数据库错误: {}", e))?;
+
+        let mut resume = ResumeState { checkpoint, ..Default::default() };
+        for p in progress {
+            let Some(file_path) = p.file_path else {
+                continue;
+            };
+            let counts = ImportCounts {
+                rows: p.total_rows,
+                success: p.success_count,
+                errors: p.error_count,
+                updated: p.updated_count,
+                merged: p.merged_count,
+            };
+            match p.sheet_name {
+                None if p.file_phase == "done" => {
+                    resume.done_files.insert(file_path, counts);
+                }
+                Some(sheet) if p.sheet_phase.as_deref() == Some("done") => {
+                    resume.done_sheets.insert((file_path, sheet), counts);
+                }
+                _ => {}
+            }
+        }
+        Ok(resume)
+    }
+
+    /// 当前文件中已完成 Sheet 的计数之和
+    fn file_counts(&self, file_path: &str) -> ImportCounts {
+        self.done_sheets
+            .iter()
+            .filter(|((file, _), _)| file == file_path)
+            .fold(ImportCounts::default(), |acc, (_, c)| add_counts(acc, *c))
+    }
+
+    /// 中断时正在处理的 Sheet 的检查点
+    fn sheet_checkpoint(&self, file_index: usize, file_name: &str, sheet_name: &str) -> Option<&TaskCheckpoint> {
+        self.checkpoint.as_ref().filter(|c| {
+            c.file_index == file_index && c.file_name == file_name && c.sheet_name == sheet_name
+        })
+    }
+}
+
+fn add_counts(a: ImportCounts, b: ImportCounts) -> ImportCounts {
+    ImportCounts {
+        rows: a.rows + b.rows,
+        success: a.success + b.success,
+        errors: a.errors + b.errors,
+        updated: a.updated + b.updated,
+        merged: a.merged + b.merged,
+    }
+}
+
+/// 后台处理任务的输入（新任务与继续中断的任务共用）
+struct ProcessingJob {
+    task_id: String,
+    project: crate::backend::infrastructure::persistence::models::project::Model,
+    fields: Vec<FieldModel>,
+    file_paths: Vec<String>,
+    file_specs: Vec<FileSpec>,
+    ai: Option<AiSettings>,
+    options: ProcessingOptions,
+    resume: Option<ResumeState>,
+}
+
+/// 单行的处理结果
+enum RowOutcome {
+    /// 新增成功记录
+    Insert,
+    /// 去重策略 update：覆盖已有记录
+    Update(i32, serde_json::Map<String, serde_json::Value>),
+    /// 去重策略 merge：补全已有记录的空字段（已有记录 ID、原 data、合并后的 data）
+    Merge(i32, serde_json::Map<String, serde_json::Value>, serde_json::Value),
+    /// 被拒绝的行（状态、原因）
+    Reject(&'static str, String),
+}
+
+/// 字段值的来源（按 Sheet 确定）
+#[derive(Debug, Clone, Copy)]
+enum FieldSource {
+    /// 映射到的列
+    Column(usize),
+    /// 未映射，仅由转换管道生成（如默认值、拼接其他列）
+    Unmapped,
+    /// 由 AI 从来源列的自由文本中提取（提取分组下标）
+    AiExtract(usize),
+}
+
+/// 默认的连续空行阈值
+const DEFAULT_EMPTY_ROW_THRESHOLD: usize = 10;
+
 static ACTIVE_TASKS: LazyLock<RwLock<HashMap<String, Arc<TaskControl>>>> =
     LazyLock::new(|| RwLock::new(HashMap::new()));
 
@@ -84,12 +342,27 @@ pub struct ProcessingEvent {
     pub success_count: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_count: Option<i32>,
+    /// 去重策略 update 覆盖的记录数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_count: Option<i32>,
+    /// 去重策略 merge 补全的记录数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merged_count: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub confidence: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mappings: Option<HashMap<String, String>>,
+    /// 映射来源：ai / cache / rules / template / manual
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping_source: Option<String>,
+    /// 表头列名（mapping_review 事件）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Vec<String>>,
+    /// 完整列映射结果（mapping_review 事件，供人工确认/修改）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column_mapping: Option<ColumnMappingResponse>,
     /// Sheet 级别的成功计数（sheet_complete 事件）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sheet_success_count: Option<i32>,
@@ -99,14 +372,29 @@ pub struct ProcessingEvent {
     /// Sheet 级别的总行数（sheet_complete 事件）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sheet_total_rows: Option<i32>,
+    /// Sheet 级别的更新计数（sheet_complete 事件）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sheet_updated_count: Option<i32>,
+    /// Sheet 级别的合并计数（sheet_complete 事件）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sheet_merged_count: Option<i32>,
+    /// AI 逐行提取的累计用量（ai_extracting / completed 事件）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ai_usage: Option<AiUsageStats>,
 }
 
 impl ProcessingEvent {
-    fn emit(&self, app: &AppHandle) {
-        let _ = app.emit("processing-progress", self);
+    fn emit(&self, sink: &EventSink) {
+        if let Some(app) = &sink.0 {
+            let _ = app.emit("processing-progress", self);
+        }
     }
 }
 
+/// 进度事件的推送目标（应用中为 Tauri 事件；单元测试中不推送）
+#[derive(Clone)]
+struct EventSink(Option<AppHandle>);
+
 // ============ 响应结构 ============
 
 #[derive(Debug, Serialize)]
@@ -120,7 +408,7 @@ pub struct StartProcessingResponse {
 
 // ============ 辅助函数 ============
 
-fn data_to_string(data: &Data) -> String {
+pub(crate) fn data_to_string(data: &Data) -> String {
     match data {
         Data::Int(i) => i.to_string(),
         Data::Float(f) => {
@@ -132,7 +420,19 @@ fn data_to_string(data: &Data) -> String {
         }
         Data::String(s) => s.clone(),
         Data::Bool(b) => b.to_string(),
-        Data::DateTime(dt) => dt.to_string(),
+        Data::DateTime(dt) => {
+            // 日期单元格：整日输出 YYYY-MM-DD，带时间输出 YYYY-MM-DD HH:MM:SS；时长输出 H:MM:SS
+            if dt.is_duration() {
+                let secs = dt.as_duration().map(|d| d.num_seconds()).unwrap_or(0);
+                format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+            } else {
+                match dt.as_datetime() {
+                    Some(t) if t.time() == chrono::NaiveTime::MIN => t.format("%Y-%m-%d").to_string(),
+                    Some(t) => t.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    None => dt.to_string(),
+                }
+            }
+        }
         Data::DateTimeIso(s) => s.clone(),
         Data::DurationIso(s) => s.clone(),
         Data::Error(e) => format!("#ERR:{:?}", e),
@@ -140,105 +440,6 @@ fn data_to_string(data: &Data) -> String {
     }
 }
 
-fn validate_value(value: &str, validation_rule: Option<&str>) -> bool {
-    if value.trim().is_empty() {
-        return true; // 空值通过（由 required 字段处理）
-    }
-    if let Some(rule) = validation_rule {
-        if let Ok(re) = Regex::new(rule) {
-            return re.is_match(value);
-        }
-    }
-    true
-}
-
-/// 根据字段类型清理数据值
-///
-/// 清理规则：
-/// - 通用：去除首尾空格、换行符、制表符
-/// - phone: 仅保留数字和 + 号
-/// - email: 去除空格、换行，转小写
-/// - text/其他: 压缩连续空白为单个空格
-fn clean_value(value: &str, field_type: &str) -> String {
-    // 第一步：通用清理 - 去除首尾空白和控制字符
-    let mut cleaned = value
-        .chars()
-        .map(|c| match c {
-            '\r' | '\n' | '\t' => ' ',  // 换行、制表符转为空格
-            c if c.is_control() => ' ', // 其他控制字符转为空格
-            c => c,
-        })
-        .collect::<String>();
-
-    // 根据字段类型进行特定清理
-    match field_type {
-        "phone" => {
-            // 手机号：仅保留数字和 + 号
-            cleaned = cleaned
-                .chars()
-                .filter(|c| c.is_ascii_digit() || *c == '+')
-                .collect();
-        }
-        "company" => {
-            // 公司名称：压缩空白；若清理后为纯数字（如 ID、编号），视为无效值返回空
-            let mut result = String::new();
-            let mut prev_space = false;
-            for c in cleaned.chars() {
-                if c.is_whitespace() {
-                    if !prev_space { result.push(' '); prev_space = true; }
-                } else {
-                    result.push(c);
-                    prev_space = false;
-                }
-            }
-            cleaned = result.trim().to_string();
-            // 纯数字（含空格分隔）不是公司名称，清空
-            if !cleaned.is_empty() && cleaned.chars().all(|c| c.is_ascii_digit() || c.is_whitespace()) {
-                cleaned = String::new();
-            }
-        }
-        "email" => {
-            // 邮箱：去除所有空格，转小写
-            cleaned = cleaned.chars().filter(|c| !c.is_whitespace()).collect();
-            cleaned = cleaned.to_lowercase();
-        }
-        "number" | "id_card" => {
-            // 数字、身份证：仅保留数字和字母
-            cleaned = cleaned
-                .chars()
-                .filter(|c| c.is_ascii_alphanumeric())
-                .collect();
-        }
-        "date" => {
-            // 日期：去除空格，保留数字、日期分隔符
-            cleaned = cleaned
-                .chars()
-                .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '/' | '.' | ':'))
-                .collect();
-        }
-        _ => {
-            // 默认文本类型：压缩连续空白为单个空格
-            let mut result = String::new();
-            let mut prev_space = false;
-            for c in cleaned.chars() {
-                if c.is_whitespace() {
-                    if !prev_space {
-                        result.push(' ');
-                        prev_space = true;
-                    }
-                } else {
-                    result.push(c);
-                    prev_space = false;
-                }
-            }
-            cleaned = result;
-        }
-    }
-
-    // 最后再次 trim
-    cleaned.trim().to_string()
-}
-
 // ============ Tauri Commands ============
 
 /// 开始处理文件
@@ -246,18 +447,26 @@ fn clean_value(value: &str, field_type: &str) -> String {
 pub async fn start_processing(
     app: AppHandle,
     db: tauri::State<'_, Arc<DatabaseConnection>>,
-    project_id: i32,
-    file_paths: Vec<String>,
-    ai_config_id: Option<i32>,
+    request: StartProcessingRequest,
 ) -> Result<StartProcessingResponse, String> {
+    let StartProcessingRequest {
+        project_id,
+        file_paths,
+        ai_config_id,
+        review_mapping,
+        mapping_mode,
+        file_specs,
+    } = request;
+
     // 获取数据库连接的克隆
     let db_conn = db.inner().clone();
 
-    // 1. 验证项目
+    // 1. 验证项目（回收站中的项目不能导入）
     let project = Project::find_by_id(project_id)
         .one(db_conn.as_ref())
         .await
         .map_err(|e| format!("数据库错误: {}", e))?
+        .filter(|p| p.deleted_at.is_none())
         .ok_or_else(|| format!("项目 {} 不存在", project_id))?;
 
     // 2. 获取字段定义
@@ -273,27 +482,37 @@ pub async fn start_processing(
         return Err("项目没有定义字段".to_string());
     }
 
-    // 3. 获取 AI 配置
-    let ai_config = if let Some(config_id) = ai_config_id {
-        AiConfigModel::find_by_id(config_id)
+    // 3. 获取 AI 配置（规则映射模式且没有 AI 提取字段时不需要）
+    let requested_mode = mapping_mode.as_deref().map(MappingMode::parse).transpose()?;
+    let has_ai_extract = fields.iter().any(|f| f.ai_extract_source.is_some());
+    let ai_config = if requested_mode == Some(MappingMode::Rules) && !has_ai_extract {
+        None
+    } else if let Some(config_id) = ai_config_id {
+        Some(AiConfigModel::find_by_id(config_id)
             .one(db_conn.as_ref())
             .await
             .map_err(|e| format!("数据库错误: {}", e))?
-            .ok_or_else(|| format!("AI 配置 {} 不存在", config_id))?
+            .ok_or_else(|| format!("AI 配置 {} 不存在", config_id))?)
     } else {
         // 使用默认配置
         let configs = AiConfigModel::find()
             .all(db_conn.as_ref())
             .await
             .map_err(|e| format!("数据库错误: {}", e))?;
-        configs.into_iter()
-            .find(|c| c.is_default)
-            .ok_or_else(|| "没有默认 AI 配置".to_string())?
+        configs.into_iter().find(|c| c.is_default)
+    };
+
+    // 未指定模式时：有 AI 配置用 AI，否则用规则匹配
+    let mapping_mode = match (requested_mode, &ai_config) {
+        (Some(MappingMode::Ai), None) => return Err("没有默认 AI 配置".to_string()),
+        (Some(mode), _) => mode,
+        (None, Some(_)) => MappingMode::Ai,
+        (None, None) => MappingMode::Rules,
     };
 
     // 4. 解密 API Key
-    let api_key = decrypt(&ai_config.api_key)
-        .map_err(|e| format!("解密失败: {}", e))?;
+    let ai_config_id = ai_config.as_ref().map(|c| c.id);
+    let ai_settings = ai_config.map(ai_settings_from).transpose()?;
 
     // 5. 创建任务
     let task_id = uuid::Uuid::new_v4().to_string();
@@ -314,16 +533,27 @@ pub async fn start_processing(
     // 提取源文件名列表
     let source_file_names: Vec<String> = file_paths
         .iter()
-        .map(|p| {
-            std::path::Path::new(p)
-                .file_name()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_else(|| "unknown".to_string())
-        })
+        .map(String::as_str)
+        .map(file_name_of)
         .collect();
     let source_files_json = serde_json::to_string(&source_file_names)
         .unwrap_or_else(|_| "[]".to_string());
 
+    // 保存处理参数，中断后据此从检查点继续
+    let options = ProcessingOptions {
+        review_mapping,
+        mapping_mode,
+    };
+    let task_options = TaskOptions {
+        file_paths: file_paths.clone(),
+        file_specs: file_specs.clone(),
+        ai_config_id,
+        review_mapping: options.review_mapping,
+        mapping_mode: mapping_mode.as_str().to_string(),
+    };
+    let options_json = serde_json::to_string(&task_options)
+        .map_err(|e| format!("JSON 序列化错误: {}", e))?;
+
     let new_task = task::ActiveModel {
         id: Set(task_id.clone()),
         project_id: Set(project_id),
@@ -336,6 +566,8 @@ pub async fn start_processing(
         error_count: Set(0),
         batch_number: Set(Some(batch_number.clone())),
         source_files: Set(Some(source_files_json)),
+        options: Set(Some(options_json)),
+        checkpoint: Set(None),
         created_at: Set(now),
         updated_at: Set(None),
     };
@@ -345,70 +577,197 @@ pub async fn start_processing(
         .await
         .map_err(|e| format!("数据库错误: {}", e))?;
 
-    // 6. 注册任务控制
-    let control = Arc::new(TaskControl {
-        paused: AtomicBool::new(false),
-        cancelled: AtomicBool::new(false),
-    });
+    // 6. 启动后台处理
+    spawn_processing(app, db_conn, ProcessingJob {
+        task_id: task_id.clone(),
+        project,
+        fields,
+        file_paths,
+        file_specs,
+        ai: ai_settings,
+        options,
+        resume: None,
+    }).await;
+
+    Ok(StartProcessingResponse {
+        task_id,
+        batch_number,
+        project_id,
+        status: "processing".to_string(),
+        source_files: source_file_names,
+    })
+}
+
+/// 从检查点继续被中断的任务
+///
+/// 按创建任务时保存的参数重新处理：已完成的文件和 Sheet 直接跳过，
+/// 中断时正在处理的 Sheet 复用已保存的列映射，从最后落库的行之后继续导入
+#[tauri::command]
+pub async fn resume_interrupted_task(
+    app: AppHandle,
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    task_id: String,
+) -> Result<StartProcessingResponse, String> {
+    let db_conn = db.inner().clone();
+
+    let task_model = ProcessingTask::find_by_id(&task_id)
+        .one(db_conn.as_ref())
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+        .ok_or_else(|| format!("任务不存在: {}", task_id))?;
+
+    if task_model.status != "interrupted" {
+        return Err(format!("只能继续已中断的任务（当前状态: {}）", task_model.status));
+    }
+    if ACTIVE_TASKS.read().await.contains_key(&task_id) {
+        return Err("任务正在运行中".to_string());
+    }
+
+    // 1. 读取创建任务时的处理参数
+    let task_options: TaskOptions = task_model.options.as_deref()
+        .ok_or_else(|| "任务未保存处理参数，无法继续，请重置后重新导入".to_string())
+        .and_then(|s| serde_json::from_str(s).map_err(|e| format!("处理参数解析失败: {}", e)))?;
+    let mapping_mode = MappingMode::parse(&task_options.mapping_mode)?;
+
+    // 2. 验证项目并获取字段定义
+    let project = Project::find_by_id(task_model.project_id)
+        .one(db_conn.as_ref())
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+        .filter(|p| p.deleted_at.is_none())
+        .ok_or_else(|| format!("项目 {} 不存在", task_model.project_id))?;
+
+    let fields = field::Entity::find()
+        .filter(field::Column::ProjectId.eq(project.id))
+        .filter(field::Column::IsDeleted.eq(false))
+        .order_by(field::Column::DisplayOrder, sea_orm::Order::Asc)
+        .all(db_conn.as_ref())
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    if fields.is_empty() {
+        return Err("项目没有定义字段".to_string());
+    }
+
+    // 3. 获取 AI 配置
+    let ai_settings = match task_options.ai_config_id {
+        Some(config_id) => Some(ai_settings_from(
+            AiConfigModel::find_by_id(config_id)
+                .one(db_conn.as_ref())
+                .await
+                .map_err(|e| format!("数据库错误: {}", e))?
+                .ok_or_else(|| format!("AI 配置 {} 不存在", config_id))?,
+        )?),
+        None => None,
+    };
+
+    // 4. 由检查点与文件进度构建已完成的进度
+    let resume = ResumeState::load(db_conn.as_ref(), &task_model).await?;
+
+    // 5. 更新任务状态并启动后台处理
+    update_task_status(&db_conn, &task_id, "processing".to_string()).await?;
+
+    tracing::info!(
+        "Resuming interrupted task {}: {} files done, {} sheets done, checkpoint: {:?}",
+        task_id,
+        resume.done_files.len(),
+        resume.done_sheets.len(),
+        resume.checkpoint.as_ref().map(|c| (&c.file_name, &c.sheet_name, c.last_row)),
+    );
+
+    let source_files = task_model.source_files.as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+
+    spawn_processing(app, db_conn, ProcessingJob {
+        task_id: task_id.clone(),
+        project,
+        fields,
+        file_paths: task_options.file_paths,
+        file_specs: task_options.file_specs,
+        ai: ai_settings,
+        options: ProcessingOptions {
+            review_mapping: task_options.review_mapping,
+            mapping_mode,
+        },
+        resume: Some(resume),
+    }).await;
+
+    Ok(StartProcessingResponse {
+        task_id,
+        batch_number: task_model.batch_number.unwrap_or_default(),
+        project_id: task_model.project_id,
+        status: "processing".to_string(),
+        source_files,
+    })
+}
+
+/// 校验人工确认的列映射：字段须为项目中可映射的字段（非计算、非 AI 提取），
+/// 每个字段最多映射一次
+fn validate_reviewed_mapping(header_row: i32, mappings: &[FieldMapping], fields: &[FieldModel]) -> Result<(), String> {
+    if header_row < 0 {
+        return Err(format!("无效的表头行: {}", header_row));
+    }
+    let mut seen_fields = std::collections::HashSet::new();
+    for m in mappings {
+        let mappable = fields.iter().any(|f| {
+            f.field_name == m.field_name && f.expression.is_none() && f.ai_extract_source.is_none()
+        });
+        if !mappable {
+            return Err(format!("字段 {} 不存在或不可映射", m.field_name));
+        }
+        if m.column_index < 0 {
+            return Err(format!("字段 {} 的列下标无效: {}", m.field_name, m.column_index));
+        }
+        if !seen_fields.insert(m.field_name.as_str()) {
+            return Err(format!("字段 {} 重复映射", m.field_name));
+        }
+    }
+    Ok(())
+}
+
+/// 由 AI 配置构建调用参数（解密 API Key）
+fn ai_settings_from(config: ai_config::Model) -> Result<AiSettings, String> {
+    Ok(AiSettings {
+        api_key: decrypt(&config.api_key)
+            .map_err(|e| format!("解密失败: {}", e))?,
+        api_url: config.api_url,
+        model_name: config.model_name,
+        temperature: config.temperature,
+        max_tokens: config.max_tokens,
+    })
+}
+
+/// 注册任务控制并在后台运行处理流程
+async fn spawn_processing(app: AppHandle, db: Arc<DatabaseConnection>, job: ProcessingJob) {
+    let app = EventSink(Some(app));
+    let control = Arc::new(TaskControl::default());
     {
         let mut tasks = ACTIVE_TASKS.write().await;
-        tasks.insert(task_id.clone(), control.clone());
+        tasks.insert(job.task_id.clone(), control.clone());
     }
 
-    // 7. 启动后台处理
-    let app_for_spawn = app.clone();
-    let project_clone = project.clone();
-    let fields_clone = fields.clone();
-    let api_url = ai_config.api_url.clone();
-    let api_key_clone = api_key.clone();
-    let model_name = ai_config.model_name.clone();
-    let temperature = ai_config.temperature;
-    let max_tokens = ai_config.max_tokens;
-    let task_id_clone = task_id.clone();
-
     tokio::spawn(async move {
-        let task_id_inner = task_id_clone;
-        let result = process_files(
-            app_for_spawn,
-            db_conn.clone(),
-            &task_id_inner,
-            &project_clone,
-            &fields_clone,
-            &file_paths,
-            &api_url,
-            &api_key_clone,
-            &model_name,
-            temperature,
-            max_tokens,
-            control.clone(),
-        ).await;
+        let result = process_files(app.clone(), db.clone(), &job, control).await;
 
         // 清理任务控制
         {
             let mut tasks = ACTIVE_TASKS.write().await;
-            tasks.remove(&task_id_inner);
+            tasks.remove(&job.task_id);
         }
 
         // 更新最终状态
         if let Err(e) = result {
-            let _ = update_task_error(&db_conn.clone(), &task_id_inner, &e).await;
+            let _ = update_task_error(&db, &job.task_id, &e).await;
             let event = ProcessingEvent {
                 event: "error".to_string(),
-                task_id: task_id_inner.clone(),
+                task_id: job.task_id.clone(),
                 message: Some(e),
                 ..Default::default()
             };
             event.emit(&app);
         }
     });
-
-    Ok(StartProcessingResponse {
-        task_id,
-        batch_number,
-        project_id,
-        status: "processing".to_string(),
-        source_files: source_file_names,
-    })
 }
 
 async fn update_task_error(db: &Arc<DatabaseConnection>, task_id: &str, _error: &str) -> Result<(), String> {
@@ -428,34 +787,50 @@ async fn update_task_error(db: &Arc<DatabaseConnection>, task_id: &str, _error:
     Ok(())
 }
 
+/// 文件路径中的文件名（任务、记录与进度中显示的名称）
+fn file_name_of(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
 async fn process_files(
-    app: AppHandle,
+    app: EventSink,
     db: Arc<DatabaseConnection>,
-    task_id: &str,
-    project: &crate::backend::infrastructure::persistence::models::project::Model,
-    fields: &[FieldModel],
-    file_paths: &[String],
-    api_url: &str,
-    api_key: &str,
-    model_name: &str,
-    temperature: f32,
-    max_tokens: i32,
+    job: &ProcessingJob,
     control: Arc<TaskControl>,
 ) -> Result<(), String> {
-    let mut total_rows = 0i32;
-    let mut processed_rows = 0i32;
-    let mut success_count = 0i32;
-    let mut error_count = 0i32;
-
-    // 获取去重字段
+    let task_id = job.task_id.as_str();
+    let project = &job.project;
+    let file_paths = &job.file_paths;
+    let resume = job.resume.as_ref();
+
+    // 从检查点继续时，计数从已完成文件的结果开始累计
+    let done_counts = resume
+        .map(|r| {
+            file_paths.iter()
+                .filter_map(|p| r.done_files.get(p).copied())
+                .fold(ImportCounts::default(), add_counts)
+        })
+        .unwrap_or_default();
+    let mut total_rows = done_counts.rows;
+    let mut processed_rows = done_counts.rows;
+    let mut success_count = done_counts.success;
+    let mut error_count = done_counts.errors;
+    let mut updated_count = done_counts.updated;
+    let mut merged_count = done_counts.merged;
+
+    // 获取去重字段，创建写入器（去重索引每个任务只加载一次）
     let dedup_fields: Vec<i32> = if project.dedup_enabled {
-        fields.iter()
+        job.fields.iter()
             .filter(|f| f.is_dedup_key)
             .map(|f| f.id)
             .collect()
     } else {
         vec![]
     };
+    let mut writer = ImportWriter::new(db.as_ref(), task_id, dedup_fields).await?;
 
     for (file_idx, file_path) in file_paths.iter().enumerate() {
         // 检查取消状态
@@ -473,10 +848,19 @@ async fn process_files(
             }
         }
 
-        let file_name = std::path::Path::new(file_path)
-            .file_name()
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_else(|| "unknown".to_string());
+        let file_name = file_name_of(file_path);
+
+        // 从检查点继续：跳过已完成的文件
+        if resume.is_some_and(|r| r.done_files.contains_key(file_path)) {
+            ProcessingEvent {
+                event: "file_skipped".to_string(),
+                task_id: task_id.to_string(),
+                current_file: Some(file_name.clone()),
+                message: Some(format!("文件已完成，跳过: {}", file_name)),
+                ..Default::default()
+            }.emit(&app);
+            continue;
+        }
 
         // 发送文件开始事件
         ProcessingEvent {
@@ -491,57 +875,51 @@ async fn process_files(
         let _ = upsert_file_progress(
             &db,
             task_id,
-            &file_name,
+            file_path,
             None,  // sheet_name 为空表示文件级别
-            Some("processing"),
-            None,  // sheet_phase
-            None,  // ai_confidence
-            None,  // mapping_count
-            None,  // success_count
-            None,  // error_count
-            None,  // total_rows
-            None,  // error_message
+            FileProgressUpdate {
+                file_phase: Some("processing"),
+                ..Default::default()
+            },
         ).await;
 
         // 处理文件
-        let result = process_single_file(
-            &app,
-            &db,
-            task_id,
-            file_path.clone(),
-            &file_name,
-            fields,
-            api_url,
-            api_key,
-            model_name,
-            temperature,
-            max_tokens,
-            &dedup_fields,
-            project.dedup_enabled,
-            &control,
-        ).await;
+        let default_spec = FileSpec::default();
+        let spec = job.file_specs.iter()
+            .find(|s| &s.file_path == file_path)
+            .unwrap_or(&default_spec);
+        let result = process_single_file(&app, &db, job, file_idx, spec, &mut writer, &control).await;
+
+        // 文件处理结束（含出错/取消）时写入缓冲区中剩余的记录
+        let result = match writer.flush(db.as_ref()).await {
+            Ok(()) => result,
+            Err(e) => result.and(Err(e)),
+        };
 
         match result {
-            Ok((rows, success, errors)) => {
+            Ok((rows, success, errors, updated, merged)) => {
                 total_rows += rows;
                 processed_rows += rows;
                 success_count += success;
                 error_count += errors;
+                updated_count += updated;
+                merged_count += merged;
 
                 // 持久化：更新文件完成状态
                 let _ = upsert_file_progress(
                     &db,
                     task_id,
-                    &file_name,
+                    file_path,
                     None,
-                    Some("done"),
-                    None,  // sheet_phase
-                    None,  // ai_confidence
-                    None,  // mapping_count
-                    Some(success),
-                    Some(errors),
-                    Some(rows),
-                    None,  // error_message
+                    FileProgressUpdate {
+                        file_phase: Some("done"),
+                        success_count: Some(success),
+                        error_count: Some(errors),
+                        total_rows: Some(rows),
+                        updated_count: Some(updated),
+                        merged_count: Some(merged),
+                        ..Default::default()
+                    },
                 ).await;
             }
             Err(e) => {
@@ -551,16 +929,13 @@ async fn process_files(
                 let _ = upsert_file_progress(
                     &db,
                     task_id,
-                    &file_name,
+                    file_path,
                     None,
-                    Some("error"),
-                    None,  // sheet_phase
-                    None,  // ai_confidence
-                    None,  // mapping_count
-                    None,  // success_count
-                    None,  // error_count
-                    None,  // total_rows
-                    Some(&e),
+                    FileProgressUpdate {
+                        file_phase: Some("error"),
+                        error_message: Some(&e),
+                        ..Default::default()
+                    },
                 ).await;
 
                 ProcessingEvent {
@@ -584,6 +959,8 @@ async fn process_files(
             processed_rows: Some(processed_rows),
             success_count: Some(success_count),
             error_count: Some(error_count),
+            updated_count: Some(updated_count),
+            merged_count: Some(merged_count),
             message: Some(format!("文件处理完成: {} 行", processed_rows)),
             ..Default::default()
         }.emit(&app);
@@ -592,6 +969,15 @@ async fn process_files(
     // 更新任务为完成
     update_task_status(&db, task_id, "completed".to_string()).await?;
 
+    // AI 逐行提取的调用用量
+    let ai_usage = Some(control.ai_usage.snapshot()).filter(|usage| usage.calls > 0);
+    if let Some(usage) = &ai_usage {
+        tracing::info!(
+            "AI 提取用量: 调用 {} 次（失败 {} 次）, 文本 {} 条, tokens {} + {}",
+            usage.calls, usage.failed_calls, usage.cells, usage.prompt_tokens, usage.completion_tokens
+        );
+    }
+
     // 发送完成事件
     ProcessingEvent {
         event: "completed".to_string(),
@@ -599,7 +985,13 @@ async fn process_files(
         processed_rows: Some(processed_rows),
         success_count: Some(success_count),
         error_count: Some(error_count),
-        message: Some(format!("处理完成: 成功 {} 行, 失败 {} 行", success_count, error_count)),
+        updated_count: Some(updated_count),
+        merged_count: Some(merged_count),
+        message: Some(format!(
+            "处理完成: 成功 {} 行, 失败 {} 行, 更新 {} 行, 合并 {} 行",
+            success_count, error_count, updated_count, merged_count
+        )),
+        ai_usage,
         ..Default::default()
     }.emit(&app);
 
@@ -607,60 +999,107 @@ async fn process_files(
 }
 
 async fn process_single_file(
-    app: &AppHandle,
+    app: &EventSink,
     db: &Arc<DatabaseConnection>,
-    task_id: &str,
-    file_path: String,
-    file_name: &str,
-    fields: &[FieldModel],
-    api_url: &str,
-    api_key: &str,
-    model_name: &str,
-    temperature: f32,
-    max_tokens: i32,
-    dedup_fields: &[i32],
-    dedup_enabled: bool,
+    job: &ProcessingJob,
+    file_index: usize,
+    spec: &FileSpec,
+    writer: &mut ImportWriter,
     control: &Arc<TaskControl>,
-) -> Result<(i32, i32, i32), String> {
-    let mut total_rows = 0i32;
-    let mut success_count = 0i32;
-    let error_count = 0i32;
-
-    // 使用 spawn_blocking 读取 Excel 并处理所有 sheets
-    let result = tokio::task::spawn_blocking(move || {
-        let mut workbook = open_workbook_auto(file_path)
-            .map_err(|e| format!("无法打开文件: {}", e))?;
-        let sheet_names = workbook.sheet_names().to_vec();
-        let mut all_rows: HashMap<String, Vec<Vec<String>>> = HashMap::new();
-
-        for sheet_name in &sheet_names {
-            let range = workbook.worksheet_range(sheet_name)
-                .map_err(|e| format!("无法读取 Sheet: {}", e))?;
-            let rows: Vec<Vec<String>> = range
-                .rows()
-                .map(|row| row.iter().map(data_to_string).collect())
-                .collect();
-            all_rows.insert(sheet_name.clone(), rows);
-        }
+) -> Result<(i32, i32, i32, i32, i32), String> {
+    let task_id = job.task_id.as_str();
+    let (project, fields, options) = (&job.project, job.fields.as_slice(), &job.options);
+    let ai = job.ai.as_ref();
+    let resume = job.resume.as_ref();
+    let file_path = job.file_paths[file_index].clone();
+    let file_name = &file_name_of(&file_path);
+    let dedup_strategy = project.dedup_strategy.as_str();
+
+    // 字段类型配置（如日期的日/月顺序），按字段 ID 预先解析
+    let field_options: HashMap<i32, FieldTypeOptions> = fields.iter()
+        .map(|f| (f.id, FieldTypeOptions::from_field(f)))
+        .collect();
 
-        Ok::<_, String>((sheet_names, all_rows))
-    })
-    .await
-    .map_err(|e| format!("任务执行失败: {}", e))?
-    .map_err(|e| format!("无法打开文件: {}", e))?;
+    // 计算字段、AI 提取字段不对应表格中的列，不参与列映射，逐行按表达式求值 / 由 AI 提取
+    let computed_fields = compile_computed_fields(fields);
+    let mappable_fields: Vec<FieldModel> = fields.iter()
+        .filter(|f| f.expression.is_none() && f.ai_extract_source.is_none())
+        .cloned()
+        .collect();
 
-    let (sheet_names, mut all_rows) = result;
+    // 构建字段定义（AI 分析与映射缓存共用）
+    let field_defs: Vec<FieldDefinition> = mappable_fields.iter().map(|f| FieldDefinition {
+        field_name: f.field_name.clone(),
+        field_label: f.field_label.clone(),
+        field_type: f.field_type.clone(),
+        additional_requirement: f.additional_requirement.clone(),
+        extraction_hint: f.extraction_hint.clone(),
+    }).collect();
+    let field_hash = field_definition_hash(&field_defs);
+
+    // 从检查点继续时，计数从当前文件已完成 Sheet 的结果开始累计
+    let done_counts = resume.map(|r| r.file_counts(&file_path)).unwrap_or_default();
+    let mut total_rows = done_counts.rows;
+    let mut success_count = done_counts.success;
+    let mut error_count = done_counts.errors;
+    let mut updated_count = done_counts.updated;
+    let mut merged_count = done_counts.merged;
+
+    // 只读取 Sheet 名称，各 Sheet 的数据在处理时按分块流式读取
+    let path_for_names = file_path.clone();
+    let sheet_names = tokio::task::spawn_blocking(move || list_sheet_names(&path_for_names))
+        .await
+        .map_err(|e| format!("任务执行失败: {}", e))??;
 
     for sheet_name in sheet_names {
         // 检查取消状态
         if control.cancelled.load(Ordering::SeqCst) {
-            return Ok((total_rows, success_count, error_count));
+            return Ok((total_rows, success_count, error_count, updated_count, merged_count));
         }
 
+        // 按文件处理范围筛选 Sheet
+        if !spec.includes_sheet(&sheet_name) {
+            ProcessingEvent {
+                event: "sheet_skipped".to_string(),
+                task_id: task_id.to_string(),
+                current_file: Some(file_name.to_string()),
+                current_sheet: Some(sheet_name.clone()),
+                message: Some(format!("跳过 Sheet: {}", sheet_name)),
+                ..Default::default()
+            }.emit(app);
+            continue;
+        }
+
+        // 从检查点继续：跳过已完成的 Sheet，中断时正在处理的 Sheet 复用已保存的列映射
+        if resume.is_some_and(|r| r.done_sheets.contains_key(&(file_path.clone(), sheet_name.clone()))) {
+            ProcessingEvent {
+                event: "sheet_skipped".to_string(),
+                task_id: task_id.to_string(),
+                current_file: Some(file_name.to_string()),
+                current_sheet: Some(sheet_name.clone()),
+                message: Some(format!("Sheet 已完成，跳过: {}", sheet_name)),
+                ..Default::default()
+            }.emit(app);
+            continue;
+        }
+        let resumed = resume.and_then(|r| r.sheet_checkpoint(file_index, file_name, &sheet_name));
+
         // 记录 Sheet 开始时的基线值（用于计算当前 Sheet 的增量）
         let sheet_start_total = total_rows;
         let sheet_start_success = success_count;
         let sheet_start_error = error_count;
+        let sheet_start_updated = updated_count;
+        let sheet_start_merged = merged_count;
+
+        // 中断前已导入的行计入当前 Sheet
+        if let Some(checkpoint) = resumed {
+            let counts = checkpoint.sheet_counts;
+            total_rows += counts.rows;
+            success_count += counts.success;
+            error_count += counts.errors;
+            updated_count += counts.updated;
+            merged_count += counts.merged;
+        }
 
         // 发送 Sheet 开始事件
         ProcessingEvent {
@@ -676,123 +1115,287 @@ async fn process_single_file(
         let _ = upsert_file_progress(
             db,
             task_id,
-            file_name,
+            &file_path,
             Some(&sheet_name),
-            None,  // file_phase 不变
-            Some("ai_analyzing"),
-            None,  // ai_confidence
-            None,  // mapping_count
-            None,  // success_count
-            None,  // error_count
-            None,  // total_rows
-            None,  // error_message
+            FileProgressUpdate {
+                sheet_phase: Some("ai_analyzing"),
+                ..Default::default()
+            },
         ).await;
 
-        // 获取已读取的 Sheet 数据
-        let rows_data = match all_rows.remove(&sheet_name) {
-            Some(rows) => rows,
-            None => {
-                // Sheet 数据不存在，标记为完成（0 行）
-                let _ = upsert_file_progress(
-                    db, task_id, file_name, Some(&sheet_name),
-                    None, Some("done"), None, None,
-                    Some(0), Some(0), Some(0), None,
-                ).await;
+        // 流式读取 Sheet：首个分块用于表头识别与列映射，其余分块在导入时逐块读取
+        let mut sheet_stream = SheetStream::open(file_path.clone(), sheet_name.clone());
+        let mut rows_data = sheet_stream.next_chunk().await?.unwrap_or_default();
+
+        if rows_data.is_empty() {
+            // Sheet 为空，标记为完成（0 行）
+            let _ = upsert_file_progress(
+                db,
+                task_id,
+                &file_path,
+                Some(&sheet_name),
+                FileProgressUpdate {
+                    sheet_phase: Some("done"),
+                    success_count: Some(0),
+                    error_count: Some(0),
+                    total_rows: Some(0),
+                    updated_count: Some(0),
+                    merged_count: Some(0),
+                    ..Default::default()
+                },
+            ).await;
+            ProcessingEvent {
+                event: "sheet_complete".to_string(),
+                task_id: task_id.to_string(),
+                current_file: Some(file_name.to_string()),
+                current_sheet: Some(sheet_name.clone()),
+                sheet_success_count: Some(0),
+                sheet_error_count: Some(0),
+                sheet_total_rows: Some(0),
+                message: Some(format!("Sheet {} 无数据，跳过", sheet_name)),
+                ..Default::default()
+            }.emit(app);
+            continue;
+        }
+
+        // 指定表头行时，AI / 缓存 / 规则映射从表头行开始分析
+        let header_offset = spec.header_row.unwrap_or(0);
+        if header_offset >= rows_data.len() {
+            return Err(format!("Sheet {} 的表头行 {} 超出数据范围", sheet_name, header_offset));
+        }
+
+        // 多级表头：填充表头区域的合并单元格，识别后将组合列名写入表头最后一行
+        fill_merged_cells(&mut rows_data, &sheet_stream.merged_ranges(), header_offset + HEADER_AREA_ROWS);
+        let header_block = detect_header_block(&rows_data[header_offset..])
+            .filter(|b| spec.header_row.is_none() || b.start == 0);
+        let mapping_base = match &header_block {
+            Some(block) => {
+                let last_row = header_offset + block.last_row();
+                rows_data[last_row] = block.headers.clone();
                 ProcessingEvent {
-                    event: "sheet_complete".to_string(),
+                    event: "header_detected".to_string(),
                     task_id: task_id.to_string(),
-                    current_file: Some(file_name.to_string()),
                     current_sheet: Some(sheet_name.clone()),
-                    sheet_success_count: Some(0),
-                    sheet_error_count: Some(0),
-                    sheet_total_rows: Some(0),
-                    message: Some(format!("Sheet {} 无数据", sheet_name)),
+                    headers: Some(block.headers.clone()),
+                    message: Some(format!(
+                        "识别到 {} 行表头（第 {}-{} 行），已合并为组合列名",
+                        block.depth, header_offset + block.start + 1, last_row + 1
+                    )),
                     ..Default::default()
                 }.emit(app);
-                continue;
+                last_row
+            }
+            None => header_offset,
+        };
+        let mapping_rows = &rows_data[mapping_base..];
+
+        // 优先复用已保存的映射模板（表头指纹匹配），命中则跳过 AI
+        let template_mapping = if resumed.is_some() {
+            None
+        } else {
+            match find_mapping_template(db.as_ref(), project.id, &rows_data).await {
+                Ok(m) => m,
+                Err(e) => {
+                    tracing::warn!("查询映射模板失败，回退到 AI 分析: {}", e);
+                    None
+                }
+            }
+        };
+
+        // 其次查找 AI 映射缓存（表头 + 字段定义均未变化；规则模式不使用）
+        let cached_mapping = if template_mapping.is_none() && resumed.is_none() && options.mapping_mode != MappingMode::Rules {
+            match find_cached_mapping(db.as_ref(), project.id, mapping_rows, &field_hash).await {
+                Ok(m) => m,
+                Err(e) => {
+                    tracing::warn!("查询映射缓存失败，回退到 AI 分析: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let (mut mapping_result, mut mapping_source) = if let Some(checkpoint) = resumed {
+            ProcessingEvent {
+                event: "mapping_resumed".to_string(),
+                task_id: task_id.to_string(),
+                current_sheet: Some(sheet_name.clone()),
+                mapping_source: Some(checkpoint.mapping_source.clone()),
+                message: Some(format!(
+                    "从检查点继续：复用中断前的列映射，从第 {} 行开始导入",
+                    checkpoint.last_row.map_or(0, |r| r + 1) + 1
+                )),
+                ..Default::default()
+            }.emit(app);
+            (checkpoint.mapping.clone(), checkpoint.mapping_source.as_str())
+        } else if let Some(template) = template_mapping {
+            (template, "template")
+        } else if let Some(cached) = cached_mapping {
+            ProcessingEvent {
+                event: "mapping_cache_hit".to_string(),
+                task_id: task_id.to_string(),
+                current_sheet: Some(sheet_name.clone()),
+                mapping_source: Some("cache".to_string()),
+                message: Some("表头与历史导入一致，复用缓存的 AI 列映射".to_string()),
+                ..Default::default()
+            }.emit(app);
+            (cached, "cache")
+        } else if let Some(ai) = ai {
+            // AI 分析列映射
+            ProcessingEvent {
+                event: "ai_analyzing".to_string(),
+                task_id: task_id.to_string(),
+                current_sheet: Some(sheet_name.clone()),
+                message: Some("AI 分析列映射...".to_string()),
+                ..Default::default()
+            }.emit(app);
+
+            // AI 分析（流式）
+            let app_clone = app.clone();
+            let task_id_clone = task_id.to_string();
+            let sheet_name_clone = sheet_name.clone();
+
+            // 构建请求提示（用于显示）- 只取前 5 行样本数据
+            let request_preview = build_request_preview(&mapping_rows[0], &field_defs, mapping_rows.get(1..6).map(|r| r.to_vec()));
+            ProcessingEvent {
+                event: "ai_request".to_string(),
+                task_id: task_id.to_string(),
+                current_sheet: Some(sheet_name.clone()),
+                message: Some(request_preview),
+                ..Default::default()
+            }.emit(app);
+
+            let result = analyze_columns_with_ai_stream(
+                app_clone,
+                ai,
+                &mapping_rows[0],
+                &field_defs,
+                mapping_rows.get(1..6).map(|r| r.to_vec()),  // 只取前 5 行样本数据
+                task_id_clone,
+                sheet_name_clone,
+            ).await;
+
+            match result {
+                Ok(result) => {
+                    // 写入映射缓存，供后续相同版式的 Sheet 复用
+                    if let Err(e) = save_cached_mapping(db.as_ref(), project.id, mapping_rows, &field_hash, &result).await {
+                        tracing::warn!("保存映射缓存失败: {}", e);
+                    }
+                    (result, "ai")
+                }
+                Err(e) if options.mapping_mode == MappingMode::AiWithRulesFallback => {
+                    ProcessingEvent {
+                        event: "ai_fallback".to_string(),
+                        task_id: task_id.to_string(),
+                        current_sheet: Some(sheet_name.clone()),
+                        mapping_source: Some("rules".to_string()),
+                        message: Some(format!("AI 分析失败，回退到规则匹配: {}", e)),
+                        ..Default::default()
+                    }.emit(app);
+                    (map_columns_by_rules(mapping_rows, &mappable_fields), "rules")
+                }
+                Err(e) => return Err(e),
             }
+        } else {
+            // 规则匹配列映射（无 AI 配置或规则模式）
+            ProcessingEvent {
+                event: "rule_mapping".to_string(),
+                task_id: task_id.to_string(),
+                current_sheet: Some(sheet_name.clone()),
+                mapping_source: Some("rules".to_string()),
+                message: Some("按表头与数据特征匹配列映射...".to_string()),
+                ..Default::default()
+            }.emit(app);
+            (map_columns_by_rules(mapping_rows, &mappable_fields), "rules")
         };
 
-        if rows_data.is_empty() {
-            // Sheet 为空，标记为完成（0 行）
+        // 指定表头行或识别到多级表头时，以表头最后一行为准（映射结果中的行号是相对 mapping_rows 的）
+        if resumed.is_none() && (spec.header_row.is_some() || (header_block.is_some() && mapping_source != "template")) {
+            mapping_result.header_row = mapping_base as i32;
+        }
+
+        // 人工确认模式：暂停导入，等待用户确认/修改映射
+        if options.review_mapping && mapping_source != "template" && resumed.is_none() {
+            let header_idx = (mapping_result.header_row.max(0) as usize).min(rows_data.len() - 1);
+
             let _ = upsert_file_progress(
-                db, task_id, file_name, Some(&sheet_name),
-                None, Some("done"), None, None,
-                Some(0), Some(0), Some(0), None,
+                db,
+                task_id,
+                &file_path,
+                Some(&sheet_name),
+                FileProgressUpdate {
+                    sheet_phase: Some("mapping_review"),
+                    ai_confidence: Some(mapping_result.confidence),
+                    mapping_count: Some(mapping_result.mappings.len() as i32),
+                    ..Default::default()
+                },
             ).await;
+            let _ = update_task_status(db, task_id, "awaiting_review".to_string()).await;
+
+            *control.reviewed_mapping.lock().unwrap() = None;
+            control.awaiting_review.store(true, Ordering::SeqCst);
+
             ProcessingEvent {
-                event: "sheet_complete".to_string(),
+                event: "mapping_review".to_string(),
                 task_id: task_id.to_string(),
                 current_file: Some(file_name.to_string()),
                 current_sheet: Some(sheet_name.clone()),
-                sheet_success_count: Some(0),
-                sheet_error_count: Some(0),
-                sheet_total_rows: Some(0),
-                message: Some(format!("Sheet {} 无数据，跳过", sheet_name)),
+                confidence: Some(mapping_result.confidence),
+                headers: Some(rows_data[header_idx].clone()),
+                column_mapping: Some(mapping_result.clone()),
+                mapping_source: Some(mapping_source.to_string()),
+                message: Some("请确认列映射".to_string()),
                 ..Default::default()
             }.emit(app);
-            continue;
-        }
 
-        // AI 分析列映射
-        ProcessingEvent {
-            event: "ai_analyzing".to_string(),
-            task_id: task_id.to_string(),
-            current_sheet: Some(sheet_name.clone()),
-            message: Some("AI 分析列映射...".to_string()),
-            ..Default::default()
-        }.emit(app);
+            // 等待 confirm_column_mapping 写入确认结果（或任务取消）
+            let reviewed = control.wait_for_review().await;
+            control.awaiting_review.store(false, Ordering::SeqCst);
+            let Some(reviewed) = reviewed else {
+                return Ok((total_rows, success_count, error_count, updated_count, merged_count));
+            };
 
-        // 构建字段定义
-        let field_defs: Vec<FieldDefinition> = fields.iter().map(|f| FieldDefinition {
-            field_name: f.field_name.clone(),
-            field_label: f.field_label.clone(),
-            field_type: f.field_type.clone(),
-            additional_requirement: f.additional_requirement.clone(),
-            extraction_hint: f.extraction_hint.clone(),
-        }).collect();
-
-        // AI 分析（流式）
-        let app_clone = app.clone();
-        let task_id_clone = task_id.to_string();
-        let sheet_name_clone = sheet_name.clone();
-
-        // 构建请求提示（用于显示）- 只取前 5 行样本数据
-        let request_preview = build_request_preview(&rows_data[0], &field_defs, rows_data.get(1..6).map(|r| r.to_vec()));
-        ProcessingEvent {
-            event: "ai_request".to_string(),
-            task_id: task_id.to_string(),
-            current_sheet: Some(sheet_name.clone()),
-            message: Some(request_preview),
-            ..Default::default()
-        }.emit(app);
+            // 保存为映射模板，后续相同表头的文件直接复用
+            if let Some(headers) = rows_data.get(reviewed.header_row.max(0) as usize) {
+                if let Err(e) = save_mapping_template(db.as_ref(), project.id, headers, &reviewed).await {
+                    tracing::warn!("保存映射模板失败: {}", e);
+                }
+            }
+
+            mapping_result = reviewed;
+            mapping_source = "manual";
+            let _ = update_task_status(db, task_id, "processing".to_string()).await;
 
-        let mapping_result = analyze_columns_with_ai_stream(
-            app_clone,
-            api_url,
-            api_key,
-            model_name,
-            temperature,
-            max_tokens,
-            &rows_data[0],
-            &field_defs,
-            rows_data.get(1..6).map(|r| r.to_vec()),  // 只取前 5 行样本数据
-            task_id_clone,
-            sheet_name_clone,
-        ).await?;
+            ProcessingEvent {
+                event: "mapping_confirmed".to_string(),
+                task_id: task_id.to_string(),
+                current_sheet: Some(sheet_name.clone()),
+                mapping_source: Some(mapping_source.to_string()),
+                message: Some("列映射已确认，继续导入".to_string()),
+                ..Default::default()
+            }.emit(app);
+        }
 
         // 发送列映射结果
         let mappings_json: HashMap<String, String> = mapping_result.mappings.iter()
             .map(|m| (m.field_name.clone(), m.column_index.to_string()))
             .collect();
 
+        let source_label = match mapping_source {
+            "template" => "映射模板",
+            "cache" => "缓存",
+            "rules" => "规则匹配",
+            "manual" => "人工确认",
+            _ => "AI",
+        };
         ProcessingEvent {
             event: "column_mapping".to_string(),
             task_id: task_id.to_string(),
             current_sheet: Some(sheet_name.clone()),
             confidence: Some(mapping_result.confidence),
             mappings: Some(mappings_json.clone()),
-            message: Some(format!("列映射完成 (置信度: {:.0}%)", mapping_result.confidence * 100.0)),
+            mapping_source: Some(mapping_source.to_string()),
+            message: Some(format!("列映射完成 (来源: {}, 置信度: {:.0}%)", source_label, mapping_result.confidence * 100.0)),
             ..Default::default()
         }.emit(app);
 
@@ -800,169 +1403,317 @@ async fn process_single_file(
         let _ = upsert_file_progress(
             db,
             task_id,
-            file_name,
+            &file_path,
             Some(&sheet_name),
-            None,  // file_phase
-            Some("importing"),
-            Some(mapping_result.confidence),
-            Some(mapping_result.mappings.len() as i32),
-            None,  // success_count
-            None,  // error_count
-            None,  // total_rows
-            None,  // error_message
+            FileProgressUpdate {
+                sheet_phase: Some("importing"),
+                ai_confidence: Some(mapping_result.confidence),
+                mapping_count: Some(mapping_result.mappings.len() as i32),
+                ..Default::default()
+            },
         ).await;
 
+        // 保存检查点：列映射已确定，中断后直接复用，不再重新分析
+        match resumed {
+            Some(checkpoint) => writer.set_checkpoint(checkpoint.clone()),
+            None => {
+                writer.set_checkpoint(TaskCheckpoint {
+                    file_index,
+                    file_name: file_name.to_string(),
+                    sheet_name: sheet_name.clone(),
+                    last_row: None,
+                    mapping: mapping_result.clone(),
+                    mapping_source: mapping_source.to_string(),
+                    sheet_counts: ImportCounts::default(),
+                });
+                writer.flush(db.as_ref()).await?;
+            }
+        }
+
         // 创建字段 ID 到索引的映射（预留用于未来优化）
         let _field_id_to_idx: HashMap<i32, usize> = fields.iter()
             .enumerate()
             .map(|(i, f)| (f.id, i))
             .collect();
 
-        // 处理数据行
+        // 处理数据行（表头之后，并限定在指定的行范围内；从检查点继续时从已落库的下一行开始）
         let header_row = mapping_result.header_row.max(0) as usize;
-        let start_row = header_row + 1;
+        let start_row = (header_row + 1)
+            .max(spec.start_row.unwrap_or(0))
+            .max(resumed.and_then(|c| c.last_row).map_or(0, |r| r + 1));
+        let end_row = spec.end_row.unwrap_or(usize::MAX);
+        let empty_row_threshold = spec.empty_row_threshold.unwrap_or(DEFAULT_EMPTY_ROW_THRESHOLD);
 
         let mut empty_count = 0;
 
-        for (row_idx, row) in rows_data.iter().enumerate().skip(start_row) {
-            // 检查取消状态
-            if control.cancelled.load(Ordering::SeqCst) {
-                break;
-            }
+        // 字段转换管道（拼接列按本 Sheet 表头解析）
+        let headers: Vec<String> = if mapping_result.header_row >= 0 {
+            rows_data.get(header_row).cloned().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let field_pipelines: HashMap<i32, TransformPipeline> = fields.iter()
+            .map(|f| (f.id, field_pipeline(f, &headers)))
+            .collect();
 
-            // 检查暂停状态
-            while control.paused.load(Ordering::SeqCst) {
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                if control.cancelled.load(Ordering::SeqCst) {
-                    return Ok((total_rows, success_count, error_count));
-                }
+        // 参与导入的字段及其来源：已映射的字段，未映射但配置了转换的字段（如默认值、拼接其他列），
+        // 以及从来源列的自由文本中由 AI 提取的字段
+        let mut field_columns: Vec<(&FieldModel, FieldSource)> = mapping_result.mappings.iter()
+            .filter_map(|m| {
+                let field = mappable_fields.iter().find(|f| f.field_name == m.field_name)?;
+                Some((field, FieldSource::Column(m.column_index as usize)))
+            })
+            .collect();
+        for field in &mappable_fields {
+            if !field_pipelines[&field.id].is_empty() && !field_columns.iter().any(|(f, _)| f.id == field.id) {
+                field_columns.push((field, FieldSource::Unmapped));
             }
+        }
+        let mut extraction_groups = build_extraction_groups(fields, &headers);
+        for (group_idx, group) in extraction_groups.iter().enumerate() {
+            field_columns.extend(group.fields.iter().map(|&f| (f, FieldSource::AiExtract(group_idx))));
+        }
 
-            // 空行检测
-            let is_empty = row.iter().all(|c| c.trim().is_empty());
-            if is_empty {
-                empty_count += 1;
-                if empty_count >= 10 {
-                    break; // 连续 10 个空行，跳到下一个 sheet
+        // 按分块处理数据行，内存中只保留当前分块
+        let mut chunk = rows_data;
+        let mut chunk_start = 0usize;
+        'rows: loop {
+            // AI 逐行提取：对本分块中尚未提取过的文本分批调用 AI
+            for group in &mut extraction_groups {
+                let rows = chunk.iter().enumerate()
+                    .filter(|(offset, _)| (start_row..=end_row).contains(&(chunk_start + offset)))
+                    .map(|(_, row)| row);
+                let extracted = group.extract_pending(rows, ai, &control.ai_usage).await;
+                if extracted > 0 {
+                    ProcessingEvent {
+                        event: "ai_extracting".to_string(),
+                        task_id: task_id.to_string(),
+                        current_file: Some(file_name.to_string()),
+                        current_sheet: Some(sheet_name.clone()),
+                        message: Some(format!("AI 已提取 {} 条文本", extracted)),
+                        ai_usage: Some(control.ai_usage.snapshot()),
+                        ..Default::default()
+                    }.emit(app);
                 }
-                continue;
             }
-            empty_count = 0;
 
-            total_rows += 1;
-
-            // 提取数据
-            let mut data: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
-            let mut validation_errors = Vec::new();
+            for (offset, row) in chunk.iter().enumerate() {
+                let row_idx = chunk_start + offset;
+                if row_idx < start_row {
+                    continue;
+                }
+                if row_idx > end_row {
+                    break 'rows;
+                }
 
-            for mapping in &mapping_result.mappings {
-                if let Some(field) = fields.iter().find(|f| f.field_name == mapping.field_name) {
-                    let col_idx = mapping.column_index as usize;
-                    if col_idx < row.len() {
-                        // 根据字段类型清理数据
-                        let value = clean_value(&row[col_idx], &field.field_type);
+                // 检查取消状态
+                if control.cancelled.load(Ordering::SeqCst) {
+                    break 'rows;
+                }
 
-                        // 必填字段验证
-                        if field.is_required && value.trim().is_empty() {
-                            validation_errors.push(format!("{} 为必填项", field.field_label));
-                        }
+                // 检查暂停状态（暂停前先写入已缓冲的记录）
+                if control.paused.load(Ordering::SeqCst) {
+                    writer.flush(db.as_ref()).await?;
+                }
+                while control.paused.load(Ordering::SeqCst) {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                    if control.cancelled.load(Ordering::SeqCst) {
+                        return Ok((total_rows, success_count, error_count, updated_count, merged_count));
+                    }
+                }
 
-                        // 格式验证
-                        let rule = field.validation_rule.as_deref();
-                        if !validate_value(&value, rule) {
-                            validation_errors.push(format!("{} 验证失败", field.field_label));
+                // 空行检测
+                let is_empty = row.iter().all(|c| c.trim().is_empty());
+                if is_empty {
+                    empty_count += 1;
+                    if empty_row_threshold > 0 && empty_count >= empty_row_threshold {
+                        break 'rows; // 连续空行达到阈值，跳到下一个 sheet
+                    }
+                    continue;
+                }
+                empty_count = 0;
+
+                total_rows += 1;
+
+                // 提取数据：按字段清洗、校验（与记录修正共用同一套逻辑）
+                let mut cleaner = RowCleaner::new(fields, &field_options, serde_json::Map::new());
+
+                for &(field, source) in &field_columns {
+                    let cell = match source {
+                        FieldSource::Column(i) if i < row.len() => row[i].as_str(),
+                        FieldSource::Column(_) => {
+                            // 列不存在但字段必填
+                            if field.is_required {
+                                cleaner.error(field, format!("{} 为必填项", field.field_label));
+                            }
+                            continue;
                         }
-
-                        // 存储（使用 field_id 作为 key）
-                        data.insert(field.id.to_string(), serde_json::Value::String(value));
-                    } else if field.is_required {
-                        // 列不存在但字段必填
-                        validation_errors.push(format!("{} 为必填项", field.field_label));
+                        FieldSource::Unmapped => "",
+                        FieldSource::AiExtract(group_idx) => match extraction_groups[group_idx].values_for(row) {
+                            Some(Ok(values)) => values.get(&field.field_name).map(String::as_str).unwrap_or(""),
+                            Some(Err(e)) => {
+                                cleaner.error(field, format!("{} AI 提取失败: {}", field.field_label, e));
+                                continue;
+                            }
+                            None => "",
+                        },
+                    };
+
+                    // 执行字段转换管道；未映射的字段转换后仍为空时视为未提供
+                    let raw = field_pipelines[&field.id].apply(cell, row);
+                    if matches!(source, FieldSource::Unmapped) && raw.trim().is_empty() {
+                        continue;
                     }
+                    cleaner.add(field, &raw);
                 }
-            }
 
-            // 检查必填字段是否在 AI 映射中完全缺失（AI 未能找到对应列）
-            let mapped_field_names: std::collections::HashSet<&str> = mapping_result.mappings
-                .iter()
-                .map(|m| m.field_name.as_str())
-                .collect();
-            for field in fields.iter().filter(|f| f.is_required) {
-                if !mapped_field_names.contains(field.field_name.as_str()) {
-                    validation_errors.push(format!("{} 为必填项（未找到对应列）", field.field_label));
+                // 派生字段：电话附加号码、身份证（出生日期、年龄、性别、省份）、计算字段、地址（省 / 市 / 区县）
+                let (data, row_errors) = cleaner.finish(&computed_fields, Some((&headers, row)));
+                let mut validation_errors: Vec<String> = row_errors.into_iter().map(|(_, message)| message).collect();
+
+                // 检查必填字段是否在 AI 映射中完全缺失（AI 未能找到对应列）
+                let mapped_field_names: std::collections::HashSet<&str> = mapping_result.mappings
+                    .iter()
+                    .map(|m| m.field_name.as_str())
+                    .chain(field_columns.iter()
+                        .filter(|(_, source)| matches!(source, FieldSource::AiExtract(_)))
+                        .map(|(f, _)| f.field_name.as_str()))
+                    .collect();
+                for field in fields.iter().filter(|f| f.is_required && f.expression.is_none()) {
+                    // 未映射但已由派生规则填充的字段视为已提供
+                    let derived = data.get(&field.id.to_string()).is_some_and(|v| !v.is_null());
+                    if !mapped_field_names.contains(field.field_name.as_str()) && !derived {
+                        validation_errors.push(format!("{} 为必填项（未找到对应列）", field.field_label));
+                    }
                 }
-            }
 
-            // 去重检查
-            let is_duplicate = if dedup_enabled && !dedup_fields.is_empty() {
-                let mut dedup_values: HashMap<String, String> = HashMap::new();
-                for field_id in dedup_fields {
-                    if let Some(val) = data.get(&field_id.to_string()) {
-                        if let Some(s) = val.as_str() {
-                            dedup_values.insert(field_id.to_string(), s.to_string());
+                // 去重检查（内存索引）：命中时返回已有记录 ID，验证失败的行无需检查
+                let duplicate = if validation_errors.is_empty() {
+                    writer.find_duplicate(db.as_ref(), &data).await?
+                } else {
+                    None
+                };
+
+                let data_json = serde_json::Value::Object(data);
+
+                // 按去重策略确定本行结果
+                let outcome = if !validation_errors.is_empty() {
+                    RowOutcome::Reject("validation_error", validation_errors.join("; "))
+                } else if let Some(existing_id) = duplicate {
+                    match dedup_strategy {
+                        // 覆盖：用新行数据替换已有记录的 data
+                        "update" => RowOutcome::Update(existing_id, writer.fetch_data(db.as_ref(), existing_id).await?),
+                        // 合并：仅补全已有记录中的空字段
+                        "merge" => {
+                            let existing_data = writer.fetch_data(db.as_ref(), existing_id).await?;
+                            match merge_record_data(&existing_data, &data_json) {
+                                Some(merged) => RowOutcome::Merge(existing_id, existing_data, merged),
+                                None => RowOutcome::Reject("duplicate", format!("与记录 #{} 重复（无可补全的空字段）", existing_id)),
+                            }
                         }
+                        _ => RowOutcome::Reject("duplicate", format!("与记录 #{} 重复", existing_id)),
+                    }
+                } else {
+                    RowOutcome::Insert
+                };
+
+                // 先计数并推进检查点，再写入本行结果（两者在同一事务中落库）
+                match &outcome {
+                    RowOutcome::Insert => success_count += 1,
+                    RowOutcome::Update(..) => updated_count += 1,
+                    RowOutcome::Merge(..) => merged_count += 1,
+                    RowOutcome::Reject(..) => error_count += 1,
+                }
+                writer.checkpoint_row(row_idx, ImportCounts {
+                    rows: total_rows - sheet_start_total,
+                    success: success_count - sheet_start_success,
+                    errors: error_count - sheet_start_error,
+                    updated: updated_count - sheet_start_updated,
+                    merged: merged_count - sheet_start_merged,
+                });
+
+                match outcome {
+                    RowOutcome::Insert => {
+                        writer.push(db.as_ref(), PendingRecord {
+                            data: data_json,
+                            raw_data: Some(format_row_indexed(row)),  // 原始行数据
+                            source_file: Some(file_name.to_string()),
+                            source_sheet: Some(sheet_name.clone()),
+                            row_number: Some(row_idx as i32),
+                            status: "success".to_string(),
+                            error_message: None,
+                        }).await?;
+                    }
+                    RowOutcome::Update(existing_id, existing_data) => {
+                        writer.update_record(db.as_ref(), existing_id, &existing_data, &data_json, Some(format_row_indexed(row))).await?;
+                    }
+                    RowOutcome::Merge(existing_id, existing_data, merged) => {
+                        writer.update_record(db.as_ref(), existing_id, &existing_data, &merged, None).await?;
+                    }
+                    // 保存被拒绝的行（保留原始行数据，便于修正后重新导入）
+                    RowOutcome::Reject(status, reason) => {
+                        writer.push(db.as_ref(), PendingRecord {
+                            data: data_json,
+                            raw_data: Some(format_row_indexed(row)),
+                            source_file: Some(file_name.to_string()),
+                            source_sheet: Some(sheet_name.clone()),
+                            row_number: Some(row_idx as i32),
+                            status: status.to_string(),
+                            error_message: Some(reason),
+                        }).await?;
                     }
                 }
-                check_duplicate(db, task_id, &dedup_values).await?
-            } else {
-                false
-            };
-
-            // 插入记录
-            let _status = if validation_errors.is_empty() && !is_duplicate {
-                let data_json = serde_json::Value::Object(data);
-                insert_record(
-                    db,
-                    task_id,
-                    &data_json,
-                    Some(row),  // 传递原始行数据
-                    Some(file_name.to_string()),
-                    Some(sheet_name.clone()),
-                    Some(row_idx as i32),
-                ).await?;
-                success_count += 1;
-                "success".to_string()
-            } else if is_duplicate {
-                "duplicate".to_string()
-            } else {
-                "validation_error".to_string()
-            };
 
-            // 每 10 行发送进度事件
-            if total_rows % 10 == 0 {
-                ProcessingEvent {
-                    event: "row_processed".to_string(),
-                    task_id: task_id.to_string(),
-                    current_row: Some(row_idx as i32),
-                    total_rows: Some(total_rows),
-                    processed_rows: Some(total_rows),
-                    success_count: Some(success_count),
-                    error_count: Some(error_count),
-                    message: Some(format!("已处理 {} 行", total_rows)),
-                    ..Default::default()
-                }.emit(app);
+                // 每 10 行发送进度事件
+                if total_rows % 10 == 0 {
+                    ProcessingEvent {
+                        event: "row_processed".to_string(),
+                        task_id: task_id.to_string(),
+                        current_row: Some(row_idx as i32),
+                        total_rows: Some(total_rows),
+                        processed_rows: Some(total_rows),
+                        success_count: Some(success_count),
+                        error_count: Some(error_count),
+                        updated_count: Some(updated_count),
+                        merged_count: Some(merged_count),
+                        message: Some(format!("已处理 {} 行", total_rows)),
+                        ..Default::default()
+                    }.emit(app);
+                }
             }
+
+            chunk_start += chunk.len();
+            chunk = match sheet_stream.next_chunk().await? {
+                Some(next) => next,
+                None => break,
+            };
         }
 
+        // Sheet 结束时写入缓冲区中剩余的记录
+        writer.flush(db.as_ref()).await?;
+
         // Sheet 完成时计算当前 Sheet 的增量值
         let sheet_success = success_count - sheet_start_success;  // 当前 Sheet 的成功数（增量）
         let sheet_error = error_count - sheet_start_error;        // 当前 Sheet 的错误数（增量）
         let sheet_total = total_rows - sheet_start_total;         // 当前 Sheet 的总行数（增量）
+        let sheet_updated = updated_count - sheet_start_updated;  // 当前 Sheet 的更新数（增量）
+        let sheet_merged = merged_count - sheet_start_merged;     // 当前 Sheet 的合并数（增量）
 
         // 持久化：更新 Sheet 完成状态和统计
         let _ = upsert_file_progress(
             db,
             task_id,
-            file_name,
+            &file_path,
             Some(&sheet_name),
-            None,  // file_phase
-            Some("done"),
-            None,  // ai_confidence
-            None,  // mapping_count
-            Some(sheet_success),
-            Some(sheet_error),
-            Some(sheet_total),
-            None,  // error_message
+            FileProgressUpdate {
+                sheet_phase: Some("done"),
+                success_count: Some(sheet_success),
+                error_count: Some(sheet_error),
+                total_rows: Some(sheet_total),
+                updated_count: Some(sheet_updated),
+                merged_count: Some(sheet_merged),
+                ..Default::default()
+            },
         ).await;
 
         // Sheet 完成 - 添加 sheet 级别统计字段
@@ -974,12 +1725,17 @@ async fn process_single_file(
             sheet_success_count: Some(sheet_success),
             sheet_error_count: Some(sheet_error),
             sheet_total_rows: Some(sheet_total),
-            message: Some(format!("Sheet {} 处理完成: 成功 {} 行, 失败 {} 行", sheet_name, sheet_success, sheet_error)),
+            sheet_updated_count: Some(sheet_updated),
+            sheet_merged_count: Some(sheet_merged),
+            message: Some(format!(
+                "Sheet {} 处理完成: 成功 {} 行, 失败 {} 行, 更新 {} 行, 合并 {} 行",
+                sheet_name, sheet_success, sheet_error, sheet_updated, sheet_merged
+            )),
             ..Default::default()
         }.emit(app);
     }
 
-    Ok((total_rows, success_count, error_count))
+    Ok((total_rows, success_count, error_count, updated_count, merged_count))
 }
 
 fn build_request_preview(
@@ -1017,12 +1773,8 @@ fn build_request_preview(
 }
 
 async fn analyze_columns_with_ai_stream(
-    app: AppHandle,
-    api_url: &str,
-    api_key: &str,
-    model_name: &str,
-    temperature: f32,
-    max_tokens: i32,
+    app: EventSink,
+    ai: &AiSettings,
     headers: &[String],
     field_defs: &[FieldDefinition],
     sample_rows: Option<Vec<Vec<String>>>,
@@ -1066,7 +1818,9 @@ async fn analyze_columns_with_ai_stream(
   "unmatched_columns": [1, 3]
 }
 
-header_row 和 column_index 均从 0 计数；-1 表示无表头"#;
+header_row 和 column_index 均从 0 计数；-1 表示无表头
+
+多级表头已按列合并为「上级/下级」形式的列名（如"联系人/电话"），以最下级名称为主、结合上级语义判断"#;
 
     // 列维度展示：表头 + 该列的样本值（方便 AI 逐列验证数据内容）
     let mut user_prompt = String::new();
@@ -1116,13 +1870,13 @@ header_row 和 column_index 均从 0 计数；-1 表示无表头"#;
     let sheet_name_for_stream = sheet_name.clone();
 
     let response = call_ai_stream(
-        api_url,
-        api_key,
-        model_name,
+        &ai.api_url,
+        &ai.api_key,
+        &ai.model_name,
         system_prompt,
         &user_prompt,
-        temperature,
-        max_tokens,
+        ai.temperature,
+        ai.max_tokens,
         true,  // json_mode: 列映射需要返回 JSON
         move |chunk: &str| {
             // 发送流式事件
@@ -1210,89 +1964,37 @@ header_row 和 column_index 均从 0 计数；-1 表示无表头"#;
     })
 }
 
-async fn check_duplicate(db: &Arc<DatabaseConnection>, task_id: &str, dedup_values: &HashMap<String, String>) -> Result<bool, String> {
-    // 从 task_id 获取 project_id
-    let task = ProcessingTask::find_by_id(task_id)
-        .one(db.as_ref())
-        .await
-        .map_err(|e| format!("数据库错误: {}", e))?;
-
-    if let Some(task) = task {
-        let mut conditions = vec!["project_id = ?".to_string()];
-        let mut params: Vec<sea_orm::Value> = vec![task.project_id.into()];
-
-        for (field_id, value) in dedup_values {
-            if !value.trim().is_empty() {
-                conditions.push(format!("json_extract(data, '$.{}') = ?", field_id));
-                params.push(value.clone().into());
-            }
+/// 合并策略：用新行补全已有记录中的空字段（已有非空值保持不变）
+///
+/// 返回合并后的 data；若没有任何字段被补全则返回 None
+fn merge_record_data(
+    existing: &serde_json::Map<String, serde_json::Value>,
+    incoming: &serde_json::Value,
+) -> Option<serde_json::Value> {
+    let mut merged = existing.clone();
+    let mut changed = false;
+
+    for (key, value) in incoming.as_object().into_iter().flatten() {
+        let incoming_empty = value.is_null() || value.as_str().map(|s| s.trim().is_empty()).unwrap_or(false);
+        if incoming_empty {
+            continue;
         }
-
-        if conditions.len() > 1 {
-            let sql = format!(
-                "SELECT id FROM project_records WHERE {} LIMIT 1",
-                conditions.join(" AND ")
-            );
-
-            let result = db.as_ref()
-                .query_one(Statement::from_sql_and_values(
-                    db.as_ref().get_database_backend(),
-                    &sql,
-                    params,
-                ))
-                .await
-                .map_err(|e| format!("数据库错误: {}", e))?;
-
-            return Ok(result.is_some());
+        let existing_empty = match merged.get(key) {
+            None | Some(serde_json::Value::Null) => true,
+            Some(serde_json::Value::String(s)) => s.trim().is_empty(),
+            Some(_) => false,
+        };
+        if existing_empty {
+            merged.insert(key.clone(), value.clone());
+            changed = true;
         }
     }
 
-    Ok(false)
-}
-
-async fn insert_record(
-    db: &Arc<DatabaseConnection>,
-    task_id: &str,
-    data: &serde_json::Value,
-    raw_data: Option<&[String]>,
-    source_file: Option<String>,
-    source_sheet: Option<String>,
-    row_number: Option<i32>,
-) -> Result<i32, String> {
-    let task = ProcessingTask::find_by_id(task_id)
-        .one(db.as_ref())
-        .await
-        .map_err(|e| format!("数据库错误: {}", e))?
-        .ok_or_else(|| format!("任务 {} 不存在", task_id))?;
-
-    let now = chrono::Utc::now().to_rfc3339();
-    let data_str = serde_json::to_string(data)
-        .map_err(|e| format!("JSON 序列化错误: {}", e))?;
-
-    // 序列化原始行数据为索引格式：1:列1内容;2:列2内容;...n:列n内容;
-    let raw_data_str = raw_data.map(|row| format_row_indexed(row));
-
-    let new_record = record::ActiveModel {
-        project_id: Set(task.project_id),
-        data: Set(data_str),
-        raw_data: Set(raw_data_str),
-        source_file: Set(source_file),
-        source_sheet: Set(source_sheet),
-        row_number: Set(row_number),
-        batch_number: Set(task.batch_number.clone()),
-        status: Set("success".to_string()),
-        error_message: Set(None),
-        created_at: Set(now),
-        updated_at: Set(None),
-        ..Default::default()
-    };
-
-    let result = new_record
-        .insert(db.as_ref())
-        .await
-        .map_err(|e| format!("数据库错误: {}", e))?;
-
-    Ok(result.id)
+    if changed {
+        Some(serde_json::Value::Object(merged))
+    } else {
+        None
+    }
 }
 
 async fn update_task_status(db: &Arc<DatabaseConnection>, task_id: &str, status: String) -> Result<(), String> {
@@ -1373,7 +2075,509 @@ pub async fn cancel_processing_task(
 ) -> Result<(), String> {
     let tasks = ACTIVE_TASKS.read().await;
     if let Some(control) = tasks.get(&task_id) {
-        control.cancelled.store(true, Ordering::SeqCst);
+        control.cancel();
     }
     update_task_status(&db, &task_id, "cancelled".to_string()).await
 }
+
+/// 确认列映射（人工确认模式）
+///
+/// 任务在 mapping_review 阶段等待该命令，确认后的映射会保存为项目映射模板
+#[tauri::command]
+pub async fn confirm_column_mapping(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    task_id: String,
+    header_row: i32,
+    mappings: Vec<FieldMapping>,
+) -> Result<(), String> {
+    let control = ACTIVE_TASKS.read().await
+        .get(&task_id)
+        .cloned()
+        .ok_or_else(|| format!("任务不存在或已结束: {}", task_id))?;
+
+    if !control.awaiting_review.load(Ordering::SeqCst) {
+        return Err("任务当前不在等待映射确认状态".to_string());
+    }
+
+    // 映射的字段必须是任务所属项目中可映射的字段
+    let task_model = ProcessingTask::find_by_id(&task_id)
+        .one(db.inner().as_ref())
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+        .ok_or_else(|| format!("任务不存在: {}", task_id))?;
+    let fields = field::Entity::find()
+        .filter(field::Column::ProjectId.eq(task_model.project_id))
+        .filter(field::Column::IsDeleted.eq(false))
+        .all(db.inner().as_ref())
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+    validate_reviewed_mapping(header_row, &mappings, &fields)?;
+
+    control.submit_review(ColumnMappingResponse {
+        header_row,
+        mappings,
+        confidence: 1.0,
+        unmatched_columns: vec![],
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::infrastructure::persistence::migrations::run_migrations;
+    use crate::backend::infrastructure::persistence::models::project;
+    use sea_orm::{ConnectionTrait, Database, Statement};
+
+    struct Fixture {
+        db: Arc<DatabaseConnection>,
+        project: project::Model,
+        fields: Vec<FieldModel>,
+    }
+
+    /// 测试项目：姓名、手机号（去重字段）、邮箱（正则校验）
+    async fn setup(dedup_strategy: &str) -> Fixture {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&db).await.unwrap();
+        let project = project::ActiveModel {
+            name: Set("测试项目".to_string()),
+            description: Set(None),
+            dedup_enabled: Set(true),
+            dedup_fields: Set(None),
+            dedup_strategy: Set(dedup_strategy.to_string()),
+            group_id: Set(None),
+            created_at: Set(chrono::Utc::now()),
+            updated_at: Set(None),
+            deleted_at: Set(None),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let mut fields = vec![];
+        for (order, (name, label, field_type, dedup, rule)) in [
+            ("name", "姓名", "text", false, None),
+            ("phone", "手机号", "phone", true, None),
+            ("email", "邮箱", "email", false, Some(r"^[^@\s]+@[^@\s]+\.[a-z]+$")),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let field = field::ActiveModel {
+                project_id: Set(project.id),
+                field_name: Set(name.to_string()),
+                field_label: Set(label.to_string()),
+                field_type: Set(field_type.to_string()),
+                is_required: Set(false),
+                is_dedup_key: Set(dedup),
+                is_deleted: Set(false),
+                validation_rule: Set(rule.map(str::to_string)),
+                display_order: Set(order as i32),
+                created_at: Set(chrono::Utc::now()),
+                ..Default::default()
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+            fields.push(field);
+        }
+
+        Fixture { db: Arc::new(db), project, fields }
+    }
+
+    fn csv_file(content: &str) -> String {
+        let path = std::env::temp_dir().join(format!("redata_test_{}.csv", uuid::Uuid::new_v4()));
+        std::fs::write(&path, content).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    async fn create_task(fx: &Fixture) -> String {
+        let task_id = uuid::Uuid::new_v4().to_string();
+        task::ActiveModel {
+            id: Set(task_id.clone()),
+            project_id: Set(fx.project.id),
+            status: Set("processing".to_string()),
+            total_files: Set(1),
+            processed_files: Set(0),
+            total_rows: Set(0),
+            processed_rows: Set(0),
+            success_count: Set(0),
+            error_count: Set(0),
+            batch_number: Set(Some(format!("BATCH_{}", &task_id[..8]))),
+            source_files: Set(None),
+            options: Set(None),
+            checkpoint: Set(None),
+            created_at: Set(chrono::Utc::now()),
+            updated_at: Set(None),
+        }
+        .insert(fx.db.as_ref())
+        .await
+        .unwrap();
+        task_id
+    }
+
+    /// 以规则映射导入文件（不调用 AI）
+    async fn import(fx: &Fixture, task_id: &str, paths: &[String], specs: &[FileSpec], resume: Option<ResumeState>) {
+        let job = ProcessingJob {
+            task_id: task_id.to_string(),
+            project: fx.project.clone(),
+            fields: fx.fields.clone(),
+            file_paths: paths.to_vec(),
+            file_specs: specs.to_vec(),
+            ai: None,
+            options: ProcessingOptions { review_mapping: false, mapping_mode: MappingMode::Rules },
+            resume,
+        };
+        process_files(EventSink(None), fx.db.clone(), &job, Arc::new(TaskControl::default()))
+            .await
+            .unwrap();
+    }
+
+    /// 项目记录（按 ID 排序）：(姓名, 手机号, 邮箱, 状态, 错误信息)
+    async fn records(fx: &Fixture) -> Vec<(String, String, String, String, Option<String>)> {
+        let [name, phone, email] = [0, 1, 2].map(|i| fx.fields[i].id);
+        fx.db
+            .query_all(Statement::from_sql_and_values(
+                fx.db.get_database_backend(),
+                "SELECT data, status, error_message FROM project_records WHERE project_id = ? ORDER BY id",
+                vec![fx.project.id.into()],
+            ))
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| {
+                let data: serde_json::Value =
+                    serde_json::from_str(&row.try_get::<String>("", "data").unwrap()).unwrap();
+                let get = |id: i32| data.get(id.to_string()).and_then(|v| v.as_str()).unwrap_or("").to_string();
+                (
+                    get(name),
+                    get(phone),
+                    get(email),
+                    row.try_get("", "status").unwrap(),
+                    row.try_get("", "error_message").unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    /// 文件级进度计数：(成功, 失败, 总行数, 更新, 合并)
+    async fn file_counts(fx: &Fixture, task_id: &str) -> (i32, i32, i32, i32, i32) {
+        let p = TaskFileProgress::find()
+            .filter(task_file_progress::Column::TaskId.eq(task_id))
+            .filter(task_file_progress::Column::SheetName.is_null())
+            .one(fx.db.as_ref())
+            .await
+            .unwrap()
+            .unwrap();
+        (p.success_count, p.error_count, p.total_rows, p.updated_count, p.merged_count)
+    }
+
+    #[test]
+    fn test_merge_record_data_fills_only_empty_fields() {
+        let existing = serde_json::json!({"1": "张三", "2": "", "3": null})
+            .as_object()
+            .unwrap()
+            .clone();
+
+        let merged = merge_record_data(&existing, &serde_json::json!({"1": "李四", "2": "a@example.com", "3": "北京"}))
+            .unwrap();
+        assert_eq!(merged, serde_json::json!({"1": "张三", "2": "a@example.com", "3": "北京"}));
+
+        // 新行的空值不覆盖，也不算补全
+        assert_eq!(merge_record_data(&existing, &serde_json::json!({"1": "李四", "2": " ", "3": null})), None);
+    }
+
+    #[tokio::test]
+    async fn test_validate_reviewed_mapping() {
+        let fx = setup("skip").await;
+        let mapping = |field: &str, column: i32| FieldMapping {
+            field_name: field.to_string(),
+            column_index: column,
+            column_header: String::new(),
+            confidence: 1.0,
+        };
+
+        assert!(validate_reviewed_mapping(0, &[mapping("name", 0), mapping("phone", 1)], &fx.fields).is_ok());
+        assert!(validate_reviewed_mapping(-1, &[mapping("name", 0)], &fx.fields).is_err());
+        assert!(validate_reviewed_mapping(0, &[mapping("unknown", 0)], &fx.fields).is_err());
+        assert!(validate_reviewed_mapping(0, &[mapping("name", -1)], &fx.fields).is_err());
+        assert!(validate_reviewed_mapping(0, &[mapping("name", 0), mapping("name", 2)], &fx.fields).is_err());
+
+        // 计算字段由表达式生成，不能映射到列
+        let mut computed = fx.fields[2].clone();
+        computed.expression = Some("{姓名}".to_string());
+        assert!(validate_reviewed_mapping(0, &[mapping("email", 2)], &[computed]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_rows_are_persisted() {
+        let fx = setup("skip").await;
+        let task_id = create_task(&fx).await;
+        let file = csv_file(
+            "姓名,手机号,邮箱\n张三,13800138000,a@example.com\n李四,13900139000,not-an-email\n张三,13800138000,b@example.com\n",
+        );
+        import(&fx, &task_id, &[file], &[], None).await;
+
+        let records = records(&fx).await;
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].3, "success");
+        assert_eq!(records[0].4, None);
+        assert_eq!(records[1].3, "validation_error");
+        assert_eq!(records[1].4.as_deref(), Some("邮箱 验证失败"));
+        assert_eq!(records[2].3, "duplicate");
+        assert!(records[2].4.as_deref().unwrap().contains("重复"), "{:?}", records[2].4);
+
+        assert_eq!(file_counts(&fx, &task_id).await, (1, 2, 3, 0, 0));
+        let task = ProcessingTask::find_by_id(&task_id).one(fx.db.as_ref()).await.unwrap().unwrap();
+        assert_eq!((task.success_count, task.error_count), (1, 2));
+    }
+
+    /// 人工确认模式：导入在映射确认前等待，确认或取消时立即继续
+    #[tokio::test]
+    async fn test_review_mapping_waits_for_confirmation_or_cancel() {
+        let fx = setup("skip").await;
+        let file = csv_file("姓名,手机号\n张三,13800138000\n");
+        let start = |task_id: String, control: Arc<TaskControl>| {
+            let job = ProcessingJob {
+                task_id,
+                project: fx.project.clone(),
+                fields: fx.fields.clone(),
+                file_paths: vec![file.clone()],
+                file_specs: vec![],
+                ai: None,
+                options: ProcessingOptions { review_mapping: true, mapping_mode: MappingMode::Rules },
+                resume: None,
+            };
+            let db = fx.db.clone();
+            tokio::spawn(async move { process_files(EventSink(None), db, &job, control).await })
+        };
+        let awaiting = |control: Arc<TaskControl>| async move {
+            let wait = async {
+                while !control.awaiting_review.load(Ordering::SeqCst) {
+                    tokio::task::yield_now().await;
+                }
+            };
+            tokio::time::timeout(tokio::time::Duration::from_secs(5), wait).await.unwrap();
+        };
+
+        // 取消时立即结束，不导入（也不保存映射模板）
+        let control = Arc::new(TaskControl::default());
+        let handle = start(create_task(&fx).await, control.clone());
+        awaiting(control.clone()).await;
+        control.cancel();
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), handle).await.unwrap().unwrap().unwrap();
+        assert!(records(&fx).await.is_empty());
+
+        // 确认映射后继续导入
+        let control = Arc::new(TaskControl::default());
+        let handle = start(create_task(&fx).await, control.clone());
+        awaiting(control.clone()).await;
+        assert!(records(&fx).await.is_empty());
+        control.submit_review(ColumnMappingResponse {
+            header_row: 0,
+            mappings: [("name", 0, "姓名"), ("phone", 1, "手机号")]
+                .map(|(field_name, column_index, column_header)| FieldMapping {
+                    field_name: field_name.to_string(),
+                    column_index,
+                    column_header: column_header.to_string(),
+                    confidence: 1.0,
+                })
+                .to_vec(),
+            confidence: 1.0,
+            unmatched_columns: vec![],
+        });
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), handle).await.unwrap().unwrap().unwrap();
+        assert_eq!(names(&records(&fx).await), vec!["张三"]);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_update_overwrites_existing_record() {
+        let fx = setup("update").await;
+        let first = create_task(&fx).await;
+        import(&fx, &first, &[csv_file("姓名,手机号,邮箱\n张三,13800138000,a@example.com\n")], &[], None).await;
+
+        let second = create_task(&fx).await;
+        import(&fx, &second, &[csv_file("姓名,手机号,邮箱\n张三丰,13800138000,\n")], &[], None).await;
+
+        let records = records(&fx).await;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].0, "张三丰");
+        assert_eq!(records[0].2, "");
+        assert_eq!(file_counts(&fx, &second).await, (0, 0, 1, 1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_dedup_merge_fills_empty_fields() {
+        let fx = setup("merge").await;
+        let first = create_task(&fx).await;
+        import(&fx, &first, &[csv_file("姓名,手机号,邮箱\n张三,13800138000,\n")], &[], None).await;
+
+        // 第一行补全邮箱；第二行已无可补全的字段，作为重复行保存
+        let second = create_task(&fx).await;
+        let file = csv_file("姓名,手机号,邮箱\n张三丰,13800138000,a@example.com\n张三,13800138000,b@example.com\n");
+        import(&fx, &second, &[file], &[], None).await;
+
+        let records = records(&fx).await;
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].0.as_str(), records[0].2.as_str()), ("张三", "a@example.com"));
+        assert_eq!(records[1].3, "duplicate");
+        assert_eq!(file_counts(&fx, &second).await, (0, 1, 2, 0, 1));
+    }
+
+    fn xlsx_file(sheets: &[(&str, Vec<Vec<&str>>)]) -> String {
+        let path = std::env::temp_dir().join(format!("redata_test_{}.xlsx", uuid::Uuid::new_v4()));
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        for (name, rows) in sheets {
+            let sheet = workbook.add_worksheet().set_name(*name).unwrap();
+            for (r, row) in rows.iter().enumerate() {
+                for (c, cell) in row.iter().enumerate().filter(|(_, cell)| !cell.is_empty()) {
+                    sheet.write_string(r as u32, c as u16, *cell).unwrap();
+                }
+            }
+        }
+        workbook.save(&path).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn names(records: &[(String, String, String, String, Option<String>)]) -> Vec<&str> {
+        records.iter().map(|r| r.0.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_file_spec_selects_sheets_and_rows() {
+        let fx = setup("skip").await;
+        let task_id = create_task(&fx).await;
+        let customers = vec![
+            vec!["2024年客户名单"],
+            vec!["姓名", "手机号", "邮箱"],
+            vec!["张一", "13800000001", "a1@example.com"],
+            vec!["张二", "13800000002", "a2@example.com"],
+            vec!["张三", "13800000003", "a3@example.com"],
+            vec!["张四", "13800000004", "a4@example.com"],
+            vec!["合计：4 人"],
+        ];
+        let other = vec![vec!["姓名", "手机号", "邮箱"], vec!["李四", "13900000001", "b@example.com"]];
+        let path = xlsx_file(&[("客户", customers), ("汇总", other.clone()), ("补充", other.clone()), ("备用", other)]);
+
+        // 只处理「客户」「补充」，再排除「补充」；表头在标题行之下，只导入第 4~5 行
+        let spec = FileSpec {
+            file_path: path.clone(),
+            include_sheets: vec!["客户".to_string(), "补充".to_string()],
+            exclude_sheets: vec!["补充".to_string()],
+            header_row: Some(1),
+            start_row: Some(3),
+            end_row: Some(4),
+            empty_row_threshold: None,
+        };
+        import(&fx, &task_id, std::slice::from_ref(&path), &[spec], None).await;
+        let _ = std::fs::remove_file(&path);
+
+        let records = records(&fx).await;
+        assert_eq!(names(&records), vec!["张二", "张三"]);
+        assert_eq!(records[0].1, "13800000002");
+        assert!(records.iter().all(|r| r.3 == "success"));
+        assert_eq!(file_counts(&fx, &task_id).await, (2, 0, 2, 0, 0));
+
+        let sheets: Vec<String> = TaskFileProgress::find()
+            .filter(task_file_progress::Column::TaskId.eq(&task_id))
+            .filter(task_file_progress::Column::SheetName.is_not_null())
+            .all(fx.db.as_ref())
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|p| p.sheet_name)
+            .collect();
+        assert_eq!(sheets, vec!["客户"]);
+    }
+
+    #[tokio::test]
+    async fn test_empty_row_threshold() {
+        // 两段数据之间隔着 2 个空行
+        let content = "姓名,手机号,邮箱\n张一,13800000001,\n,,\n,,\n张二,13800000002,\n";
+        for (threshold, expected) in [(Some(2), vec!["张一"]), (Some(0), vec!["张一", "张二"]), (None, vec!["张一", "张二"])] {
+            let fx = setup("skip").await;
+            let task_id = create_task(&fx).await;
+            let path = csv_file(content);
+            let spec = FileSpec { file_path: path.clone(), empty_row_threshold: threshold, ..Default::default() };
+            import(&fx, &task_id, std::slice::from_ref(&path), &[spec], None).await;
+            let _ = std::fs::remove_file(&path);
+
+            // 空行不计入总行数
+            assert_eq!(names(&records(&fx).await), expected, "threshold {:?}", threshold);
+            assert_eq!(file_counts(&fx, &task_id).await.2, expected.len() as i32);
+        }
+    }
+
+    /// 在指定目录下以固定文件名生成 xlsx（用于构造不同目录下的同名文件）
+    fn xlsx_file_named(name: &str, sheets: &[(&str, Vec<Vec<&str>>)]) -> String {
+        let dir = std::env::temp_dir().join(format!("redata_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::rename(xlsx_file(sheets), &path).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_interrupt_mid_sheet() {
+        let fx = setup("skip").await;
+        let task_id = create_task(&fx).await;
+        let header = vec!["姓名", "手机号", "邮箱"];
+        let people: Vec<(String, String)> =
+            (0..1200).map(|i| (format!("客户{}", i), format!("139{:08}", i))).collect();
+        let mut big = vec![header.clone()];
+        big.extend(people.iter().map(|(name, phone)| vec![name.as_str(), phone.as_str(), ""]));
+
+        // 不同目录下的同名文件：第一个已完成，第二个在 Sheet B 中途中断
+        let done = vec![header.clone(), vec!["张三", "13800000001", ""], vec!["李四", "13800000002", ""]];
+        let first = xlsx_file_named("客户.xlsx", &[("S", done)]);
+        let second = xlsx_file_named("客户.xlsx", &[("A", vec![header.clone(), vec!["王五", "13800000003", ""]]), ("B", big)]);
+        let paths = vec![first.clone(), second.clone()];
+
+        // 模拟中断：只导入到第 600 行（检查点随最后一批记录落库），再把 Sheet B 与文件 2 的进度改回处理中
+        let spec = FileSpec { file_path: second.clone(), end_row: Some(600), ..Default::default() };
+        import(&fx, &task_id, &paths, &[spec], None).await;
+        assert_eq!(records(&fx).await.len(), 603);
+        for sql in [
+            "UPDATE task_file_progress SET sheet_phase = 'importing', success_count = 0, total_rows = 0 \
+             WHERE task_id = ? AND sheet_name = 'B' AND file_path = ?",
+            "UPDATE task_file_progress SET file_phase = 'processing' WHERE task_id = ? AND sheet_name IS NULL AND file_path = ?",
+        ] {
+            fx.db
+                .execute(Statement::from_sql_and_values(
+                    fx.db.get_database_backend(),
+                    sql,
+                    vec![task_id.clone().into(), second.clone().into()],
+                ))
+                .await
+                .unwrap();
+        }
+
+        let task_model = ProcessingTask::find_by_id(&task_id).one(fx.db.as_ref()).await.unwrap().unwrap();
+        let resume = ResumeState::load(fx.db.as_ref(), &task_model).await.unwrap();
+        let checkpoint = resume.checkpoint.as_ref().unwrap();
+        assert_eq!((checkpoint.file_index, checkpoint.sheet_name.as_str(), checkpoint.last_row), (1, "B", Some(600)));
+        assert_eq!(resume.done_files.keys().collect::<Vec<_>>(), vec![&first]);
+        assert!(resume.done_sheets.contains_key(&(second.clone(), "A".to_string())));
+
+        import(&fx, &task_id, &paths, &[], Some(resume)).await;
+
+        // 已导入的行不重复，剩余的行全部导入
+        let imported = records(&fx).await;
+        assert_eq!(imported.len(), 1203);
+        assert!(imported.iter().all(|r| r.3 == "success"), "{:?}", imported.iter().find(|r| r.3 != "success"));
+        let mut expected: Vec<String> = ["张三", "李四", "王五"].map(str::to_string).to_vec();
+        expected.extend(people.into_iter().map(|(name, _)| name));
+        assert_eq!(imported.into_iter().map(|r| r.0).collect::<Vec<_>>(), expected);
+
+        let progress = TaskFileProgress::find()
+            .filter(task_file_progress::Column::TaskId.eq(&task_id))
+            .filter(task_file_progress::Column::SheetName.is_null())
+            .filter(task_file_progress::Column::FilePath.eq(&second))
+            .one(fx.db.as_ref())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((progress.file_phase.as_str(), progress.success_count, progress.total_rows), ("done", 1201, 1201));
+    }
+}
@@ -73,6 +73,7 @@ pub async fn get_project_groups(
     // 获取每个分组的项目数量
     let projects = Project::find()
         .filter(project::Column::GroupId.is_not_null())
+        .filter(project::Column::DeletedAt.is_null())
         .all(db.inner().as_ref())
         .await
         .map_err(|e| format!("数据库错误: {}", e))?;
@@ -125,6 +126,7 @@ pub async fn get_project_groups_flat(
     // 获取每个分组的项目数量
     let projects = Project::find()
         .filter(project::Column::GroupId.is_not_null())
+        .filter(project::Column::DeletedAt.is_null())
         .all(db.inner().as_ref())
         .await
         .map_err(|e| format!("数据库错误: {}", e))?;
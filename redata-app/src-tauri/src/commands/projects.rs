@@ -4,14 +4,19 @@
 // 前端通过 invoke() 调用这些命令，零网络开销
 
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, Set, Statement, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::backend::infrastructure::{
-    persistence::models::{project, Project},
+    persistence::models::{project, record, task, Project, ProjectRecord, ProcessingTask},
 };
+use super::record_history::{actor_label, append_history_from, Snapshot, OP_PURGE};
+
+/// 有未结束的导入任务（处理中、已暂停、等待确认映射）的项目不能移入回收站
+const ACTIVE_TASK_STATUSES: [&str; 3] = ["processing", "paused", "awaiting_review"];
 
 /// 项目创建请求
 #[derive(Debug, Deserialize)]
@@ -42,6 +47,30 @@ pub struct ProjectResponse {
     pub dedup_strategy: String,
     pub created_at: String,
     pub updated_at: Option<String>,
+    pub deleted_at: Option<String>,
+}
+
+/// 回收站中的项目
+#[derive(Debug, Serialize)]
+pub struct RecycledProjectResponse {
+    #[serde(flatten)]
+    pub project: ProjectResponse,
+    /// 保留的成功记录数（不含被拒绝的行）
+    pub record_count: u64,
+}
+
+/// 彻底删除项目的结果（各表删除的行数）
+#[derive(Debug, Serialize)]
+pub struct PurgeProjectResponse {
+    pub project_id: i32,
+    pub fields: u64,
+    pub records: u64,
+    pub tasks: u64,
+    pub task_file_progress: u64,
+    pub batches: u64,
+    pub mapping_templates: u64,
+    pub mapping_cache: u64,
+    pub rolled_back_records: u64,
 }
 
 impl From<project::Model> for ProjectResponse {
@@ -57,16 +86,18 @@ impl From<project::Model> for ProjectResponse {
             dedup_strategy: model.dedup_strategy,
             created_at: model.created_at.to_rfc3339(),
             updated_at: model.updated_at.map(|dt| dt.to_rfc3339()),
+            deleted_at: model.deleted_at.map(|dt| dt.to_rfc3339()),
         }
     }
 }
 
-/// 获取项目列表
+/// 获取项目列表（不含回收站中的项目）
 #[tauri::command]
 pub async fn get_projects(
     db: tauri::State<'_, Arc<DatabaseConnection>>,
 ) -> Result<Vec<ProjectResponse>, String> {
     let projects = Project::find()
+        .filter(project::Column::DeletedAt.is_null())
         .all(db.inner().as_ref())
         .await
         .map_err(|e| format!("Database error: {}", e))?;
@@ -90,8 +121,15 @@ pub async fn create_project(
         .await
         .map_err(|e| format!("Database error: {}", e))?;
 
-    if existing.is_some() {
-        return Err(format!("Project with name '{}' already exists", name));
+    match existing {
+        Some(p) if p.deleted_at.is_some() => {
+            return Err(format!(
+                "Project with name '{}' is in the recycle bin, restore or purge it first",
+                name
+            ));
+        }
+        Some(_) => return Err(format!("Project with name '{}' already exists", name)),
+        None => {}
     }
 
     // 创建项目
@@ -104,6 +142,7 @@ pub async fn create_project(
         dedup_strategy: Set("skip".to_string()),
         created_at: Set(now),
         updated_at: Set(None),
+        deleted_at: Set(None),
         ..Default::default()
     };
 
@@ -115,7 +154,7 @@ pub async fn create_project(
     Ok(result.into())
 }
 
-/// 获取单个项目
+/// 获取单个项目（回收站中的项目视为不存在）
 #[tauri::command]
 pub async fn get_project(
     db: tauri::State<'_, Arc<DatabaseConnection>>,
@@ -125,6 +164,7 @@ pub async fn get_project(
         .one(db.inner().as_ref())
         .await
         .map_err(|e| format!("Database error: {}", e))?
+        .filter(|p| p.deleted_at.is_none())
         .ok_or_else(|| format!("Project {} not found", id))?;
 
     Ok(project.into())
@@ -141,11 +181,12 @@ pub async fn update_project(
     dedup_fields: Option<Vec<String>>,
     dedup_strategy: Option<String>,
 ) -> Result<ProjectResponse, String> {
-    // 查找项目
+    // 查找项目（回收站中的项目不可修改）
     let project = Project::find_by_id(id)
         .one(db.inner().as_ref())
         .await
         .map_err(|e| format!("Database error: {}", e))?
+        .filter(|p| p.deleted_at.is_none())
         .ok_or_else(|| format!("Project {} not found", id))?;
 
     // 更新项目
@@ -182,25 +223,319 @@ pub async fn update_project(
     Ok(result.into())
 }
 
-/// 删除项目
+/// 删除项目（移入回收站，保留字段、记录、任务等全部关联数据，可恢复）
 #[tauri::command]
 pub async fn delete_project(
     db: tauri::State<'_, Arc<DatabaseConnection>>,
     id: i32,
 ) -> Result<(), String> {
+    move_to_recycle_bin(db.inner().as_ref(), id).await
+}
+
+/// 将项目移入回收站（Tauri Command 与 HTTP API 共用）
+pub async fn move_to_recycle_bin(conn: &DatabaseConnection, id: i32) -> Result<(), String> {
     // 查找项目
     let project = Project::find_by_id(id)
-        .one(db.inner().as_ref())
+        .one(conn)
         .await
         .map_err(|e| format!("Database error: {}", e))?
+        .filter(|p| p.deleted_at.is_none())
         .ok_or_else(|| format!("Project {} not found", id))?;
 
-    // 删除项目
-    let active: project::ActiveModel = project.into();
+    // 有正在进行的导入任务时不允许删除
+    let running = ProcessingTask::find()
+        .filter(task::Column::ProjectId.eq(id))
+        .filter(task::Column::Status.is_in(ACTIVE_TASK_STATUSES))
+        .count(conn)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    if running > 0 {
+        return Err(format!("Project {} has {} running task(s), cancel them first", id, running));
+    }
+
+    // 移入回收站
+    let mut active: project::ActiveModel = project.into();
+    active.deleted_at = Set(Some(chrono::Utc::now()));
     active
-        .delete(db.inner().as_ref())
+        .update(conn)
         .await
         .map_err(|e| format!("Database error: {}", e))?;
 
     Ok(())
 }
+
+/// 获取回收站中的项目（按删除时间倒序）
+#[tauri::command]
+pub async fn get_deleted_projects(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+) -> Result<Vec<RecycledProjectResponse>, String> {
+    let projects = Project::find()
+        .filter(project::Column::DeletedAt.is_not_null())
+        .order_by_desc(project::Column::DeletedAt)
+        .all(db.inner().as_ref())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut responses = Vec::with_capacity(projects.len());
+    for project in projects {
+        let record_count = ProjectRecord::find()
+            .filter(record::Column::ProjectId.eq(project.id))
+            .filter(record::Column::Status.eq("success"))
+            .count(db.inner().as_ref())
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        responses.push(RecycledProjectResponse { project: project.into(), record_count });
+    }
+
+    Ok(responses)
+}
+
+/// 从回收站恢复项目
+#[tauri::command]
+pub async fn restore_project(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    id: i32,
+) -> Result<ProjectResponse, String> {
+    restore_from_recycle_bin(db.inner().as_ref(), id).await.map(Into::into)
+}
+
+async fn restore_from_recycle_bin(conn: &DatabaseConnection, id: i32) -> Result<project::Model, String> {
+    let project = Project::find_by_id(id)
+        .one(conn)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| format!("Project {} not found", id))?;
+    if project.deleted_at.is_none() {
+        return Err(format!("Project {} is not in the recycle bin", id));
+    }
+
+    let mut active: project::ActiveModel = project.into();
+    active.deleted_at = Set(None);
+    active.updated_at = Set(Some(chrono::Utc::now()));
+    active
+        .update(conn)
+        .await
+        .map_err(|e| format!("Database error: {}", e))
+}
+
+/// 彻底删除回收站中的项目及其全部关联数据（单个事务），返回各表删除的行数
+#[tauri::command]
+pub async fn purge_project(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    id: i32,
+    actor: Option<String>,
+) -> Result<PurgeProjectResponse, String> {
+    purge(db.inner().as_ref(), id, actor.as_deref()).await
+}
+
+async fn purge(conn: &DatabaseConnection, id: i32, actor: Option<&str>) -> Result<PurgeProjectResponse, String> {
+    let project = Project::find_by_id(id)
+        .one(conn)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| format!("Project {} not found", id))?;
+    if project.deleted_at.is_none() {
+        return Err(format!("Project {} is not in the recycle bin, delete it first", id));
+    }
+
+    let txn = conn.begin().await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    // 记录历史不随项目删除，先写入被删除记录的最终版本
+    append_history_from(
+        &txn,
+        "project_records",
+        "project_id = ?",
+        vec![id.into()],
+        OP_PURGE,
+        Snapshot::Before,
+        &actor_label(actor),
+    )
+    .await?;
+
+    // 按依赖顺序删除：任务文件进度依赖任务，其余表直接按 project_id 删除
+    let mut deleted = Vec::with_capacity(9);
+    for sql in [
+        "DELETE FROM task_file_progress WHERE task_id IN (SELECT id FROM processing_tasks WHERE project_id = ?)",
+        "DELETE FROM processing_tasks WHERE project_id = ?",
+        "DELETE FROM project_records WHERE project_id = ?",
+        "DELETE FROM project_fields WHERE project_id = ?",
+        "DELETE FROM batches WHERE project_id = ?",
+        "DELETE FROM mapping_templates WHERE project_id = ?",
+        "DELETE FROM ai_mapping_cache WHERE project_id = ?",
+        "DELETE FROM rolled_back_records WHERE project_id = ?",
+        "DELETE FROM projects WHERE id = ?",
+    ] {
+        let result = txn
+            .execute(Statement::from_sql_and_values(txn.get_database_backend(), sql, vec![id.into()]))
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        deleted.push(result.rows_affected());
+    }
+
+    txn.commit().await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let response = PurgeProjectResponse {
+        project_id: id,
+        task_file_progress: deleted[0],
+        tasks: deleted[1],
+        records: deleted[2],
+        fields: deleted[3],
+        batches: deleted[4],
+        mapping_templates: deleted[5],
+        mapping_cache: deleted[6],
+        rolled_back_records: deleted[7],
+    };
+    tracing::info!("Purged project {}: {:?}", id, response);
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::infrastructure::persistence::migrations::run_migrations;
+    use sea_orm::Database;
+
+    async fn setup() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&db).await.unwrap();
+        db
+    }
+
+    async fn exec(db: &DatabaseConnection, sql: &str) {
+        db.execute(Statement::from_string(db.get_database_backend(), sql.to_string()))
+            .await
+            .unwrap();
+    }
+
+    async fn count(db: &DatabaseConnection, sql: &str) -> i64 {
+        db.query_one(Statement::from_string(db.get_database_backend(), sql.to_string()))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get_by_index(0)
+            .unwrap()
+    }
+
+    /// 创建项目，并在 8 张关联表中各写入一行
+    async fn seed_project(db: &DatabaseConnection, id: i32) {
+        let now = "2026-01-01T00:00:00+00:00";
+        for sql in [
+            format!("INSERT INTO projects (id, name, created_at) VALUES ({id}, 'p{id}', '{now}')"),
+            format!("INSERT INTO project_fields (project_id, field_name, field_label, field_type, created_at) VALUES ({id}, 'phone', '手机号', 'phone', '{now}')"),
+            format!("INSERT INTO processing_tasks (id, project_id, status, created_at) VALUES ('task-{id}', {id}, 'completed', '{now}')"),
+            format!("INSERT INTO task_file_progress (task_id, file_name, created_at) VALUES ('task-{id}', 'a.xlsx', '{now}')"),
+            format!("INSERT INTO project_records (project_id, data, created_at) VALUES ({id}, '{{}}', '{now}')"),
+            format!("INSERT INTO batches (batch_number, project_id, created_at) VALUES ('BATCH_{id}', {id}, '{now}')"),
+            format!("INSERT INTO mapping_templates (project_id, header_fingerprint, created_at) VALUES ({id}, 'fp', '{now}')"),
+            format!("INSERT INTO ai_mapping_cache (project_id, header_fingerprint, field_hash, mapping, created_at) VALUES ({id}, 'fp', 'h', '{{}}', '{now}')"),
+            format!("INSERT INTO rolled_back_records (id, project_id, created_at, rolled_back_at) VALUES ({}, {id}, '{now}', '{now}')", 100 + id),
+        ] {
+            exec(db, &sql).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_blocked_by_unfinished_tasks() {
+        let db = setup().await;
+        seed_project(&db, 1).await;
+
+        for status in ACTIVE_TASK_STATUSES {
+            exec(&db, &format!("UPDATE processing_tasks SET status = '{}' WHERE project_id = 1", status)).await;
+            let err = move_to_recycle_bin(&db, 1).await.unwrap_err();
+            assert!(err.contains("running task"), "{}: {}", status, err);
+        }
+
+        exec(&db, "UPDATE processing_tasks SET status = 'completed' WHERE project_id = 1").await;
+        move_to_recycle_bin(&db, 1).await.unwrap();
+        assert!(Project::find_by_id(1).one(&db).await.unwrap().unwrap().deleted_at.is_some());
+        // 关联数据保留，重复删除视为不存在
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM project_records WHERE project_id = 1").await, 1);
+        assert!(move_to_recycle_bin(&db, 1).await.unwrap_err().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_restore_project() {
+        let db = setup().await;
+        seed_project(&db, 1).await;
+        assert!(restore_from_recycle_bin(&db, 1).await.unwrap_err().contains("not in the recycle bin"));
+
+        move_to_recycle_bin(&db, 1).await.unwrap();
+        let restored = restore_from_recycle_bin(&db, 1).await.unwrap();
+        assert!(restored.deleted_at.is_none());
+        assert!(restored.updated_at.is_some());
+        assert!(restore_from_recycle_bin(&db, 2).await.unwrap_err().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_purge_removes_all_project_data() {
+        let db = setup().await;
+        seed_project(&db, 1).await;
+        seed_project(&db, 2).await;
+        assert!(purge(&db, 1, None).await.unwrap_err().contains("delete it first"));
+
+        move_to_recycle_bin(&db, 1).await.unwrap();
+        let response = purge(&db, 1, Some("admin")).await.unwrap();
+        assert_eq!(
+            [
+                response.fields,
+                response.records,
+                response.tasks,
+                response.task_file_progress,
+                response.batches,
+                response.mapping_templates,
+                response.mapping_cache,
+                response.rolled_back_records,
+            ],
+            [1; 8]
+        );
+
+        // 9 张表中项目 1 的数据全部删除，项目 2 不受影响
+        for (table, project_column) in [
+            ("projects", "id"),
+            ("project_fields", "project_id"),
+            ("processing_tasks", "project_id"),
+            ("project_records", "project_id"),
+            ("batches", "project_id"),
+            ("mapping_templates", "project_id"),
+            ("ai_mapping_cache", "project_id"),
+            ("rolled_back_records", "project_id"),
+        ] {
+            let sql = format!("SELECT COUNT(*) FROM {} WHERE {} = ", table, project_column);
+            assert_eq!(count(&db, &format!("{}1", sql)).await, 0, "{}", table);
+            assert_eq!(count(&db, &format!("{}2", sql)).await, 1, "{}", table);
+        }
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM task_file_progress WHERE task_id = 'task-1'").await, 0);
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM task_file_progress WHERE task_id = 'task-2'").await, 1);
+
+        // 被删除记录的最终版本写入历史
+        assert_eq!(
+            count(&db, "SELECT COUNT(*) FROM record_history WHERE project_id = 1 AND operation = 'purge' AND actor = 'admin'").await,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_purge_is_one_transaction() {
+        let db = setup().await;
+        seed_project(&db, 1).await;
+        move_to_recycle_bin(&db, 1).await.unwrap();
+
+        // 最后一步（删除项目本身）失败时，之前各表的删除全部回滚
+        exec(
+            &db,
+            "CREATE TRIGGER block_project_delete BEFORE DELETE ON projects \
+             BEGIN SELECT RAISE(ABORT, 'blocked'); END",
+        )
+        .await;
+        assert!(purge(&db, 1, None).await.unwrap_err().contains("blocked"));
+
+        for table in ["project_fields", "processing_tasks", "project_records", "batches", "mapping_templates", "ai_mapping_cache", "rolled_back_records"] {
+            assert_eq!(count(&db, &format!("SELECT COUNT(*) FROM {} WHERE project_id = 1", table)).await, 1, "{}", table);
+        }
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM task_file_progress").await, 1);
+        assert_eq!(count(&db, "SELECT COUNT(*) FROM record_history").await, 0);
+    }
+}
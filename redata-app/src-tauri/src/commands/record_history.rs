@@ -0,0 +1,487 @@
+// 记录修改历史（record_history，只追加）
+//
+// 编辑、修正、删除、撤回、恢复及导入覆盖等操作在修改 project_records 的同一事务中
+// 写入历史：修改前后的 data 与状态、操作类型、时间和操作者。表上的触发器禁止
+// UPDATE / DELETE，彻底删除项目时历史也会保留。
+//
+// 每条历史的修改前版本都可以通过 revert_record 回退（回退本身也会记入历史）。
+
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Statement, TransactionTrait,
+};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::sync::Arc;
+
+use crate::backend::infrastructure::persistence::models::{field, Project, ProjectField, ProjectRecord};
+use super::records::{find_duplicate_of, RecordResponse};
+
+/// 未指定操作者时的默认标签
+pub const DEFAULT_ACTOR: &str = "local_user";
+
+// 操作类型
+pub const OP_UPDATE: &str = "update";
+pub const OP_REVALIDATE: &str = "revalidate";
+pub const OP_DELETE: &str = "delete";
+pub const OP_ROLLBACK: &str = "rollback";
+pub const OP_RESTORE: &str = "restore";
+pub const OP_IMPORT_UPDATE: &str = "import_update";
+pub const OP_PURGE: &str = "purge";
+pub const OP_REVERT: &str = "revert";
+
+/// 记录的一个版本（data JSON 字符串 + 状态 + 错误信息）
+#[derive(Debug, Clone, Copy)]
+pub struct RecordVersion<'a> {
+    pub data: &'a str,
+    pub status: &'a str,
+    pub error_message: Option<&'a str>,
+}
+
+/// 批量写入历史时，查询到的 data 作为修改前还是修改后的版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Snapshot {
+    /// 删除、撤回：记录从 project_records 移除
+    Before,
+    /// 恢复：记录重新放回 project_records
+    After,
+}
+
+/// 操作者标签（空值使用 DEFAULT_ACTOR）
+pub fn actor_label(actor: Option<&str>) -> String {
+    actor
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(DEFAULT_ACTOR)
+        .to_string()
+}
+
+/// 追加一条记录历史
+pub async fn append_history<C: ConnectionTrait>(
+    conn: &C,
+    record_id: i32,
+    project_id: i32,
+    operation: &str,
+    before: Option<RecordVersion<'_>>,
+    after: Option<RecordVersion<'_>>,
+    actor: &str,
+) -> Result<(), String> {
+    conn.execute(Statement::from_sql_and_values(
+        conn.get_database_backend(),
+        "INSERT INTO record_history \
+         (record_id, project_id, operation, before_data, before_status, before_error_message, \
+          after_data, after_status, after_error_message, actor, created_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        vec![
+            record_id.into(),
+            project_id.into(),
+            operation.into(),
+            before.map(|v| v.data.to_string()).into(),
+            before.map(|v| v.status.to_string()).into(),
+            before.and_then(|v| v.error_message.map(str::to_string)).into(),
+            after.map(|v| v.data.to_string()).into(),
+            after.map(|v| v.status.to_string()).into(),
+            after.and_then(|v| v.error_message.map(str::to_string)).into(),
+            actor.into(),
+            chrono::Utc::now().to_rfc3339().into(),
+        ],
+    ))
+    .await
+    .map_err(|e| format!("写入记录历史失败: {}", e))?;
+    Ok(())
+}
+
+/// 为表中满足条件的记录批量追加历史（table 为 project_records 或 rolled_back_records），返回写入条数
+pub async fn append_history_from<C: ConnectionTrait>(
+    conn: &C,
+    table: &str,
+    where_sql: &str,
+    params: Vec<sea_orm::Value>,
+    operation: &str,
+    snapshot: Snapshot,
+    actor: &str,
+) -> Result<u64, String> {
+    let (data_col, status_col, error_col) = match snapshot {
+        Snapshot::Before => ("before_data", "before_status", "before_error_message"),
+        Snapshot::After => ("after_data", "after_status", "after_error_message"),
+    };
+    let mut values: Vec<sea_orm::Value> = vec![
+        operation.into(),
+        actor.into(),
+        chrono::Utc::now().to_rfc3339().into(),
+    ];
+    values.extend(params);
+
+    let result = conn
+        .execute(Statement::from_sql_and_values(
+            conn.get_database_backend(),
+            format!(
+                "INSERT INTO record_history \
+                 (record_id, project_id, operation, {data_col}, {status_col}, {error_col}, actor, created_at) \
+                 SELECT id, project_id, ?, data, status, error_message, ?, ? FROM {table} WHERE {where_sql}",
+                data_col = data_col,
+                status_col = status_col,
+                error_col = error_col,
+                table = table,
+                where_sql = where_sql
+            ),
+            values,
+        ))
+        .await
+        .map_err(|e| format!("写入记录历史失败: {}", e))?;
+    Ok(result.rows_affected())
+}
+
+// ============ 响应结构 ============
+
+#[derive(Debug, Serialize)]
+pub struct RecordHistoryEntry {
+    pub id: i64,
+    pub record_id: i32,
+    pub project_id: i32,
+    pub operation: String,
+    pub before_data: Option<JsonValue>,
+    pub before_status: Option<String>,
+    pub before_error_message: Option<String>,
+    pub after_data: Option<JsonValue>,
+    pub after_status: Option<String>,
+    pub after_error_message: Option<String>,
+    pub actor: String,
+    pub created_at: String,
+}
+
+impl RecordHistoryEntry {
+    fn from_row(row: &sea_orm::QueryResult) -> Self {
+        let json = |col: &str| {
+            row.try_get::<Option<String>>("", col)
+                .ok()
+                .flatten()
+                .map(|s| serde_json::from_str(&s).unwrap_or(JsonValue::Object(Default::default())))
+        };
+        Self {
+            id: row.try_get("", "id").unwrap_or_default(),
+            record_id: row.try_get("", "record_id").unwrap_or_default(),
+            project_id: row.try_get("", "project_id").unwrap_or_default(),
+            operation: row.try_get("", "operation").unwrap_or_default(),
+            before_data: json("before_data"),
+            before_status: row.try_get("", "before_status").unwrap_or_default(),
+            before_error_message: row.try_get("", "before_error_message").unwrap_or_default(),
+            after_data: json("after_data"),
+            after_status: row.try_get("", "after_status").unwrap_or_default(),
+            after_error_message: row.try_get("", "after_error_message").unwrap_or_default(),
+            actor: row.try_get("", "actor").unwrap_or_default(),
+            created_at: row.try_get("", "created_at").unwrap_or_default(),
+        }
+    }
+}
+
+const HISTORY_COLUMNS: &str = "id, record_id, project_id, operation, before_data, before_status, \
+     before_error_message, after_data, after_status, after_error_message, actor, created_at";
+
+// ============ Tauri Commands ============
+
+/// 获取记录的修改历史（最新在前）
+#[tauri::command]
+pub async fn get_record_history(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    record_id: i32,
+) -> Result<Vec<RecordHistoryEntry>, String> {
+    list_history(db.inner().as_ref(), record_id).await
+}
+
+async fn list_history(conn: &DatabaseConnection, record_id: i32) -> Result<Vec<RecordHistoryEntry>, String> {
+    let rows = conn
+        .query_all(Statement::from_sql_and_values(
+            conn.get_database_backend(),
+            format!(
+                "SELECT {} FROM record_history WHERE record_id = ? ORDER BY id DESC",
+                HISTORY_COLUMNS
+            ),
+            vec![record_id.into()],
+        ))
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    Ok(rows.iter().map(RecordHistoryEntry::from_row).collect())
+}
+
+/// 将记录回退到某条历史修改之前的版本
+///
+/// - 记录仍存在：覆盖 data、状态与错误信息
+/// - 记录已删除：按原 ID 重新创建（来源文件、原始行等导入信息无法恢复）
+/// - 记录随导入撤回：需先恢复导入
+///
+/// 回退为成功状态时重新执行去重检查，与现有记录重复的标记为 duplicate。
+#[tauri::command]
+pub async fn revert_record(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    history_id: i64,
+    actor: Option<String>,
+) -> Result<RecordResponse, String> {
+    revert(db.inner().as_ref(), history_id, &actor_label(actor.as_deref())).await
+}
+
+async fn revert(conn: &DatabaseConnection, history_id: i64, actor: &str) -> Result<RecordResponse, String> {
+    let backend = conn.get_database_backend();
+
+    let entry = conn
+        .query_one(Statement::from_sql_and_values(
+            backend,
+            format!("SELECT {} FROM record_history WHERE id = ?", HISTORY_COLUMNS),
+            vec![history_id.into()],
+        ))
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+        .map(|row| RecordHistoryEntry::from_row(&row))
+        .ok_or_else(|| format!("历史记录 {} 不存在", history_id))?;
+
+    let (Some(data), Some(mut status)) = (entry.before_data, entry.before_status) else {
+        return Err("该操作之前记录不存在，无法回退".to_string());
+    };
+    let mut error_message = entry.before_error_message;
+
+    let project = Project::find_by_id(entry.project_id)
+        .one(conn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+        .ok_or_else(|| format!("项目 {} 已彻底删除，无法回退记录", entry.project_id))?;
+    let fields = ProjectField::find()
+        .filter(field::Column::ProjectId.eq(entry.project_id))
+        .filter(field::Column::IsDeleted.eq(false))
+        .all(conn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    let data_str = serde_json::to_string(&data).map_err(|e| format!("JSON 序列化错误: {}", e))?;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let txn = conn.begin().await.map_err(|e| format!("开启事务失败: {}", e))?;
+    let current = ProjectRecord::find_by_id(entry.record_id)
+        .one(&txn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+    if current.is_none() {
+        let rolled_back = txn
+            .query_one(Statement::from_sql_and_values(
+                backend,
+                "SELECT id FROM rolled_back_records WHERE id = ?",
+                vec![entry.record_id.into()],
+            ))
+            .await
+            .map_err(|e| format!("数据库错误: {}", e))?;
+        if rolled_back.is_some() {
+            return Err(format!("记录 {} 已随导入撤回，请先恢复导入", entry.record_id));
+        }
+    }
+
+    // 回退期间其他记录可能已导入相同的去重值
+    if status == "success" {
+        let values = data.as_object().cloned().unwrap_or_default();
+        if let Some(dup_id) = find_duplicate_of(&txn, &project, &fields, &values, Some(entry.record_id)).await? {
+            status = "duplicate".to_string();
+            error_message = Some(format!("与记录 #{} 重复（回退时检查）", dup_id));
+        }
+    }
+    let target = RecordVersion { data: &data_str, status: &status, error_message: error_message.as_deref() };
+
+    match current {
+        Some(current) => {
+            append_history(
+                &txn,
+                current.id,
+                current.project_id,
+                OP_REVERT,
+                Some(RecordVersion {
+                    data: &current.data,
+                    status: &current.status,
+                    error_message: current.error_message.as_deref(),
+                }),
+                Some(target),
+                actor,
+            )
+            .await?;
+            txn.execute(Statement::from_sql_and_values(
+                backend,
+                "UPDATE project_records SET data = ?, status = ?, error_message = ?, updated_at = ? WHERE id = ?",
+                vec![
+                    data_str.clone().into(),
+                    status.clone().into(),
+                    error_message.clone().into(),
+                    now.into(),
+                    current.id.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| format!("数据库错误: {}", e))?;
+        }
+        None => {
+            txn.execute(Statement::from_sql_and_values(
+                backend,
+                "INSERT INTO project_records (id, project_id, data, status, error_message, created_at, updated_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                vec![
+                    entry.record_id.into(),
+                    entry.project_id.into(),
+                    data_str.clone().into(),
+                    status.clone().into(),
+                    error_message.clone().into(),
+                    now.clone().into(),
+                    now.into(),
+                ],
+            ))
+            .await
+            .map_err(|e| format!("数据库错误: {}", e))?;
+            append_history(&txn, entry.record_id, entry.project_id, OP_REVERT, None, Some(target), actor).await?;
+        }
+    }
+
+    let record = ProjectRecord::find_by_id(entry.record_id)
+        .one(&txn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+        .ok_or_else(|| format!("记录 {} 不存在", entry.record_id))?;
+    txn.commit().await.map_err(|e| format!("提交事务失败: {}", e))?;
+
+    tracing::info!("Reverted record {} to version before history {} by {}", entry.record_id, history_id, actor);
+    Ok(record.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::infrastructure::persistence::migrations::run_migrations;
+    use sea_orm::Database;
+
+    async fn exec(db: &DatabaseConnection, sql: &str) {
+        db.execute(Statement::from_string(db.get_database_backend(), sql.to_string()))
+            .await
+            .unwrap();
+    }
+
+    /// 项目 1：手机号（字段 1）为去重字段；记录 1 为验证失败，记录 2 为成功
+    async fn setup() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&db).await.unwrap();
+        let now = "2026-01-01T00:00:00+00:00";
+        for sql in [
+            format!("INSERT INTO projects (id, name, dedup_enabled, created_at) VALUES (1, 'p', 1, '{now}')"),
+            format!("INSERT INTO project_fields (id, project_id, field_name, field_label, field_type, is_dedup_key, created_at) VALUES (1, 1, 'phone', '手机号', 'phone', 1, '{now}')"),
+            format!("INSERT INTO project_records (id, project_id, data, status, error_message, created_at) VALUES (1, 1, '{{\"1\":\"bad\"}}', 'validation_error', '手机号 格式错误', '{now}')"),
+            format!("INSERT INTO project_records (id, project_id, data, status, created_at) VALUES (2, 1, '{{\"1\":\"13800000002\"}}', 'success', '{now}')"),
+        ] {
+            exec(&db, &sql).await;
+        }
+        db
+    }
+
+    fn version<'a>(data: &'a str, status: &'a str, error_message: Option<&'a str>) -> Option<RecordVersion<'a>> {
+        Some(RecordVersion { data, status, error_message })
+    }
+
+    /// 模拟 delete_record：写入删除历史后移除记录，返回历史 ID
+    async fn delete(db: &DatabaseConnection, id: i32) -> i64 {
+        let record = ProjectRecord::find_by_id(id).one(db).await.unwrap().unwrap();
+        append_history(
+            db,
+            id,
+            1,
+            OP_DELETE,
+            version(&record.data, &record.status, record.error_message.as_deref()),
+            None,
+            "tester",
+        )
+        .await
+        .unwrap();
+        exec(db, &format!("DELETE FROM project_records WHERE id = {}", id)).await;
+        list_history(db, id).await.unwrap()[0].id
+    }
+
+    async fn record(db: &DatabaseConnection, id: i32) -> (String, String, Option<String>) {
+        let r = ProjectRecord::find_by_id(id).one(db).await.unwrap().unwrap();
+        (r.data, r.status, r.error_message)
+    }
+
+    #[tokio::test]
+    async fn test_history_is_listed_newest_first_and_append_only() {
+        let db = setup().await;
+        let before = version("{}", "validation_error", Some("缺少手机号"));
+        let after = version(r#"{"1":"bad"}"#, "validation_error", Some("手机号 格式错误"));
+        append_history(&db, 1, 1, OP_UPDATE, before, after, "tester").await.unwrap();
+        append_history_from(&db, "project_records", "id = ?", vec![1.into()], OP_ROLLBACK, Snapshot::Before, "tester")
+            .await
+            .unwrap();
+
+        let history = list_history(&db, 1).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].operation, OP_ROLLBACK);
+        assert_eq!(history[0].before_status.as_deref(), Some("validation_error"));
+        assert_eq!(history[0].before_error_message.as_deref(), Some("手机号 格式错误"));
+        assert_eq!(history[0].after_data, None);
+        assert_eq!(history[1].operation, OP_UPDATE);
+        assert_eq!(history[1].before_error_message.as_deref(), Some("缺少手机号"));
+        assert_eq!(history[1].after_data, Some(serde_json::json!({"1": "bad"})));
+        assert!(list_history(&db, 2).await.unwrap().is_empty());
+
+        for sql in ["UPDATE record_history SET actor = 'x'", "DELETE FROM record_history"] {
+            let tampered = db.execute(Statement::from_string(db.get_database_backend(), sql.to_string())).await;
+            assert!(tampered.is_err(), "{}", sql);
+        }
+        assert_eq!(list_history(&db, 1).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_revert_existing_record_restores_status_and_error() {
+        let db = setup().await;
+        let before = version(r#"{"1":"bad"}"#, "validation_error", Some("手机号 格式错误"));
+        let after = version(r#"{"1":"13800000001"}"#, "success", None);
+        append_history(&db, 1, 1, OP_REVALIDATE, before, after, "tester").await.unwrap();
+        exec(&db, r#"UPDATE project_records SET data = '{"1":"13800000001"}', status = 'success', error_message = NULL WHERE id = 1"#).await;
+        let history_id = list_history(&db, 1).await.unwrap()[0].id;
+
+        let reverted = revert(&db, history_id, "tester").await.unwrap();
+        assert_eq!(reverted.status, "validation_error");
+        assert_eq!(
+            record(&db, 1).await,
+            (r#"{"1":"bad"}"#.to_string(), "validation_error".to_string(), Some("手机号 格式错误".to_string()))
+        );
+
+        // 回退本身记入历史，且可以再次回退
+        let history = list_history(&db, 1).await.unwrap();
+        assert_eq!(history[0].operation, OP_REVERT);
+        assert_eq!(history[0].before_status.as_deref(), Some("success"));
+        assert_eq!(history[0].after_error_message.as_deref(), Some("手机号 格式错误"));
+        revert(&db, history[0].id, "tester").await.unwrap();
+        assert_eq!(record(&db, 1).await.1, "success");
+    }
+
+    #[tokio::test]
+    async fn test_revert_recreates_deleted_record() {
+        let db = setup().await;
+
+        // 验证失败的记录按原 ID 重建，保留原错误信息
+        let history_id = delete(&db, 1).await;
+        revert(&db, history_id, "tester").await.unwrap();
+        assert_eq!(
+            record(&db, 1).await,
+            (r#"{"1":"bad"}"#.to_string(), "validation_error".to_string(), Some("手机号 格式错误".to_string()))
+        );
+        let history = list_history(&db, 1).await.unwrap();
+        assert_eq!((history[0].operation.as_str(), history[0].before_data.is_none()), (OP_REVERT, true));
+
+        // 删除后重新导入了相同手机号：重建的记录标记为重复
+        let history_id = delete(&db, 2).await;
+        exec(&db, r#"INSERT INTO project_records (id, project_id, data, status, created_at) VALUES (3, 1, '{"1":"13800000002"}', 'success', '2026-01-02T00:00:00+00:00')"#).await;
+        revert(&db, history_id, "tester").await.unwrap();
+        let (_, status, error_message) = record(&db, 2).await;
+        assert_eq!(status, "duplicate");
+        assert!(error_message.unwrap().contains("#3"));
+        assert_eq!(list_history(&db, 2).await.unwrap()[0].after_status.as_deref(), Some("duplicate"));
+
+        // 修改前不存在的版本、随导入撤回的记录不能回退
+        let err = revert(&db, list_history(&db, 2).await.unwrap()[0].id, "tester").await.unwrap_err();
+        assert!(err.contains("无法回退"), "{}", err);
+        let history_id = delete(&db, 3).await;
+        exec(&db, r#"INSERT INTO rolled_back_records (id, project_id, data, status, created_at, rolled_back_at) VALUES (3, 1, '{}', 'success', '2026-01-02T00:00:00+00:00', '2026-01-03T00:00:00+00:00')"#).await;
+        let err = revert(&db, history_id, "tester").await.unwrap_err();
+        assert!(err.contains("请先恢复导入"), "{}", err);
+    }
+}
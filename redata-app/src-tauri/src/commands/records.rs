@@ -12,7 +12,16 @@ use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::backend::infrastructure::persistence::models::{record, ProjectRecord};
+use crate::backend::infrastructure::persistence::models::{
+    field, project, record, Project, ProjectField, ProjectRecord,
+};
+use super::data_utils::{compile_computed_fields, json_value_to_string, FieldTypeOptions, RowCleaner};
+use super::address_utils::{address_components_key, ADDRESS_COMPONENTS};
+use super::currency_utils::{currency_unit_key, CURRENCY_UNIT_COMPONENT};
+use super::record_history::{
+    actor_label, append_history, append_history_from, RecordVersion, Snapshot, OP_DELETE, OP_REVALIDATE,
+    OP_UPDATE,
+};
 
 // ============ 响应结构 ============
 
@@ -32,6 +41,25 @@ pub struct RecordResponse {
     pub updated_at: Option<String>,
 }
 
+/// 字段级验证错误
+#[derive(Debug, Serialize)]
+pub struct FieldValidationError {
+    pub field_id: String,
+    pub field_label: String,
+    pub message: String,
+}
+
+/// 记录修正（重新验证）结果
+#[derive(Debug, Serialize)]
+pub struct RevalidateRecordResponse {
+    pub record: RecordResponse,
+    /// 是否已转为 success
+    pub promoted: bool,
+    pub field_errors: Vec<FieldValidationError>,
+    /// 与之重复的成功记录 ID
+    pub duplicate_of: Option<i32>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct QueryRecordsResponse {
     pub records: Vec<RecordResponse>,
@@ -218,7 +246,7 @@ pub async fn query_records(
     }
     if let Some(f) = &filters {
         for (field_id, value) in f {
-            conditions.push(format!("json_extract(data, '$.{}') = ?", field_id));
+            conditions.push(format!("{} = ?", json_text_expr(field_id)));
             params.push(value.clone());
         }
     }
@@ -308,39 +336,233 @@ pub async fn update_record(
     db: tauri::State<'_, Arc<DatabaseConnection>>,
     id: i32,
     data: JsonValue,
+    actor: Option<String>,
+) -> Result<RecordResponse, String> {
+    update(db.inner().as_ref(), id, data, actor.as_deref()).await
+}
+
+async fn update(
+    conn: &DatabaseConnection,
+    id: i32,
+    data: JsonValue,
+    actor: Option<&str>,
 ) -> Result<RecordResponse, String> {
     let record = ProjectRecord::find_by_id(id)
-        .one(db.inner().as_ref())
+        .one(conn)
         .await
         .map_err(|e| format!("数据库错误: {}", e))?
         .ok_or_else(|| format!("记录 {} 不存在", id))?;
 
-    let data_str = serde_json::to_string(&data)
+    let JsonValue::Object(submitted) = data else {
+        return Err("记录数据必须为 JSON 对象".to_string());
+    };
+    let fields = ProjectField::find()
+        .filter(field::Column::ProjectId.eq(record.project_id))
+        .filter(field::Column::IsDeleted.eq(false))
+        .all(conn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    // 与导入相同的清洗（数值 / 金额存为 JSON 数字等），计算字段、地址解析结果随编辑后的值重新计算；
+    // 只保存不改变状态，验证错误由修正验证（revalidate_record）处理，计算失败的字段保留原值
+    let field_options: HashMap<i32, FieldTypeOptions> = fields
+        .iter()
+        .map(|f| (f.id, FieldTypeOptions::from_field(f)))
+        .collect();
+    let mut cleaner = RowCleaner::new(&fields, &field_options, submitted.clone());
+    for f in fields.iter().filter(|f| f.expression.is_none()) {
+        if let Some(raw) = submitted.get(&f.id.to_string()).map(json_value_to_string) {
+            cleaner.add(f, &raw);
+        }
+    }
+    let (cleaned, errors) = cleaner.finish(&compile_computed_fields(&fields), None);
+    for (_, message) in errors {
+        tracing::debug!("记录 {} 编辑后字段未通过验证: {}", id, message);
+    }
+
+    let data_str = serde_json::to_string(&JsonValue::Object(cleaned))
         .map_err(|e| format!("JSON 序列化错误: {}", e))?;
     let now = chrono::Utc::now().to_rfc3339();
 
+    let txn = conn.begin().await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    append_history(
+        &txn,
+        record.id,
+        record.project_id,
+        OP_UPDATE,
+        Some(RecordVersion { data: &record.data, status: &record.status, error_message: record.error_message.as_deref() }),
+        Some(RecordVersion { data: &data_str, status: &record.status, error_message: record.error_message.as_deref() }),
+        &actor_label(actor),
+    )
+    .await?;
+
     let mut active: record::ActiveModel = record.into();
     active.data = Set(data_str);
     active.updated_at = Set(Some(now));
 
     let result = active
-        .update(db.inner().as_ref())
+        .update(&txn)
         .await
         .map_err(|e| format!("数据库错误: {}", e))?;
+    txn.commit().await.map_err(|e| format!("提交事务失败: {}", e))?;
 
     Ok(result.into())
 }
 
+/// 修正记录并重新验证
+///
+/// 对编辑后的 data 重新执行 clean_value 和字段 validation_rule：
+/// - 验证失败：保存修改，状态为 validation_error，返回字段级错误
+/// - 验证通过但与已有成功记录重复：状态为 duplicate
+/// - 全部通过：状态转为 success
+#[tauri::command]
+pub async fn revalidate_record(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    id: i32,
+    data: JsonValue,
+    actor: Option<String>,
+) -> Result<RevalidateRecordResponse, String> {
+    revalidate(db.inner().as_ref(), id, data, actor.as_deref()).await
+}
+
+async fn revalidate(
+    conn: &DatabaseConnection,
+    id: i32,
+    data: JsonValue,
+    actor: Option<&str>,
+) -> Result<RevalidateRecordResponse, String> {
+    let record = ProjectRecord::find_by_id(id)
+        .one(conn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+        .ok_or_else(|| format!("记录 {} 不存在", id))?;
+
+    let project = Project::find_by_id(record.project_id)
+        .one(conn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+        .ok_or_else(|| format!("项目 {} 不存在", record.project_id))?;
+
+    let fields = ProjectField::find()
+        .filter(field::Column::ProjectId.eq(record.project_id))
+        .filter(field::Column::IsDeleted.eq(false))
+        .all(conn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    // 重新清洗并验证每个字段（与导入共用同一套逻辑；未定义的 key 原样保留）
+    let submitted = data.as_object().cloned().unwrap_or_default();
+    let field_options: HashMap<i32, FieldTypeOptions> = fields
+        .iter()
+        .map(|f| (f.id, FieldTypeOptions::from_field(f)))
+        .collect();
+    let mut cleaner = RowCleaner::new(&fields, &field_options, submitted.clone());
+
+    // 计算字段稍后按表达式重新求值
+    for f in fields.iter().filter(|f| f.expression.is_none()) {
+        match submitted.get(&f.id.to_string()).map(json_value_to_string) {
+            Some(raw) => cleaner.add(f, &raw),
+            None if f.is_required => cleaner.error(f, format!("{} 为必填项", f.field_label)),
+            None => {}
+        }
+    }
+
+    // 派生字段：电话附加号码、身份证（出生日期、年龄、性别、省份）、计算字段、地址（省 / 市 / 区县）
+    let computed_fields = compile_computed_fields(&fields);
+    let (cleaned, errors) = cleaner.finish(&computed_fields, None);
+    let field_errors: Vec<FieldValidationError> = errors
+        .into_iter()
+        .map(|(f, message)| FieldValidationError {
+            field_id: f.id.to_string(),
+            field_label: f.field_label.clone(),
+            message,
+        })
+        .collect();
+
+    // 验证通过后重新执行去重检查（排除自身）
+    let mut duplicate_of = None;
+    if field_errors.is_empty() {
+        duplicate_of = find_duplicate_of(conn, &project, &fields, &cleaned, Some(id)).await?;
+    }
+
+    let (status, error_message) = if !field_errors.is_empty() {
+        let messages: Vec<&str> = field_errors.iter().map(|e| e.message.as_str()).collect();
+        ("validation_error".to_string(), Some(messages.join("; ")))
+    } else if let Some(dup_id) = duplicate_of {
+        ("duplicate".to_string(), Some(format!("与记录 #{} 重复", dup_id)))
+    } else {
+        ("success".to_string(), None)
+    };
+    let promoted = status == "success" && record.status != "success";
+
+    let data_str = serde_json::to_string(&JsonValue::Object(cleaned))
+        .map_err(|e| format!("JSON 序列化错误: {}", e))?;
+
+    let txn = conn.begin().await.map_err(|e| format!("开启事务失败: {}", e))?;
+    append_history(
+        &txn,
+        record.id,
+        record.project_id,
+        OP_REVALIDATE,
+        Some(RecordVersion { data: &record.data, status: &record.status, error_message: record.error_message.as_deref() }),
+        Some(RecordVersion { data: &data_str, status: &status, error_message: error_message.as_deref() }),
+        &actor_label(actor),
+    )
+    .await?;
+
+    let mut active: record::ActiveModel = record.into();
+    active.data = Set(data_str);
+    active.status = Set(status);
+    active.error_message = Set(error_message);
+    active.updated_at = Set(Some(chrono::Utc::now().to_rfc3339()));
+
+    let result = active
+        .update(&txn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+    txn.commit().await.map_err(|e| format!("提交事务失败: {}", e))?;
+
+    Ok(RevalidateRecordResponse {
+        record: result.into(),
+        promoted,
+        field_errors,
+        duplicate_of,
+    })
+}
+
 /// 删除单条记录
 #[tauri::command]
 pub async fn delete_record(
     db: tauri::State<'_, Arc<DatabaseConnection>>,
     id: i32,
+    actor: Option<String>,
 ) -> Result<(), String> {
+    let txn = db.inner().as_ref().begin().await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    let Some(record) = ProjectRecord::find_by_id(id)
+        .one(&txn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+    else {
+        return Ok(());
+    };
+
+    append_history(
+        &txn,
+        record.id,
+        record.project_id,
+        OP_DELETE,
+        Some(RecordVersion { data: &record.data, status: &record.status, error_message: record.error_message.as_deref() }),
+        None,
+        &actor_label(actor.as_deref()),
+    )
+    .await?;
     ProjectRecord::delete_by_id(id)
-        .exec(db.inner().as_ref())
+        .exec(&txn)
         .await
         .map_err(|e| format!("数据库错误: {}", e))?;
+    txn.commit().await.map_err(|e| format!("提交事务失败: {}", e))?;
 
     Ok(())
 }
@@ -350,12 +572,27 @@ pub async fn delete_record(
 pub async fn delete_project_records(
     db: tauri::State<'_, Arc<DatabaseConnection>>,
     project_id: i32,
+    actor: Option<String>,
 ) -> Result<u64, String> {
+    let txn = db.inner().as_ref().begin().await
+        .map_err(|e| format!("开启事务失败: {}", e))?;
+    append_history_from(
+        &txn,
+        "project_records",
+        "project_id = ?",
+        vec![project_id.into()],
+        OP_DELETE,
+        Snapshot::Before,
+        &actor_label(actor.as_deref()),
+    )
+    .await?;
+
     let result = ProjectRecord::delete_many()
         .filter(record::Column::ProjectId.eq(project_id))
-        .exec(db.inner().as_ref())
+        .exec(&txn)
         .await
         .map_err(|e| format!("数据库错误: {}", e))?;
+    txn.commit().await.map_err(|e| format!("提交事务失败: {}", e))?;
 
     Ok(result.rows_affected)
 }
@@ -389,26 +626,69 @@ pub async fn check_duplicate(
     project_id: i32,
     dedup_values: HashMap<String, String>,
 ) -> Result<Option<i32>, String> {
-    if dedup_values.is_empty() {
+    find_duplicate_record(db.inner().as_ref(), project_id, &dedup_values, None).await
+}
+
+/// 按项目的去重设置查找与 data 重复的成功记录（未启用去重时返回 None，可排除指定记录）
+pub(crate) async fn find_duplicate_of<C: ConnectionTrait>(
+    conn: &C,
+    project: &project::Model,
+    fields: &[field::Model],
+    data: &serde_json::Map<String, JsonValue>,
+    exclude_id: Option<i32>,
+) -> Result<Option<i32>, String> {
+    if !project.dedup_enabled {
         return Ok(None);
     }
+    let dedup_values: HashMap<String, String> = fields
+        .iter()
+        .filter(|f| f.is_dedup_key)
+        .filter_map(|f| {
+            let key = f.id.to_string();
+            let value = data.get(&key).map(json_value_to_string)?;
+            Some((key, value))
+        })
+        .collect();
+    find_duplicate_record(conn, project.id, &dedup_values, exclude_id).await
+}
 
-    let mut conditions = vec!["project_id = ?".to_string()];
+/// 查找与给定去重字段值相同的成功记录（空值不参与比对，可排除指定记录）
+async fn find_duplicate_record<C: ConnectionTrait>(
+    conn: &C,
+    project_id: i32,
+    dedup_values: &HashMap<String, String>,
+    exclude_id: Option<i32>,
+) -> Result<Option<i32>, String> {
+    let mut conditions = vec!["project_id = ?".to_string(), "status = 'success'".to_string()];
     let mut params: Vec<sea_orm::Value> = vec![project_id.into()];
 
-    for (field_id, value) in &dedup_values {
-        conditions.push(format!("json_extract(data, '$.{}') = ?", field_id));
+    for (field_id, value) in dedup_values {
+        if value.trim().is_empty() {
+            continue;
+        }
+        let field_id = validate_field_id(field_id)?;
+        conditions.push(format!("{} = ?", json_text_expr(&field_id)));
         params.push(value.clone().into());
     }
 
+    // 没有可比对的字段值，视为不重复
+    if params.len() == 1 {
+        return Ok(None);
+    }
+
+    if let Some(exclude) = exclude_id {
+        conditions.push("id != ?".to_string());
+        params.push(exclude.into());
+    }
+
     let sql = format!(
         "SELECT id FROM project_records WHERE {} LIMIT 1",
         conditions.join(" AND ")
     );
 
-    let result = db.inner().as_ref()
+    let result = conn
         .query_one(Statement::from_sql_and_values(
-            db.inner().as_ref().get_database_backend(),
+            conn.get_database_backend(),
             &sql,
             params,
         ))
@@ -437,12 +717,13 @@ pub enum FilterOperator {
     Between,
     IsEmpty,
     IsNotEmpty,
+    Includes,  // 包含任一值：list 字段的某一项（或单值字段的值）等于给定值之一
 }
 
 /// 单个筛选条件
 #[derive(Debug, serde::Deserialize)]
 pub struct FilterCondition {
-    pub field: String,
+    pub field: String,  // 字段 ID；地址字段可用 "{field_id}.province" 等引用解析出的组成部分
     pub operator: FilterOperator,
     pub value: Option<JsonValue>,  // 单值或范围值 [start, end]
 }
@@ -537,6 +818,9 @@ pub async fn query_records_advanced(
 }
 
 /// 获取字段唯一值（用于下拉筛选）
+///
+/// field_id 为地址字段的组成部分（如 "5.city"）时，返回解析出的省 / 市 / 区县取值，用于分面；
+/// list 字段返回各项的唯一值
 #[tauri::command]
 pub async fn get_field_distinct_values(
     db: tauri::State<'_, Arc<DatabaseConnection>>,
@@ -546,29 +830,18 @@ pub async fn get_field_distinct_values(
     limit: Option<i32>,
 ) -> Result<Vec<String>, String> {
     let limit = limit.unwrap_or(100).min(500);
+    let field_id = resolve_field_path(&field_id)?;
 
-    let sql = if let Some(s) = &search {
-        if !s.trim().is_empty() {
-            format!(
-                "SELECT DISTINCT json_extract(data, '$.{}') as value FROM project_records \
-                 WHERE project_id = ? AND json_extract(data, '$.{}') LIKE ? \
-                 ORDER BY value LIMIT ?",
-                field_id, field_id
-            )
-        } else {
-            format!(
-                "SELECT DISTINCT json_extract(data, '$.{}') as value FROM project_records \
-                 WHERE project_id = ? ORDER BY value LIMIT ?",
-                field_id
-            )
-        }
-    } else {
-        format!(
-            "SELECT DISTINCT json_extract(data, '$.{}') as value FROM project_records \
-             WHERE project_id = ? ORDER BY value LIMIT ?",
-            field_id
-        )
+    // json_each 将 list 等数组值展开为单项（单值字段即值本身）
+    let search_condition = match &search {
+        Some(s) if !s.trim().is_empty() => format!(" AND {} LIKE ?", json_each_text_expr()),
+        _ => String::new(),
     };
+    let sql = format!(
+        "SELECT DISTINCT {} as value FROM project_records, json_each(project_records.data, '$.{}') j \
+         WHERE project_id = ?{} ORDER BY value LIMIT ?",
+        json_each_text_expr(), field_id, search_condition
+    );
 
     let params: Vec<sea_orm::Value> = if let Some(s) = &search {
         if !s.trim().is_empty() {
@@ -636,16 +909,6 @@ pub async fn get_source_files(
     Ok(files)
 }
 
-/// 辅助函数：JsonValue 转字符串
-fn json_value_to_string(v: &JsonValue) -> String {
-    match v {
-        JsonValue::String(s) => s.clone(),
-        JsonValue::Number(n) => n.to_string(),
-        JsonValue::Bool(b) => b.to_string(),
-        _ => v.to_string(),
-    }
-}
-
 // ============ xlsx 导出辅助 ============
 
 /// 验证字段 ID 格式，防止 SQL 注入
@@ -662,6 +925,73 @@ fn validate_field_id(field: &str) -> Result<String, String> {
     }
 }
 
+/// 解析筛选字段引用，返回 data 中的 JSON 路径（不含 "$."）
+/// - "5"：字段值
+/// - "5.city"：地址字段解析出的组成部分，存储在 "5_address" 下
+/// - "5.unit"：金额字段的币种，存储在 "5_unit" 下
+fn resolve_field_path(field: &str) -> Result<String, String> {
+    match field.split_once('.') {
+        Some((field_id, component)) => {
+            let field_id = validate_field_id(field_id)?;
+            if component == CURRENCY_UNIT_COMPONENT {
+                Ok(currency_unit_key(&field_id))
+            } else if ADDRESS_COMPONENTS.contains(&component) {
+                Ok(format!("{}.{}", address_components_key(&field_id), component))
+            } else {
+                Err(format!("无效的字段组成部分: {}", component))
+            }
+        }
+        None => validate_field_id(field),
+    }
+}
+
+/// 辅助函数：字段值的文本表达式（number 字段存储为 JSON 数字，按文本比较时统一转为字符串；
+/// boolean 字段存储为 JSON 布尔值，转为 'true' / 'false'）
+fn json_text_expr(field_id: &str) -> String {
+    format!(
+        "(CASE json_type(data, '$.{id}') WHEN 'true' THEN 'true' WHEN 'false' THEN 'false' \
+         ELSE CAST(json_extract(data, '$.{id}') AS TEXT) END)",
+        id = field_id
+    )
+}
+
+/// 辅助函数：json_each 表（别名 j）中单项的文本表达式，规则同 json_text_expr
+fn json_each_text_expr() -> &'static str {
+    "(CASE j.type WHEN 'true' THEN 'true' WHEN 'false' THEN 'false' ELSE CAST(j.value AS TEXT) END)"
+}
+
+/// 辅助函数：比较值是否均为数字（是则按数值比较，否则按文本比较，如 YYYY-MM-DD 日期）
+fn all_numeric(values: &[&JsonValue]) -> bool {
+    values.iter().all(|v| match v {
+        JsonValue::Number(_) => true,
+        JsonValue::String(s) => s.trim().parse::<f64>().is_ok(),
+        _ => false,
+    })
+}
+
+/// 辅助函数：比较运算条件（数字按 REAL 比较，其余按文本比较）
+///
+/// 按数值比较时只纳入 JSON 数字和纯数字文本：空值、「待定」等文本被 CAST 为 0，
+/// 「1,200」会被截断为 1，均不参与比较（历史数据中的数值字符串由迁移转为 JSON 数字）
+fn compare_condition(field_id: &str, op: &str, values: &[&JsonValue]) -> String {
+    let text_expr = json_text_expr(field_id);
+    if all_numeric(values) {
+        let placeholders = vec!["CAST(? AS REAL)"; values.len()].join(" AND ");
+        format!(
+            "((json_type(data, '$.{id}') IN ('integer', 'real') \
+             OR ({text} != '' AND {text} NOT GLOB '*[^0-9.+-]*')) \
+             AND CAST(json_extract(data, '$.{id}') AS REAL) {op} {placeholders})",
+            id = field_id,
+            text = text_expr,
+            op = op,
+            placeholders = placeholders
+        )
+    } else {
+        let placeholders = vec!["?"; values.len()].join(" AND ");
+        format!("{} {} {}", text_expr, op, placeholders)
+    }
+}
+
 /// 辅助函数：根据高级筛选请求构建 WHERE 子句和参数列表
 fn build_where_for_filter(project_id: i32, filter: &AdvancedFilterRequest) -> (String, Vec<String>) {
     let conjunction = filter.conjunction.as_deref().unwrap_or("and");
@@ -696,11 +1026,11 @@ fn build_where_for_filter(project_id: i32, filter: &AdvancedFilterRequest) -> (S
     let mut field_conditions: Vec<String> = Vec::new();
     for cond in &filter.conditions {
         // 验证字段 ID 格式，防止 SQL 注入
-        let field_id = match validate_field_id(&cond.field) {
+        let field_id = match resolve_field_path(&cond.field) {
             Ok(id) => id,
             Err(_) => continue, // 跳过无效字段
         };
-        let field_expr = format!("json_extract(data, '$.{}')", field_id);
+        let field_expr = json_text_expr(&field_id);
         match &cond.operator {
             FilterOperator::Eq => {
                 if let Some(v) = &cond.value {
@@ -740,25 +1070,25 @@ fn build_where_for_filter(project_id: i32, filter: &AdvancedFilterRequest) -> (S
             }
             FilterOperator::Gt => {
                 if let Some(v) = &cond.value {
-                    field_conditions.push(format!("{} > ?", field_expr));
+                    field_conditions.push(compare_condition(&field_id, ">", &[v]));
                     params.push(json_value_to_string(v));
                 }
             }
             FilterOperator::Lt => {
                 if let Some(v) = &cond.value {
-                    field_conditions.push(format!("{} < ?", field_expr));
+                    field_conditions.push(compare_condition(&field_id, "<", &[v]));
                     params.push(json_value_to_string(v));
                 }
             }
             FilterOperator::Gte => {
                 if let Some(v) = &cond.value {
-                    field_conditions.push(format!("{} >= ?", field_expr));
+                    field_conditions.push(compare_condition(&field_id, ">=", &[v]));
                     params.push(json_value_to_string(v));
                 }
             }
             FilterOperator::Lte => {
                 if let Some(v) = &cond.value {
-                    field_conditions.push(format!("{} <= ?", field_expr));
+                    field_conditions.push(compare_condition(&field_id, "<=", &[v]));
                     params.push(json_value_to_string(v));
                 }
             }
@@ -766,13 +1096,30 @@ fn build_where_for_filter(project_id: i32, filter: &AdvancedFilterRequest) -> (S
                 if let Some(v) = &cond.value {
                     if let Some(arr) = v.as_array() {
                         if arr.len() >= 2 {
-                            field_conditions.push(format!("{} BETWEEN ? AND ?", field_expr));
+                            field_conditions.push(compare_condition(&field_id, "BETWEEN", &[&arr[0], &arr[1]]));
                             params.push(json_value_to_string(&arr[0]));
                             params.push(json_value_to_string(&arr[1]));
                         }
                     }
                 }
             }
+            FilterOperator::Includes => {
+                if let Some(v) = &cond.value {
+                    let values: Vec<&JsonValue> = match v.as_array() {
+                        Some(arr) => arr.iter().collect(),
+                        None => vec![v],
+                    };
+                    if !values.is_empty() {
+                        field_conditions.push(format!(
+                            "EXISTS (SELECT 1 FROM json_each(data, '$.{}') j WHERE {} IN ({}))",
+                            field_id,
+                            json_each_text_expr(),
+                            vec!["?"; values.len()].join(", ")
+                        ));
+                        params.extend(values.into_iter().map(json_value_to_string));
+                    }
+                }
+            }
             FilterOperator::IsEmpty => {
                 field_conditions.push(format!(
                     "({} IS NULL OR {} = '' OR {} = 'null')",
@@ -911,13 +1258,25 @@ pub async fn export_records_xlsx(
 
         let mut col: u16 = 0;
         for field_id in &field_ids {
-            let value = data
-                .get(field_id)
-                .map(|v| json_value_to_string(v))
-                .unwrap_or_default();
-            worksheet
-                .write(row_num, col, value.as_str())
-                .map_err(|e| format!("xlsx 写入错误: {}", e))?;
+            // 数字字段写为数值单元格，其余写为文本
+            match data.get(field_id) {
+                Some(JsonValue::Number(n)) => {
+                    worksheet
+                        .write(row_num, col, n.as_f64().unwrap_or_default())
+                        .map_err(|e| format!("xlsx 写入错误: {}", e))?;
+                }
+                Some(JsonValue::Bool(b)) => {
+                    worksheet
+                        .write(row_num, col, if *b { "是" } else { "否" })
+                        .map_err(|e| format!("xlsx 写入错误: {}", e))?;
+                }
+                other => {
+                    let value = other.map(json_value_to_string).unwrap_or_default();
+                    worksheet
+                        .write(row_num, col, value.as_str())
+                        .map_err(|e| format!("xlsx 写入错误: {}", e))?;
+                }
+            }
             col += 1;
         }
         if include_import_time {
@@ -946,3 +1305,310 @@ pub async fn export_records_xlsx(
 
     Ok(rows.len() as u64)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::infrastructure::persistence::migrations::run_migrations;
+    use sea_orm::Database;
+
+    /// 测试项目：手机号（first 策略，其余号码写入备用电话）、备用电话、邮箱（正则校验）
+    async fn setup() -> (DatabaseConnection, i32, Vec<field::Model>) {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&db).await.unwrap();
+        let project = project::ActiveModel {
+            name: Set("测试项目".to_string()),
+            dedup_enabled: Set(false),
+            dedup_strategy: Set("skip".to_string()),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let mut fields = vec![];
+        for (name, label, field_type, rule, type_options) in [
+            ("phone", "手机号", "phone", None, Some(r#"{"phone_policy":"first","phone_extra_field":"backup_phone"}"#)),
+            ("backup_phone", "备用电话", "phone", None, None),
+            ("email", "邮箱", "email", Some(r"^[^@\s]+@[^@\s]+\.[a-z]+$"), None),
+        ] {
+            let field = field::ActiveModel {
+                project_id: Set(project.id),
+                field_name: Set(name.to_string()),
+                field_label: Set(label.to_string()),
+                field_type: Set(field_type.to_string()),
+                validation_rule: Set(rule.map(str::to_string)),
+                type_options: Set(type_options.map(str::to_string)),
+                created_at: Set(chrono::Utc::now()),
+                ..Default::default()
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+            fields.push(field);
+        }
+        (db, project.id, fields)
+    }
+
+    async fn rejected_record(db: &DatabaseConnection, project_id: i32, data: JsonValue) -> i32 {
+        record::ActiveModel {
+            project_id: Set(project_id),
+            data: Set(data.to_string()),
+            status: Set("validation_error".to_string()),
+            error_message: Set(Some("邮箱 验证失败".to_string())),
+            created_at: Set(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap()
+        .id
+    }
+
+    #[tokio::test]
+    async fn test_revalidate_promotes_fixed_record() {
+        let (db, project_id, fields) = setup().await;
+        let [phone, email] = [fields[0].id, fields[2].id].map(|id| id.to_string());
+        let id = rejected_record(&db, project_id, serde_json::json!({&phone: "13800138000", &email: "bad"})).await;
+
+        // 仍然无效：保持 validation_error 并返回字段错误
+        let result = revalidate(&db, id, serde_json::json!({&phone: "13800138000", &email: "still bad"}), None)
+            .await
+            .unwrap();
+        assert!(!result.promoted);
+        assert_eq!(result.record.status, "validation_error");
+        assert_eq!(result.field_errors.len(), 1);
+        assert_eq!(result.field_errors[0].field_id, email);
+
+        // 修正后转为 success，错误信息清空，并记入历史
+        let result = revalidate(&db, id, serde_json::json!({&phone: "13800138000", &email: " A@Example.com "}), None)
+            .await
+            .unwrap();
+        assert!(result.promoted);
+        assert_eq!(result.record.status, "success");
+        assert_eq!(result.record.error_message, None);
+        assert_eq!(result.record.data[&email], "a@example.com");
+
+        let history = db
+            .query_all(Statement::from_sql_and_values(
+                db.get_database_backend(),
+                "SELECT after_status FROM record_history WHERE record_id = ? AND operation = 'revalidate' ORDER BY id",
+                vec![id.into()],
+            ))
+            .await
+            .unwrap();
+        let statuses: Vec<String> = history.iter().map(|r| r.try_get("", "after_status").unwrap()).collect();
+        assert_eq!(statuses, vec!["validation_error", "success"]);
+    }
+
+    #[tokio::test]
+    async fn test_revalidate_cleans_like_import() {
+        let (db, project_id, fields) = setup().await;
+        let [phone, backup, email] = [0, 1, 2].map(|i| fields[i].id.to_string());
+        let id = rejected_record(&db, project_id, serde_json::json!({&phone: "", &email: "bad"})).await;
+
+        // 多号码单元格：与导入一样，其余号码写入备用电话字段
+        let result = revalidate(
+            &db,
+            id,
+            serde_json::json!({&phone: "+86 138-0013-8000 / 13900139000", &email: "a@example.com"}),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.record.status, "success");
+        assert_eq!(result.record.data[&phone], "13800138000");
+        assert_eq!(result.record.data[&backup], "13900139000");
+    }
+
+    async fn add_field(db: &DatabaseConnection, project_id: i32, name: &str, field_type: &str) -> String {
+        field::ActiveModel {
+            project_id: Set(project_id),
+            field_name: Set(name.to_string()),
+            field_label: Set(name.to_string()),
+            field_type: Set(field_type.to_string()),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap()
+        .id
+        .to_string()
+    }
+
+    async fn success_record(db: &DatabaseConnection, project_id: i32, data: JsonValue) -> i32 {
+        record::ActiveModel {
+            project_id: Set(project_id),
+            data: Set(data.to_string()),
+            status: Set("success".to_string()),
+            created_at: Set(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap()
+        .id
+    }
+
+    /// 按高级筛选条件查询记录 ID（升序）
+    async fn filtered(db: &DatabaseConnection, project_id: i32, conditions: JsonValue) -> Vec<i32> {
+        let filter: AdvancedFilterRequest =
+            serde_json::from_value(serde_json::json!({ "conditions": conditions })).unwrap();
+        let (where_clause, params) = build_where_for_filter(project_id, &filter);
+        db.query_all(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            format!("SELECT id FROM project_records WHERE {} ORDER BY id", where_clause),
+            params.into_iter().map(sea_orm::Value::from).collect::<Vec<_>>(),
+        ))
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| r.try_get_by_index::<i32>(0).unwrap())
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn test_numeric_filters_and_number_backfill() {
+        let (db, project_id, _) = setup().await;
+        let amount = add_field(&db, project_id, "amount", "number").await;
+        let big = success_record(&db, project_id, serde_json::json!({&amount: 1500})).await;
+        let small = success_record(&db, project_id, serde_json::json!({&amount: 80.5})).await;
+        // 早期版本以字符串保存的数值
+        let legacy = success_record(&db, project_id, serde_json::json!({&amount: "1,200"})).await;
+        let pending = success_record(&db, project_id, serde_json::json!({&amount: "待定"})).await;
+        success_record(&db, project_id, serde_json::json!({&amount: ""})).await;
+
+        // 模拟从旧版本升级：数值字符串转为 JSON 数字并记入历史，无法识别的文本保持原样
+        let amount_of = |id: i32| {
+            let db = &db;
+            let amount = &amount;
+            async move {
+                let data: String = record::Entity::find_by_id(id).one(db).await.unwrap().unwrap().data;
+                serde_json::from_str::<JsonValue>(&data).unwrap()[amount].clone()
+            }
+        };
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            "DELETE FROM schema_migrations WHERE name = 'backfill_number_values'".to_string(),
+        ))
+        .await
+        .unwrap();
+        run_migrations(&db).await.unwrap();
+        assert_eq!(amount_of(legacy).await, 1200);
+        assert_eq!(amount_of(pending).await, "待定");
+        let history = db
+            .query_all(Statement::from_string(
+                db.get_database_backend(),
+                "SELECT record_id FROM record_history WHERE operation = 'backfill'".to_string(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(history.iter().map(|r| r.try_get_by_index::<i32>(0).unwrap()).collect::<Vec<_>>(), vec![legacy]);
+
+        // 只执行一次：之后启动不再扫描记录
+        let later = success_record(&db, project_id, serde_json::json!({&amount: "2,000"})).await;
+        run_migrations(&db).await.unwrap();
+        assert_eq!(amount_of(later).await, "2,000");
+
+        let gt = serde_json::json!([{ "field": &amount, "operator": "gt", "value": 1000 }]);
+        assert_eq!(filtered(&db, project_id, gt).await, vec![big, legacy]);
+        // 空值与非数值文本不参与数值比较（不会被当作 0）
+        let lt = serde_json::json!([{ "field": &amount, "operator": "lt", "value": "100" }]);
+        assert_eq!(filtered(&db, project_id, lt).await, vec![small]);
+        let between = serde_json::json!([{ "field": &amount, "operator": "between", "value": [80, 1200] }]);
+        assert_eq!(filtered(&db, project_id, between).await, vec![small, legacy]);
+    }
+
+    #[tokio::test]
+    async fn test_includes_and_address_component_filters() {
+        let (db, project_id, fields) = setup().await;
+        let [phone, backup] = [fields[0].id, fields[1].id].map(|id| id.to_string());
+        let address = add_field(&db, project_id, "address", "address").await;
+        let address_key = address_components_key(&address);
+
+        let beijing = success_record(
+            &db,
+            project_id,
+            serde_json::json!({
+                &phone: "13800138000",
+                &backup: ["13900139000", "010-12345678"],
+                &address: "北京市海淀区中关村大街1号",
+                &address_key: { "province": "北京市", "city": "北京市", "district": "海淀区" },
+            }),
+        )
+        .await;
+        let shanghai = success_record(
+            &db,
+            project_id,
+            serde_json::json!({
+                &phone: "13700137000",
+                &address: "上海市浦东新区世纪大道100号",
+                &address_key: { "province": "上海市", "city": "上海市", "district": "浦东新区" },
+            }),
+        )
+        .await;
+
+        // Includes：数组字段匹配任一项，单值字段匹配其值
+        let in_list = serde_json::json!([{ "field": &backup, "operator": "includes", "value": ["010-12345678", "x"] }]);
+        assert_eq!(filtered(&db, project_id, in_list).await, vec![beijing]);
+        let in_scalar = serde_json::json!([{ "field": &phone, "operator": "includes", "value": ["13700137000", "13800138000"] }]);
+        assert_eq!(filtered(&db, project_id, in_scalar).await, vec![beijing, shanghai]);
+
+        // 地址组成部分
+        let province = serde_json::json!([{ "field": format!("{}.province", address), "operator": "eq", "value": "上海市" }]);
+        assert_eq!(filtered(&db, project_id, province).await, vec![shanghai]);
+        let district = serde_json::json!([{ "field": format!("{}.district", address), "operator": "includes", "value": ["海淀区", "朝阳区"] }]);
+        assert_eq!(filtered(&db, project_id, district).await, vec![beijing]);
+
+        // 无效的组成部分被忽略（不产生条件）
+        let invalid = serde_json::json!([{ "field": format!("{}.street", address), "operator": "eq", "value": "x" }]);
+        assert_eq!(filtered(&db, project_id, invalid).await, vec![beijing, shanghai]);
+    }
+
+    #[tokio::test]
+    async fn test_update_stores_numbers_like_import() {
+        let (db, project_id, fields) = setup().await;
+        let phone = fields[0].id.to_string();
+        let amount = add_field(&db, project_id, "amount", "currency").await;
+        let id = success_record(&db, project_id, serde_json::json!({&phone: "13800138000", &amount: 100})).await;
+
+        let record = update(&db, id, serde_json::json!({&phone: "138 0013 8000", &amount: "1,200"}), None)
+            .await
+            .unwrap();
+        assert_eq!(record.data[&phone], "13800138000");
+        assert_eq!(record.data[&amount], 1200);
+        assert_eq!(record.status, "success");
+        let gt = serde_json::json!([{"field": amount, "operator": "gt", "value": "1000"}]);
+        assert_eq!(filtered(&db, project_id, gt).await, vec![id]);
+    }
+
+    #[tokio::test]
+    async fn test_update_keeps_computed_value_when_expression_fails() {
+        let (db, project_id, _) = setup().await;
+        let price = add_field(&db, project_id, "price", "number").await;
+        let total = field::ActiveModel {
+            project_id: Set(project_id),
+            field_name: Set("total".to_string()),
+            field_label: Set("total".to_string()),
+            field_type: Set("number".to_string()),
+            expression: Set(Some("{price} * 2".to_string())),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap()
+        .id
+        .to_string();
+        let id = success_record(&db, project_id, serde_json::json!({&price: 10, &total: 20})).await;
+
+        let record = update(&db, id, serde_json::json!({&price: 5, &total: 20}), None).await.unwrap();
+        assert_eq!(record.data[&total], 10);
+
+        // 表达式求值失败：保留上次的计算结果，不清空
+        let record = update(&db, id, serde_json::json!({&price: "abc", &total: 10}), None).await.unwrap();
+        assert_eq!(record.data[&total], 10);
+    }
+}
@@ -0,0 +1,402 @@
+// 行政区划数据（GB/T 2260 省级、地级）
+//
+// 身份证前两位为省级行政区划代码；地址解析时用于识别省份、地级市的全称与简称。
+
+/// 省级行政区划：（代码, 全称, 简称）
+pub const PROVINCES: &[(&str, &str, &str)] = &[
+    ("11", "北京市", "北京"),
+    ("12", "天津市", "天津"),
+    ("13", "河北省", "河北"),
+    ("14", "山西省", "山西"),
+    ("15", "内蒙古自治区", "内蒙古"),
+    ("21", "辽宁省", "辽宁"),
+    ("22", "吉林省", "吉林"),
+    ("23", "黑龙江省", "黑龙江"),
+    ("31", "上海市", "上海"),
+    ("32", "江苏省", "江苏"),
+    ("33", "浙江省", "浙江"),
+    ("34", "安徽省", "安徽"),
+    ("35", "福建省", "福建"),
+    ("36", "江西省", "江西"),
+    ("37", "山东省", "山东"),
+    ("41", "河南省", "河南"),
+    ("42", "湖北省", "湖北"),
+    ("43", "湖南省", "湖南"),
+    ("44", "广东省", "广东"),
+    ("45", "广西壮族自治区", "广西"),
+    ("46", "海南省", "海南"),
+    ("50", "重庆市", "重庆"),
+    ("51", "四川省", "四川"),
+    ("52", "贵州省", "贵州"),
+    ("53", "云南省", "云南"),
+    ("54", "西藏自治区", "西藏"),
+    ("61", "陕西省", "陕西"),
+    ("62", "甘肃省", "甘肃"),
+    ("63", "青海省", "青海"),
+    ("64", "宁夏回族自治区", "宁夏"),
+    ("65", "新疆维吾尔自治区", "新疆"),
+    ("71", "台湾省", "台湾"),
+    ("81", "香港特别行政区", "香港"),
+    ("82", "澳门特别行政区", "澳门"),
+];
+
+/// 地级行政区划：（所属省级代码, 全称, 简称）
+///
+/// 直辖市不单独列出地级市（解析时城市取直辖市本身）；省直辖的县级市一并列出
+pub const CITIES: &[(&str, &str, &str)] = &[
+    ("13", "石家庄市", "石家庄"),
+    ("13", "唐山市", "唐山"),
+    ("13", "秦皇岛市", "秦皇岛"),
+    ("13", "邯郸市", "邯郸"),
+    ("13", "邢台市", "邢台"),
+    ("13", "保定市", "保定"),
+    ("13", "张家口市", "张家口"),
+    ("13", "承德市", "承德"),
+    ("13", "沧州市", "沧州"),
+    ("13", "廊坊市", "廊坊"),
+    ("13", "衡水市", "衡水"),
+    ("14", "太原市", "太原"),
+    ("14", "大同市", "大同"),
+    ("14", "阳泉市", "阳泉"),
+    ("14", "长治市", "长治"),
+    ("14", "晋城市", "晋城"),
+    ("14", "朔州市", "朔州"),
+    ("14", "晋中市", "晋中"),
+    ("14", "运城市", "运城"),
+    ("14", "忻州市", "忻州"),
+    ("14", "临汾市", "临汾"),
+    ("14", "吕梁市", "吕梁"),
+    ("15", "呼和浩特市", "呼和浩特"),
+    ("15", "包头市", "包头"),
+    ("15", "乌海市", "乌海"),
+    ("15", "赤峰市", "赤峰"),
+    ("15", "通辽市", "通辽"),
+    ("15", "鄂尔多斯市", "鄂尔多斯"),
+    ("15", "呼伦贝尔市", "呼伦贝尔"),
+    ("15", "巴彦淖尔市", "巴彦淖尔"),
+    ("15", "乌兰察布市", "乌兰察布"),
+    ("15", "兴安盟", "兴安"),
+    ("15", "锡林郭勒盟", "锡林郭勒"),
+    ("15", "阿拉善盟", "阿拉善"),
+    ("21", "沈阳市", "沈阳"),
+    ("21", "大连市", "大连"),
+    ("21", "鞍山市", "鞍山"),
+    ("21", "抚顺市", "抚顺"),
+    ("21", "本溪市", "本溪"),
+    ("21", "丹东市", "丹东"),
+    ("21", "锦州市", "锦州"),
+    ("21", "营口市", "营口"),
+    ("21", "阜新市", "阜新"),
+    ("21", "辽阳市", "辽阳"),
+    ("21", "盘锦市", "盘锦"),
+    ("21", "铁岭市", "铁岭"),
+    ("21", "朝阳市", "朝阳"),
+    ("21", "葫芦岛市", "葫芦岛"),
+    ("22", "长春市", "长春"),
+    ("22", "吉林市", "吉林"),
+    ("22", "四平市", "四平"),
+    ("22", "辽源市", "辽源"),
+    ("22", "通化市", "通化"),
+    ("22", "白山市", "白山"),
+    ("22", "松原市", "松原"),
+    ("22", "白城市", "白城"),
+    ("22", "延边朝鲜族自治州", "延边"),
+    ("23", "哈尔滨市", "哈尔滨"),
+    ("23", "齐齐哈尔市", "齐齐哈尔"),
+    ("23", "鸡西市", "鸡西"),
+    ("23", "鹤岗市", "鹤岗"),
+    ("23", "双鸭山市", "双鸭山"),
+    ("23", "大庆市", "大庆"),
+    ("23", "伊春市", "伊春"),
+    ("23", "佳木斯市", "佳木斯"),
+    ("23", "七台河市", "七台河"),
+    ("23", "牡丹江市", "牡丹江"),
+    ("23", "黑河市", "黑河"),
+    ("23", "绥化市", "绥化"),
+    ("23", "大兴安岭地区", "大兴安岭"),
+    ("32", "南京市", "南京"),
+    ("32", "无锡市", "无锡"),
+    ("32", "徐州市", "徐州"),
+    ("32", "常州市", "常州"),
+    ("32", "苏州市", "苏州"),
+    ("32", "南通市", "南通"),
+    ("32", "连云港市", "连云港"),
+    ("32", "淮安市", "淮安"),
+    ("32", "盐城市", "盐城"),
+    ("32", "扬州市", "扬州"),
+    ("32", "镇江市", "镇江"),
+    ("32", "泰州市", "泰州"),
+    ("32", "宿迁市", "宿迁"),
+    ("33", "杭州市", "杭州"),
+    ("33", "宁波市", "宁波"),
+    ("33", "温州市", "温州"),
+    ("33", "嘉兴市", "嘉兴"),
+    ("33", "湖州市", "湖州"),
+    ("33", "绍兴市", "绍兴"),
+    ("33", "金华市", "金华"),
+    ("33", "衢州市", "衢州"),
+    ("33", "舟山市", "舟山"),
+    ("33", "台州市", "台州"),
+    ("33", "丽水市", "丽水"),
+    ("34", "合肥市", "合肥"),
+    ("34", "芜湖市", "芜湖"),
+    ("34", "蚌埠市", "蚌埠"),
+    ("34", "淮南市", "淮南"),
+    ("34", "马鞍山市", "马鞍山"),
+    ("34", "淮北市", "淮北"),
+    ("34", "铜陵市", "铜陵"),
+    ("34", "安庆市", "安庆"),
+    ("34", "黄山市", "黄山"),
+    ("34", "滁州市", "滁州"),
+    ("34", "阜阳市", "阜阳"),
+    ("34", "宿州市", "宿州"),
+    ("34", "六安市", "六安"),
+    ("34", "亳州市", "亳州"),
+    ("34", "池州市", "池州"),
+    ("34", "宣城市", "宣城"),
+    ("35", "福州市", "福州"),
+    ("35", "厦门市", "厦门"),
+    ("35", "莆田市", "莆田"),
+    ("35", "三明市", "三明"),
+    ("35", "泉州市", "泉州"),
+    ("35", "漳州市", "漳州"),
+    ("35", "南平市", "南平"),
+    ("35", "龙岩市", "龙岩"),
+    ("35", "宁德市", "宁德"),
+    ("36", "南昌市", "南昌"),
+    ("36", "景德镇市", "景德镇"),
+    ("36", "萍乡市", "萍乡"),
+    ("36", "九江市", "九江"),
+    ("36", "新余市", "新余"),
+    ("36", "鹰潭市", "鹰潭"),
+    ("36", "赣州市", "赣州"),
+    ("36", "吉安市", "吉安"),
+    ("36", "宜春市", "宜春"),
+    ("36", "抚州市", "抚州"),
+    ("36", "上饶市", "上饶"),
+    ("37", "济南市", "济南"),
+    ("37", "青岛市", "青岛"),
+    ("37", "淄博市", "淄博"),
+    ("37", "枣庄市", "枣庄"),
+    ("37", "东营市", "东营"),
+    ("37", "烟台市", "烟台"),
+    ("37", "潍坊市", "潍坊"),
+    ("37", "济宁市", "济宁"),
+    ("37", "泰安市", "泰安"),
+    ("37", "威海市", "威海"),
+    ("37", "日照市", "日照"),
+    ("37", "临沂市", "临沂"),
+    ("37", "德州市", "德州"),
+    ("37", "聊城市", "聊城"),
+    ("37", "滨州市", "滨州"),
+    ("37", "菏泽市", "菏泽"),
+    ("41", "郑州市", "郑州"),
+    ("41", "开封市", "开封"),
+    ("41", "洛阳市", "洛阳"),
+    ("41", "平顶山市", "平顶山"),
+    ("41", "安阳市", "安阳"),
+    ("41", "鹤壁市", "鹤壁"),
+    ("41", "新乡市", "新乡"),
+    ("41", "焦作市", "焦作"),
+    ("41", "濮阳市", "濮阳"),
+    ("41", "许昌市", "许昌"),
+    ("41", "漯河市", "漯河"),
+    ("41", "三门峡市", "三门峡"),
+    ("41", "南阳市", "南阳"),
+    ("41", "商丘市", "商丘"),
+    ("41", "信阳市", "信阳"),
+    ("41", "周口市", "周口"),
+    ("41", "驻马店市", "驻马店"),
+    ("41", "济源市", "济源"),
+    ("42", "武汉市", "武汉"),
+    ("42", "黄石市", "黄石"),
+    ("42", "十堰市", "十堰"),
+    ("42", "宜昌市", "宜昌"),
+    ("42", "襄阳市", "襄阳"),
+    ("42", "鄂州市", "鄂州"),
+    ("42", "荆门市", "荆门"),
+    ("42", "孝感市", "孝感"),
+    ("42", "荆州市", "荆州"),
+    ("42", "黄冈市", "黄冈"),
+    ("42", "咸宁市", "咸宁"),
+    ("42", "随州市", "随州"),
+    ("42", "恩施土家族苗族自治州", "恩施"),
+    ("42", "仙桃市", "仙桃"),
+    ("42", "潜江市", "潜江"),
+    ("42", "天门市", "天门"),
+    ("42", "神农架林区", "神农架"),
+    ("43", "长沙市", "长沙"),
+    ("43", "株洲市", "株洲"),
+    ("43", "湘潭市", "湘潭"),
+    ("43", "衡阳市", "衡阳"),
+    ("43", "邵阳市", "邵阳"),
+    ("43", "岳阳市", "岳阳"),
+    ("43", "常德市", "常德"),
+    ("43", "张家界市", "张家界"),
+    ("43", "益阳市", "益阳"),
+    ("43", "郴州市", "郴州"),
+    ("43", "永州市", "永州"),
+    ("43", "怀化市", "怀化"),
+    ("43", "娄底市", "娄底"),
+    ("43", "湘西土家族苗族自治州", "湘西"),
+    ("44", "广州市", "广州"),
+    ("44", "韶关市", "韶关"),
+    ("44", "深圳市", "深圳"),
+    ("44", "珠海市", "珠海"),
+    ("44", "汕头市", "汕头"),
+    ("44", "佛山市", "佛山"),
+    ("44", "江门市", "江门"),
+    ("44", "湛江市", "湛江"),
+    ("44", "茂名市", "茂名"),
+    ("44", "肇庆市", "肇庆"),
+    ("44", "惠州市", "惠州"),
+    ("44", "梅州市", "梅州"),
+    ("44", "汕尾市", "汕尾"),
+    ("44", "河源市", "河源"),
+    ("44", "阳江市", "阳江"),
+    ("44", "清远市", "清远"),
+    ("44", "东莞市", "东莞"),
+    ("44", "中山市", "中山"),
+    ("44", "潮州市", "潮州"),
+    ("44", "揭阳市", "揭阳"),
+    ("44", "云浮市", "云浮"),
+    ("45", "南宁市", "南宁"),
+    ("45", "柳州市", "柳州"),
+    ("45", "桂林市", "桂林"),
+    ("45", "梧州市", "梧州"),
+    ("45", "北海市", "北海"),
+    ("45", "防城港市", "防城港"),
+    ("45", "钦州市", "钦州"),
+    ("45", "贵港市", "贵港"),
+    ("45", "玉林市", "玉林"),
+    ("45", "百色市", "百色"),
+    ("45", "贺州市", "贺州"),
+    ("45", "河池市", "河池"),
+    ("45", "来宾市", "来宾"),
+    ("45", "崇左市", "崇左"),
+    ("46", "海口市", "海口"),
+    ("46", "三亚市", "三亚"),
+    ("46", "三沙市", "三沙"),
+    ("46", "儋州市", "儋州"),
+    ("46", "五指山市", "五指山"),
+    ("46", "琼海市", "琼海"),
+    ("46", "文昌市", "文昌"),
+    ("46", "万宁市", "万宁"),
+    ("46", "东方市", "东方"),
+    ("51", "成都市", "成都"),
+    ("51", "自贡市", "自贡"),
+    ("51", "攀枝花市", "攀枝花"),
+    ("51", "泸州市", "泸州"),
+    ("51", "德阳市", "德阳"),
+    ("51", "绵阳市", "绵阳"),
+    ("51", "广元市", "广元"),
+    ("51", "遂宁市", "遂宁"),
+    ("51", "内江市", "内江"),
+    ("51", "乐山市", "乐山"),
+    ("51", "南充市", "南充"),
+    ("51", "眉山市", "眉山"),
+    ("51", "宜宾市", "宜宾"),
+    ("51", "广安市", "广安"),
+    ("51", "达州市", "达州"),
+    ("51", "雅安市", "雅安"),
+    ("51", "巴中市", "巴中"),
+    ("51", "资阳市", "资阳"),
+    ("51", "阿坝藏族羌族自治州", "阿坝"),
+    ("51", "甘孜藏族自治州", "甘孜"),
+    ("51", "凉山彝族自治州", "凉山"),
+    ("52", "贵阳市", "贵阳"),
+    ("52", "六盘水市", "六盘水"),
+    ("52", "遵义市", "遵义"),
+    ("52", "安顺市", "安顺"),
+    ("52", "毕节市", "毕节"),
+    ("52", "铜仁市", "铜仁"),
+    ("52", "黔西南布依族苗族自治州", "黔西南"),
+    ("52", "黔东南苗族侗族自治州", "黔东南"),
+    ("52", "黔南布依族苗族自治州", "黔南"),
+    ("53", "昆明市", "昆明"),
+    ("53", "曲靖市", "曲靖"),
+    ("53", "玉溪市", "玉溪"),
+    ("53", "保山市", "保山"),
+    ("53", "昭通市", "昭通"),
+    ("53", "丽江市", "丽江"),
+    ("53", "普洱市", "普洱"),
+    ("53", "临沧市", "临沧"),
+    ("53", "楚雄彝族自治州", "楚雄"),
+    ("53", "红河哈尼族彝族自治州", "红河"),
+    ("53", "文山壮族苗族自治州", "文山"),
+    ("53", "西双版纳傣族自治州", "西双版纳"),
+    ("53", "大理白族自治州", "大理"),
+    ("53", "德宏傣族景颇族自治州", "德宏"),
+    ("53", "怒江傈僳族自治州", "怒江"),
+    ("53", "迪庆藏族自治州", "迪庆"),
+    ("54", "拉萨市", "拉萨"),
+    ("54", "日喀则市", "日喀则"),
+    ("54", "昌都市", "昌都"),
+    ("54", "林芝市", "林芝"),
+    ("54", "山南市", "山南"),
+    ("54", "那曲市", "那曲"),
+    ("54", "阿里地区", "阿里"),
+    ("61", "西安市", "西安"),
+    ("61", "铜川市", "铜川"),
+    ("61", "宝鸡市", "宝鸡"),
+    ("61", "咸阳市", "咸阳"),
+    ("61", "渭南市", "渭南"),
+    ("61", "延安市", "延安"),
+    ("61", "汉中市", "汉中"),
+    ("61", "榆林市", "榆林"),
+    ("61", "安康市", "安康"),
+    ("61", "商洛市", "商洛"),
+    ("62", "兰州市", "兰州"),
+    ("62", "嘉峪关市", "嘉峪关"),
+    ("62", "金昌市", "金昌"),
+    ("62", "白银市", "白银"),
+    ("62", "天水市", "天水"),
+    ("62", "武威市", "武威"),
+    ("62", "张掖市", "张掖"),
+    ("62", "平凉市", "平凉"),
+    ("62", "酒泉市", "酒泉"),
+    ("62", "庆阳市", "庆阳"),
+    ("62", "定西市", "定西"),
+    ("62", "陇南市", "陇南"),
+    ("62", "临夏回族自治州", "临夏"),
+    ("62", "甘南藏族自治州", "甘南"),
+    ("63", "西宁市", "西宁"),
+    ("63", "海东市", "海东"),
+    ("63", "海北藏族自治州", "海北"),
+    ("63", "黄南藏族自治州", "黄南"),
+    ("63", "海南藏族自治州", "海南州"),
+    ("63", "果洛藏族自治州", "果洛"),
+    ("63", "玉树藏族自治州", "玉树"),
+    ("63", "海西蒙古族藏族自治州", "海西"),
+    ("64", "银川市", "银川"),
+    ("64", "石嘴山市", "石嘴山"),
+    ("64", "吴忠市", "吴忠"),
+    ("64", "固原市", "固原"),
+    ("64", "中卫市", "中卫"),
+    ("65", "乌鲁木齐市", "乌鲁木齐"),
+    ("65", "克拉玛依市", "克拉玛依"),
+    ("65", "吐鲁番市", "吐鲁番"),
+    ("65", "哈密市", "哈密"),
+    ("65", "昌吉回族自治州", "昌吉"),
+    ("65", "博尔塔拉蒙古自治州", "博尔塔拉"),
+    ("65", "巴音郭楞蒙古自治州", "巴音郭楞"),
+    ("65", "阿克苏地区", "阿克苏"),
+    ("65", "克孜勒苏柯尔克孜自治州", "克孜勒苏"),
+    ("65", "喀什地区", "喀什"),
+    ("65", "和田地区", "和田"),
+    ("65", "伊犁哈萨克自治州", "伊犁"),
+    ("65", "塔城地区", "塔城"),
+    ("65", "阿勒泰地区", "阿勒泰"),
+    ("65", "石河子市", "石河子"),
+    ("65", "阿拉尔市", "阿拉尔"),
+    ("65", "图木舒克市", "图木舒克"),
+    ("65", "五家渠市", "五家渠"),
+];
+
+/// 直辖市代码（城市即直辖市本身）
+pub const MUNICIPALITIES: &[&str] = &["11", "12", "31", "50"];
+
+/// 按代码查找省级行政区划全称
+pub fn province_by_code(code: &str) -> Option<&'static str> {
+    PROVINCES.iter().find(|(c, _, _)| *c == code).map(|(_, name, _)| *name)
+}
@@ -0,0 +1,345 @@
+// 基于规则的列映射（离线兜底）
+//
+// 不依赖 AI：按表头与字段标签/字段名的相似度 + 样本数据与字段类型正则的吻合度打分，
+// 贪心分配得到 ColumnMappingResponse。用于无 AI 配置或 AI 调用失败时继续导入。
+
+use regex::Regex;
+use std::collections::HashSet;
+
+use crate::backend::infrastructure::persistence::models::field::Model as FieldModel;
+use super::ai_service::{ColumnMappingResponse, FieldMapping};
+use super::data_utils::clean_value;
+use super::fields::get_validation_rule;
+use super::header_detector::HEADER_SEPARATOR;
+
+/// 在前几行中探测表头
+const HEADER_SCAN_ROWS: usize = 5;
+
+/// 参与类型校验的样本行数
+const SAMPLE_ROWS: usize = 20;
+
+/// 建立映射的最低得分
+const MIN_SCORE: f32 = 0.5;
+
+/// 归一化列名：转小写，去除空白与标点
+fn normalize_name(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// 字符二元组 Dice 系数（对中文按字切分同样适用）
+fn dice_coefficient(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len() < 2 || b.len() < 2 {
+        return if a == b && !a.is_empty() { 1.0 } else { 0.0 };
+    }
+
+    let bigrams_a: Vec<(char, char)> = a.windows(2).map(|w| (w[0], w[1])).collect();
+    let mut bigrams_b: Vec<(char, char)> = b.windows(2).map(|w| (w[0], w[1])).collect();
+    let total = (bigrams_a.len() + bigrams_b.len()) as f32;
+
+    let mut common = 0;
+    for bg in &bigrams_a {
+        if let Some(pos) = bigrams_b.iter().position(|x| x == bg) {
+            bigrams_b.swap_remove(pos);
+            common += 1;
+        }
+    }
+    2.0 * common as f32 / total
+}
+
+/// 单个候选名称与表头的相似度（0~1）
+fn name_similarity(header: &str, candidate: &str) -> f32 {
+    let h = normalize_name(header);
+    let c = normalize_name(candidate);
+    if h.is_empty() || c.is_empty() {
+        return 0.0;
+    }
+    if h == c {
+        return 1.0;
+    }
+    if h.contains(&c) || c.contains(&h) {
+        // 包含关系：如「手机号码」与「手机号」，按长度比例给分
+        let (short, long) = if h.chars().count() < c.chars().count() { (&h, &c) } else { (&c, &h) };
+        let ratio = short.chars().count() as f32 / long.chars().count() as f32;
+        return 0.6 + 0.3 * ratio;
+    }
+    dice_coefficient(&h, &c) * 0.8
+}
+
+/// 多级表头的组合列名（如「联系人/电话」）与候选名称的相似度：
+/// 整体与最下级名称按原分计算，上级名称只代表分组，打折计算
+fn composite_similarity(header: &str, candidate: &str) -> f32 {
+    let parts: Vec<&str> = header.split(HEADER_SEPARATOR).collect();
+    let Some((last, parents)) = parts.split_last() else {
+        return 0.0;
+    };
+    if parents.is_empty() {
+        return name_similarity(header, candidate);
+    }
+    let parent_score = parents
+        .iter()
+        .map(|p| name_similarity(p, candidate) * 0.8)
+        .fold(0.0, f32::max);
+    name_similarity(header, candidate)
+        .max(name_similarity(last, candidate))
+        .max(parent_score)
+}
+
+/// 表头与字段（标签 / 字段名）的相似度，取较高者
+pub fn header_similarity(header: &str, field: &FieldModel) -> f32 {
+    composite_similarity(header, &field.field_label).max(composite_similarity(header, &field.field_name))
+}
+
+/// 样本值与字段类型正则的吻合比例；字段无验证规则或无样本时返回 None
+fn type_match_ratio(values: &[&str], field: &FieldModel) -> Option<f32> {
+    let rule = field
+        .validation_rule
+        .clone()
+        .or_else(|| get_validation_rule(&field.field_type))?;
+    let re = Regex::new(&rule).ok()?;
+
+    let non_empty: Vec<String> = values
+        .iter()
+        .map(|v| clean_value(v, &field.field_type))
+        .filter(|v| !v.is_empty())
+        .collect();
+    if non_empty.is_empty() {
+        return None;
+    }
+
+    let matched = non_empty.iter().filter(|v| re.is_match(v)).count();
+    Some(matched as f32 / non_empty.len() as f32)
+}
+
+/// 综合得分：有类型规则时表头占 70%、数据占 30%；数据明显不符合类型时直接否决
+fn score_column(header: &str, values: &[&str], field: &FieldModel) -> f32 {
+    let name_score = header_similarity(header, field);
+    match type_match_ratio(values, field) {
+        Some(ratio) if ratio < 0.5 => 0.0,
+        // 表头无法识别，但数据高度符合类型特征（如手机号、邮箱列）
+        Some(ratio) if name_score < 0.3 && ratio >= 0.9 => 0.5 * ratio + 0.05,
+        Some(ratio) => 0.7 * name_score + 0.3 * ratio,
+        None => name_score,
+    }
+}
+
+/// 以指定行为表头计算映射，返回（映射结果，总分）
+fn map_with_header_row(
+    rows: &[Vec<String>],
+    header_row: usize,
+    fields: &[FieldModel],
+) -> (Vec<FieldMapping>, f32) {
+    let headers = &rows[header_row];
+    let samples: Vec<&Vec<String>> = rows.iter().skip(header_row + 1).take(SAMPLE_ROWS).collect();
+    let column_count = rows
+        .iter()
+        .take(header_row + 1 + SAMPLE_ROWS)
+        .map(|r| r.len())
+        .max()
+        .unwrap_or(0);
+
+    // 计算所有（字段, 列）组合得分
+    let mut candidates: Vec<(f32, usize, usize)> = Vec::new();
+    for (field_idx, field) in fields.iter().enumerate() {
+        for col in 0..column_count {
+            let header = headers.get(col).map(|s| s.as_str()).unwrap_or("");
+            let values: Vec<&str> = samples
+                .iter()
+                .map(|r| r.get(col).map(|s| s.as_str()).unwrap_or(""))
+                .collect();
+            let score = score_column(header, &values, field);
+            if score >= MIN_SCORE {
+                candidates.push((score, field_idx, col));
+            }
+        }
+    }
+
+    // 贪心分配：得分高者优先，每个字段、每一列最多使用一次
+    candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    let mut used_fields = HashSet::new();
+    let mut used_columns = HashSet::new();
+    let mut mappings = Vec::new();
+    let mut total = 0.0;
+
+    for (score, field_idx, col) in candidates {
+        if used_fields.contains(&field_idx) || used_columns.contains(&col) {
+            continue;
+        }
+        used_fields.insert(field_idx);
+        used_columns.insert(col);
+        total += score;
+        mappings.push(FieldMapping {
+            field_name: fields[field_idx].field_name.clone(),
+            column_index: col as i32,
+            column_header: headers.get(col).cloned().unwrap_or_default(),
+            confidence: score,
+        });
+    }
+
+    mappings.sort_by_key(|m| m.column_index);
+    (mappings, total)
+}
+
+/// 规则映射：在前几行中选出得分最高的表头行，并据此生成列映射
+pub fn map_columns_by_rules(rows: &[Vec<String>], fields: &[FieldModel]) -> ColumnMappingResponse {
+    let mut best: Option<(usize, Vec<FieldMapping>, f32)> = None;
+    for header_row in 0..rows.len().min(HEADER_SCAN_ROWS) {
+        let (mappings, total) = map_with_header_row(rows, header_row, fields);
+        if best.as_ref().map(|(_, _, t)| total > *t).unwrap_or(true) {
+            best = Some((header_row, mappings, total));
+        }
+    }
+
+    let Some((header_row, mappings, _)) = best else {
+        return ColumnMappingResponse {
+            header_row: -1,
+            mappings: vec![],
+            confidence: 0.0,
+            unmatched_columns: vec![],
+        };
+    };
+
+    let confidence = if mappings.is_empty() {
+        0.0
+    } else {
+        mappings.iter().map(|m| m.confidence).sum::<f32>() / mappings.len() as f32
+    };
+
+    let mapped_columns: HashSet<i32> = mappings.iter().map(|m| m.column_index).collect();
+    let unmatched_columns = rows[header_row]
+        .iter()
+        .enumerate()
+        .filter(|(i, h)| !h.trim().is_empty() && !mapped_columns.contains(&(*i as i32)))
+        .map(|(i, _)| i as i32)
+        .collect();
+
+    ColumnMappingResponse {
+        header_row: header_row as i32,
+        mappings,
+        confidence,
+        unmatched_columns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_field(name: &str, label: &str, field_type: &str) -> FieldModel {
+        FieldModel {
+            id: 0,
+            project_id: 1,
+            field_name: name.to_string(),
+            field_label: label.to_string(),
+            field_type: field_type.to_string(),
+            is_required: false,
+            is_dedup_key: false,
+            is_deleted: false,
+            additional_requirement: None,
+            validation_rule: None,
+            extraction_hint: None,
+            type_options: None,
+            transforms: None,
+            expression: None,
+            ai_extract_source: None,
+            display_order: 0,
+            created_at: chrono::Utc::now(),
+            deleted_at: None,
+        }
+    }
+
+    fn rows(data: &[&[&str]]) -> Vec<Vec<String>> {
+        data.iter()
+            .map(|r| r.iter().map(|s| s.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_maps_by_header_name() {
+        let fields = vec![
+            make_field("name", "姓名", "text"),
+            make_field("phone", "手机号", "phone"),
+            make_field("email", "邮箱", "email"),
+        ];
+        let data = rows(&[
+            &["序号", "客户姓名", "手机号码", "电子邮箱"],
+            &["1", "张三", "13800138000", "zhang@example.com"],
+            &["2", "李四", "13900139000", "li@example.com"],
+        ]);
+
+        let result = map_columns_by_rules(&data, &fields);
+        assert_eq!(result.header_row, 0);
+        let col_of = |name: &str| result.mappings.iter().find(|m| m.field_name == name).map(|m| m.column_index);
+        assert_eq!(col_of("name"), Some(1));
+        assert_eq!(col_of("phone"), Some(2));
+        assert_eq!(col_of("email"), Some(3));
+        assert_eq!(result.unmatched_columns, vec![0]);
+    }
+
+    #[test]
+    fn test_rejects_column_with_wrong_data_type() {
+        let fields = vec![make_field("phone", "电话", "phone")];
+        let data = rows(&[
+            &["电话编号"],
+            &["A-001"],
+            &["A-002"],
+        ]);
+
+        let result = map_columns_by_rules(&data, &fields);
+        assert!(result.mappings.is_empty());
+    }
+
+    #[test]
+    fn test_detects_header_below_title_row() {
+        let fields = vec![
+            make_field("name", "姓名", "text"),
+            make_field("email", "邮箱", "email"),
+        ];
+        let data = rows(&[
+            &["2024年客户名单", ""],
+            &["姓名", "邮箱"],
+            &["张三", "zhang@example.com"],
+        ]);
+
+        let result = map_columns_by_rules(&data, &fields);
+        assert_eq!(result.header_row, 1);
+        assert_eq!(result.mappings.len(), 2);
+    }
+
+    #[test]
+    fn test_maps_composite_headers() {
+        let fields = vec![
+            make_field("contact_name", "姓名", "text"),
+            make_field("contact_phone", "电话", "phone"),
+        ];
+        let data = rows(&[
+            &["序号", "联系人/姓名", "联系人/电话"],
+            &["1", "张三", "13800138000"],
+            &["2", "李四", "13900139000"],
+        ]);
+
+        let result = map_columns_by_rules(&data, &fields);
+        assert_eq!(result.header_row, 0);
+        let col_of = |name: &str| result.mappings.iter().find(|m| m.field_name == name).map(|m| m.column_index);
+        assert_eq!(col_of("contact_name"), Some(1));
+        assert_eq!(col_of("contact_phone"), Some(2));
+    }
+
+    #[test]
+    fn test_maps_by_content_without_header() {
+        let fields = vec![make_field("email", "邮箱", "email")];
+        let data = rows(&[
+            &["联系人", "备注"],
+            &["张三", "a@example.com"],
+            &["李四", "b@example.com"],
+        ]);
+
+        let result = map_columns_by_rules(&data, &fields);
+        assert_eq!(result.mappings.len(), 1);
+        assert_eq!(result.mappings[0].column_index, 1);
+    }
+}
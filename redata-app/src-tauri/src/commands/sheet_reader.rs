@@ -0,0 +1,332 @@
+// 流式 Sheet 读取
+//
+// 导入时按 Sheet 逐个打开，在后台线程中按固定行数分块读取，通过有界通道交给导入循环，
+// 峰值内存只与少量分块有关，而不是整个工作簿。
+// xlsx 使用 calamine 的单元格流式读取；xls / xlsb / ods 不支持流式，按 Sheet 读取后分块转换；
+// CSV/TSV 视为单 Sheet 工作簿，逐行解码读取。
+// xlsx / xls 的合并单元格区域随首个分块一起提供（已换算为相对行列下标），用于多级表头识别。
+
+use calamine::{open_workbook_auto, Data, DataRef, Dimensions, Reader, Sheets, XlsxError};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+use super::csv_reader::{csv_sheet_name, is_delimited_file, open_delimited};
+use super::processing::data_to_string;
+
+/// 每个分块的行数（同时也是映射分析可见的前置行数）
+pub const CHUNK_ROWS: usize = 1000;
+
+/// 通道中最多缓存的分块数
+const CHANNEL_CHUNKS: usize = 2;
+
+type ChunkSender = mpsc::Sender<Result<Vec<Vec<String>>, String>>;
+
+/// 读取工作簿的 Sheet 名称列表（不读取单元格数据）
+pub fn list_sheet_names(path: &str) -> Result<Vec<String>, String> {
+    if is_delimited_file(path) {
+        return Ok(vec![csv_sheet_name(path)]);
+    }
+    let workbook = open_workbook_auto(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    Ok(workbook.sheet_names().to_vec())
+}
+
+/// 合并单元格区域（相对于读出的行列下标，首尾均包含）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergedRange {
+    pub first_row: usize,
+    pub first_col: usize,
+    pub last_row: usize,
+    pub last_col: usize,
+}
+
+type MergedSlot = Arc<Mutex<Vec<MergedRange>>>;
+
+/// 将工作表绝对坐标的合并区域换算为相对于数据区域起点的下标
+fn relative_ranges(regions: &[Dimensions], origin: (u32, u32)) -> Vec<MergedRange> {
+    regions
+        .iter()
+        .filter(|d| d.end.0 >= origin.0 && d.end.1 >= origin.1)
+        .map(|d| MergedRange {
+            first_row: d.start.0.saturating_sub(origin.0) as usize,
+            first_col: d.start.1.saturating_sub(origin.1) as usize,
+            last_row: (d.end.0 - origin.0) as usize,
+            last_col: (d.end.1 - origin.1) as usize,
+        })
+        .collect()
+}
+
+/// 单个 Sheet 的分块行流
+pub struct SheetStream {
+    rx: mpsc::Receiver<Result<Vec<Vec<String>>, String>>,
+    merged: MergedSlot,
+}
+
+impl SheetStream {
+    /// 在后台线程中开始读取指定 Sheet
+    pub fn open(path: String, sheet_name: String) -> Self {
+        let (tx, rx) = mpsc::channel(CHANNEL_CHUNKS);
+        let merged: MergedSlot = Arc::default();
+        let merged_writer = merged.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = read_sheet(&path, &sheet_name, &tx, &merged_writer) {
+                let _ = tx.blocking_send(Err(e));
+            }
+        });
+        Self { rx, merged }
+    }
+
+    /// 合并单元格区域（读取首个分块后可用；CSV 等无合并信息时为空）
+    pub fn merged_ranges(&self) -> Vec<MergedRange> {
+        self.merged.lock().unwrap().clone()
+    }
+
+    /// 读取下一个分块，读完返回 None
+    pub async fn next_chunk(&mut self) -> Result<Option<Vec<Vec<String>>>, String> {
+        match self.rx.recv().await {
+            Some(Ok(chunk)) => Ok(Some(chunk)),
+            Some(Err(e)) => Err(e),
+            None => Ok(None),
+        }
+    }
+}
+
+/// 发送一个分块；接收端已关闭（导入提前结束）时返回 false
+fn send_chunk(tx: &ChunkSender, chunk: &mut Vec<Vec<String>>) -> bool {
+    if chunk.is_empty() {
+        return true;
+    }
+    let full = std::mem::replace(chunk, Vec::with_capacity(CHUNK_ROWS));
+    tx.blocking_send(Ok(full)).is_ok()
+}
+
+fn read_sheet(path: &str, sheet_name: &str, tx: &ChunkSender, merged: &MergedSlot) -> Result<(), String> {
+    if is_delimited_file(path) {
+        return stream_delimited(path, tx);
+    }
+    let workbook = open_workbook_auto(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    match workbook {
+        Sheets::Xlsx(mut xlsx) => {
+            let regions = match xlsx.worksheet_merge_cells(sheet_name) {
+                Some(Ok(regions)) => regions,
+                _ => vec![],
+            };
+            let mut reader = match xlsx.worksheet_cells_reader(sheet_name) {
+                Ok(reader) => reader,
+                // 图表页等非工作表，按空 Sheet 处理
+                Err(XlsxError::NotAWorksheet(_)) => return Ok(()),
+                Err(e) => return Err(format!("无法读取 Sheet: {}", e)),
+            };
+            let dimensions = reader.dimensions();
+            let col_offset = dimensions.start.1;
+            let on_origin = |first_row: u32| {
+                *merged.lock().unwrap() = relative_ranges(&regions, (first_row, col_offset));
+            };
+            stream_cells(dimensions, tx, on_origin, || {
+                loop {
+                    let cell = reader
+                        .next_cell()
+                        .map_err(|e| format!("无法读取 Sheet: {}", e))?;
+                    match cell {
+                        Some(c) if matches!(c.get_value(), DataRef::Empty) => continue,
+                        Some(c) => {
+                            let value = data_to_string(&Data::from(c.get_value().clone()));
+                            return Ok(Some((c.get_position(), value)));
+                        }
+                        None => return Ok(None),
+                    }
+                }
+            })
+        }
+        mut other => {
+            let regions = match &other {
+                Sheets::Xls(xls) => xls.worksheet_merge_cells(sheet_name).unwrap_or_default(),
+                _ => vec![],
+            };
+            let range = other
+                .worksheet_range(sheet_name)
+                .map_err(|e| format!("无法读取 Sheet: {}", e))?;
+            if let Some(origin) = range.start() {
+                *merged.lock().unwrap() = relative_ranges(&regions, origin);
+            }
+            let mut chunk = Vec::with_capacity(CHUNK_ROWS);
+            for row in range.rows() {
+                chunk.push(row.iter().map(data_to_string).collect());
+                if chunk.len() >= CHUNK_ROWS && !send_chunk(tx, &mut chunk) {
+                    return Ok(());
+                }
+            }
+            send_chunk(tx, &mut chunk);
+            Ok(())
+        }
+    }
+}
+
+/// 逐行读取 CSV/TSV，短行补齐到已出现的最大列数（与工作簿的矩形区域一致）
+fn stream_delimited(path: &str, tx: &ChunkSender) -> Result<(), String> {
+    let mut width = 0;
+    let mut chunk = Vec::with_capacity(CHUNK_ROWS);
+    for record in open_delimited(path)? {
+        let mut row = record?;
+        width = width.max(row.len());
+        row.resize(width, String::new());
+        chunk.push(row);
+        if chunk.len() >= CHUNK_ROWS && !send_chunk(tx, &mut chunk) {
+            return Ok(());
+        }
+    }
+    send_chunk(tx, &mut chunk);
+    Ok(())
+}
+
+/// 将按行序产生的单元格组装为与 worksheet_range 一致的行
+/// （从第一个非空单元格所在行开始，列从 dimension 起始列开始，行宽一致）
+/// 读到首个单元格时以其所在行调用 on_origin
+fn stream_cells<O, F>(
+    dimensions: Dimensions,
+    tx: &ChunkSender,
+    on_origin: O,
+    mut next_cell: F,
+) -> Result<(), String>
+where
+    O: FnOnce(u32),
+    F: FnMut() -> Result<Option<((u32, u32), String)>, String>,
+{
+    let col_offset = dimensions.start.1;
+    let width = (dimensions.end.1.saturating_sub(col_offset) + 1) as usize;
+
+    let mut chunk: Vec<Vec<String>> = Vec::with_capacity(CHUNK_ROWS);
+    let mut current: Vec<String> = vec![String::new(); width];
+    let mut current_row: Option<u32> = None;
+    let mut on_origin = Some(on_origin);
+
+    while let Some(((row, col), value)) = next_cell()? {
+        if current_row != Some(row) {
+            if let Some(prev) = current_row {
+                chunk.push(std::mem::replace(&mut current, vec![String::new(); width]));
+                if chunk.len() >= CHUNK_ROWS && !send_chunk(tx, &mut chunk) {
+                    return Ok(());
+                }
+                // 中间的空行
+                for _ in prev + 1..row {
+                    chunk.push(vec![String::new(); width]);
+                    if chunk.len() >= CHUNK_ROWS && !send_chunk(tx, &mut chunk) {
+                        return Ok(());
+                    }
+                }
+            } else if let Some(f) = on_origin.take() {
+                f(row);
+            }
+            current_row = Some(row);
+        }
+
+        let idx = col.saturating_sub(col_offset) as usize;
+        if idx >= current.len() {
+            current.resize(idx + 1, String::new());
+        }
+        current[idx] = value;
+    }
+
+    if current_row.is_some() {
+        chunk.push(current);
+    }
+    send_chunk(tx, &mut chunk);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_xlsxwriter::{Format, Workbook};
+
+    fn temp_path(ext: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("redata_test_{}.{}", uuid::Uuid::new_v4(), ext))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    async fn read_all(path: &str, sheet: &str) -> (Vec<Vec<Vec<String>>>, Vec<MergedRange>) {
+        let mut stream = SheetStream::open(path.to_string(), sheet.to_string());
+        let mut chunks = Vec::new();
+        while let Some(chunk) = stream.next_chunk().await.unwrap() {
+            chunks.push(chunk);
+        }
+        (chunks, stream.merged_ranges())
+    }
+
+    #[tokio::test]
+    async fn test_xlsx_chunk_boundary_and_merged_header() {
+        // 数据区域从 B3 开始：两行表头（首行「联系人」合并两列），之后为数据行，
+        // 相对第 1000 行（第二个分块的首行）留空
+        const ROWS: u32 = 2101;
+        let path = temp_path("xlsx");
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.merge_range(2, 1, 2, 2, "联系人", &Format::new()).unwrap();
+        sheet.write_string(2, 3, "备注").unwrap();
+        sheet.write_string(3, 1, "姓名").unwrap();
+        sheet.write_string(3, 2, "手机号").unwrap();
+        for rel in 2..ROWS {
+            if rel == 1000 {
+                continue;
+            }
+            sheet.write_string(rel + 2, 1, format!("name{}", rel)).unwrap();
+            sheet.write_string(rel + 2, 2, format!("138{:08}", rel)).unwrap();
+        }
+        workbook.save(&path).unwrap();
+
+        let (chunks, merged) = read_all(&path, "Sheet1").await;
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), vec![CHUNK_ROWS, CHUNK_ROWS, 101]);
+        let rows: Vec<&Vec<String>> = chunks.iter().flatten().collect();
+        assert!(rows.iter().all(|r| r.len() == 3));
+        assert_eq!(rows[0], &vec!["联系人", "", "备注"]);
+        assert_eq!(rows[1], &vec!["姓名", "手机号", ""]);
+        assert_eq!(rows[999], &vec!["name999", "13800000999", ""]);
+        assert_eq!(rows[1000], &vec!["", "", ""]);
+        assert_eq!(rows[1001], &vec!["name1001", "13800001001", ""]);
+        assert_eq!(rows[ROWS as usize - 1][0], format!("name{}", ROWS - 1));
+
+        // 合并区域换算为相对数据区域起点的下标
+        assert_eq!(merged, vec![MergedRange { first_row: 0, first_col: 0, last_row: 0, last_col: 1 }]);
+    }
+
+    #[tokio::test]
+    async fn test_csv_chunk_boundary() {
+        let path = temp_path("csv");
+        let mut content = String::from("姓名,手机号\n");
+        for i in 1..CHUNK_ROWS {
+            content.push_str(&format!("name{}\n", i));
+        }
+        content.push_str("last,13800138000,extra\n");
+        std::fs::write(&path, content).unwrap();
+
+        let (chunks, merged) = read_all(&path, "").await;
+        let _ = std::fs::remove_file(&path);
+
+        // 恰好 1000 行构成第一个分块，第 1001 行单独成块；短行补齐到已出现的最大列数
+        assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), vec![CHUNK_ROWS, 1]);
+        assert_eq!(chunks[0][1], vec!["name1", ""]);
+        assert_eq!(chunks[1][0], vec!["last", "13800138000", "extra"]);
+        assert!(merged.is_empty());
+    }
+
+    #[test]
+    fn test_relative_ranges() {
+        let regions = vec![
+            Dimensions { start: (2, 1), end: (2, 2) },
+            // 完全位于数据区域之前的合并区域被丢弃
+            Dimensions { start: (0, 0), end: (1, 0) },
+            // 跨越起点的区域截断到起点
+            Dimensions { start: (1, 1), end: (3, 1) },
+        ];
+        assert_eq!(
+            relative_ranges(&regions, (2, 1)),
+            vec![
+                MergedRange { first_row: 0, first_col: 0, last_row: 0, last_col: 1 },
+                MergedRange { first_row: 0, first_col: 0, last_row: 1, last_col: 0 },
+            ]
+        );
+    }
+}
@@ -15,8 +15,10 @@ use crate::backend::infrastructure::persistence::models::{record, task, ProjectR
 /// 项目统计数据
 #[derive(Debug, Serialize)]
 pub struct ProjectStatistics {
-    /// 总记录数
+    /// 总记录数（导入成功的记录）
     pub total_records: u64,
+    /// 被拒绝的记录数（验证失败、重复）
+    pub rejected_records: u64,
     /// 今日新增记录数
     pub today_records: u64,
     /// 本周新增记录数（最近 7 天）
@@ -43,9 +45,17 @@ pub async fn get_project_statistics(
 ) -> Result<ProjectStatistics, String> {
     let conn = db.inner().as_ref();
 
-    // 1. 总记录数
+    // 1. 总记录数（被拒绝的行单独统计）
     let total_records = ProjectRecord::find()
         .filter(record::Column::ProjectId.eq(project_id))
+        .filter(record::Column::Status.eq("success"))
+        .count(conn)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    let rejected_records = ProjectRecord::find()
+        .filter(record::Column::ProjectId.eq(project_id))
+        .filter(record::Column::Status.ne("success"))
         .count(conn)
         .await
         .map_err(|e| format!("数据库错误: {}", e))?;
@@ -53,7 +63,7 @@ pub async fn get_project_statistics(
     // 2. 今日新增记录数
     let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
     let today_sql = format!(
-        "SELECT COUNT(*) as cnt FROM project_records WHERE project_id = ? AND DATE(created_at) = DATE('{}')",
+        "SELECT COUNT(*) as cnt FROM project_records WHERE project_id = ? AND status = 'success' AND DATE(created_at) = DATE('{}')",
         today
     );
     let today_result = conn
@@ -73,7 +83,7 @@ pub async fn get_project_statistics(
         .format("%Y-%m-%d")
         .to_string();
     let week_sql = format!(
-        "SELECT COUNT(*) as cnt FROM project_records WHERE project_id = ? AND DATE(created_at) >= DATE('{}')",
+        "SELECT COUNT(*) as cnt FROM project_records WHERE project_id = ? AND status = 'success' AND DATE(created_at) >= DATE('{}')",
         week_ago
     );
     let week_result = conn
@@ -93,7 +103,7 @@ pub async fn get_project_statistics(
         .format("%Y-%m-%d")
         .to_string();
     let month_sql = format!(
-        "SELECT COUNT(*) as cnt FROM project_records WHERE project_id = ? AND DATE(created_at) >= DATE('{}')",
+        "SELECT COUNT(*) as cnt FROM project_records WHERE project_id = ? AND status = 'success' AND DATE(created_at) >= DATE('{}')",
         month_ago
     );
     let month_result = conn
@@ -144,6 +154,7 @@ pub async fn get_project_statistics(
 
     Ok(ProjectStatistics {
         total_records,
+        rejected_records,
         today_records,
         week_records,
         month_records,
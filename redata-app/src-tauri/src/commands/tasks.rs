@@ -4,15 +4,19 @@
 
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection,
-    EntityTrait, QueryFilter, QueryOrder, Set, Statement,
+    EntityTrait, QueryFilter, QueryOrder, Set, Statement, TransactionTrait,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::backend::infrastructure::persistence::models::{
-    task, ProcessingTask,
+    field, task, Project, ProjectField, ProcessingTask,
     task_file_progress, TaskFileProgress, ProjectRecord,
 };
+use super::records::find_duplicate_of;
+use super::record_history::{
+    actor_label, append_history_from, Snapshot, OP_DELETE, OP_RESTORE, OP_ROLLBACK,
+};
 
 // ============ 响应结构 ============
 
@@ -29,6 +33,8 @@ pub struct TaskResponse {
     pub error_count: i32,
     pub batch_number: Option<String>,
     pub source_files: Option<Vec<String>>,
+    /// 异常中断且保存了处理参数，可通过 resume_interrupted_task 从检查点继续
+    pub resumable: bool,
     pub created_at: String,
     pub updated_at: Option<String>,
 }
@@ -40,6 +46,8 @@ impl From<task::Model> for TaskResponse {
             serde_json::from_str(&s).ok()
         });
 
+        let resumable = m.status == "interrupted" && m.options.is_some();
+
         Self {
             task_id: m.id.clone(),
             project_id: m.project_id,
@@ -52,6 +60,7 @@ impl From<task::Model> for TaskResponse {
             error_count: m.error_count,
             batch_number: m.batch_number,
             source_files,
+            resumable,
             created_at: m.created_at.to_rfc3339(),
             updated_at: m.updated_at.map(|t| t.to_rfc3339()),
         }
@@ -99,6 +108,8 @@ pub async fn create_processing_task(
         error_count: Set(0),
         batch_number: Set(Some(batch_number)),
         source_files: Set(None),
+        options: Set(None),
+        checkpoint: Set(None),
         created_at: Set(now),
         updated_at: Set(None),
     };
@@ -190,6 +201,8 @@ pub struct FileProgressResponse {
     pub total_rows: i32,
     pub success_count: i32,
     pub error_count: i32,
+    pub updated_count: i32,
+    pub merged_count: i32,
 }
 
 #[derive(Debug, Serialize)]
@@ -201,6 +214,8 @@ pub struct SheetProgressResponse {
     pub success_count: i32,
     pub error_count: i32,
     pub total_rows: i32,
+    pub updated_count: i32,
+    pub merged_count: i32,
     pub error_message: Option<String>,
 }
 
@@ -239,6 +254,8 @@ pub async fn get_task_full_progress(
                 total_rows: 0,
                 success_count: 0,
                 error_count: 0,
+                updated_count: 0,
+                merged_count: 0,
             }
         });
 
@@ -252,6 +269,8 @@ pub async fn get_task_full_progress(
                 success_count: record.success_count,
                 error_count: record.error_count,
                 total_rows: record.total_rows,
+                updated_count: record.updated_count,
+                merged_count: record.merged_count,
                 error_message: record.error_message,
             });
         } else {
@@ -260,6 +279,8 @@ pub async fn get_task_full_progress(
             file_progress.total_rows = record.total_rows;
             file_progress.success_count = record.success_count;
             file_progress.error_count = record.error_count;
+            file_progress.updated_count = record.updated_count;
+            file_progress.merged_count = record.merged_count;
         }
     }
 
@@ -270,6 +291,8 @@ pub async fn get_task_full_progress(
             file_progress.total_rows = file_progress.sheets.iter().map(|s| s.total_rows).sum();
             file_progress.success_count = file_progress.sheets.iter().map(|s| s.success_count).sum();
             file_progress.error_count = file_progress.sheets.iter().map(|s| s.error_count).sum();
+            file_progress.updated_count = file_progress.sheets.iter().map(|s| s.updated_count).sum();
+            file_progress.merged_count = file_progress.sheets.iter().map(|s| s.merged_count).sum();
         }
 
         // 兜底修正：文件已完成时，将残留中间态的 sheet（空 sheet 未能正确更新状态）
@@ -292,28 +315,48 @@ pub async fn get_task_full_progress(
     })
 }
 
-/// 更新或插入文件进度（供 processing.rs 内部调用）
+/// 文件 / Sheet 进度的更新内容（None 表示保持原值，新建时使用默认值）
+#[derive(Debug, Default)]
+pub struct FileProgressUpdate<'a> {
+    pub file_phase: Option<&'a str>,
+    pub sheet_phase: Option<&'a str>,
+    pub ai_confidence: Option<f32>,
+    pub mapping_count: Option<i32>,
+    pub success_count: Option<i32>,
+    pub error_count: Option<i32>,
+    pub total_rows: Option<i32>,
+    pub updated_count: Option<i32>,
+    pub merged_count: Option<i32>,
+    pub error_message: Option<&'a str>,
+}
+
+/// 更新或插入文件进度（供 processing.rs 内部调用，按文件完整路径匹配）
 pub async fn upsert_file_progress(
     db: &Arc<DatabaseConnection>,
     task_id: &str,
-    file_name: &str,
+    file_path: &str,
     sheet_name: Option<&str>,
-    file_phase: Option<&str>,
-    sheet_phase: Option<&str>,
-    ai_confidence: Option<f32>,
-    mapping_count: Option<i32>,
-    success_count: Option<i32>,
-    error_count: Option<i32>,
-    total_rows: Option<i32>,
-    error_message: Option<&str>,
+    update: FileProgressUpdate<'_>,
 ) -> Result<(), String> {
+    let FileProgressUpdate {
+        file_phase,
+        sheet_phase,
+        ai_confidence,
+        mapping_count,
+        success_count,
+        error_count,
+        total_rows,
+        updated_count,
+        merged_count,
+        error_message,
+    } = update;
     let now = chrono::Utc::now();
 
     // 查找现有记录
     let existing = if let Some(sheet) = sheet_name {
         TaskFileProgress::find()
             .filter(task_file_progress::Column::TaskId.eq(task_id))
-            .filter(task_file_progress::Column::FileName.eq(file_name))
+            .filter(task_file_progress::Column::FilePath.eq(file_path))
             .filter(task_file_progress::Column::SheetName.eq(sheet))
             .one(db.as_ref())
             .await
@@ -321,7 +364,7 @@ pub async fn upsert_file_progress(
     } else {
         TaskFileProgress::find()
             .filter(task_file_progress::Column::TaskId.eq(task_id))
-            .filter(task_file_progress::Column::FileName.eq(file_name))
+            .filter(task_file_progress::Column::FilePath.eq(file_path))
             .filter(task_file_progress::Column::SheetName.is_null())
             .one(db.as_ref())
             .await
@@ -353,6 +396,12 @@ pub async fn upsert_file_progress(
         if let Some(count) = total_rows {
             active.total_rows = Set(count);
         }
+        if let Some(count) = updated_count {
+            active.updated_count = Set(count);
+        }
+        if let Some(count) = merged_count {
+            active.merged_count = Set(count);
+        }
         if error_message.is_some() {
             active.error_message = Set(error_message.map(|s| s.to_string()));
         }
@@ -361,9 +410,14 @@ pub async fn upsert_file_progress(
         active.update(db.as_ref()).await.map_err(|e| format!("数据库错误: {}", e))?;
     } else {
         // 创建新记录
+        let file_name = std::path::Path::new(file_path)
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
         let new_record = task_file_progress::ActiveModel {
             task_id: Set(task_id.to_string()),
-            file_name: Set(file_name.to_string()),
+            file_name: Set(file_name),
+            file_path: Set(Some(file_path.to_string())),
             file_phase: Set(file_phase.unwrap_or("waiting").to_string()),
             sheet_name: Set(sheet_name.map(|s| s.to_string())),
             sheet_phase: Set(sheet_phase.map(|s| s.to_string())),
@@ -372,6 +426,8 @@ pub async fn upsert_file_progress(
             success_count: Set(success_count.unwrap_or(0)),
             error_count: Set(error_count.unwrap_or(0)),
             total_rows: Set(total_rows.unwrap_or(0)),
+            updated_count: Set(updated_count.unwrap_or(0)),
+            merged_count: Set(merged_count.unwrap_or(0)),
             error_message: Set(error_message.map(|s| s.to_string())),
             created_at: Set(now),
             updated_at: Set(None),
@@ -390,6 +446,7 @@ pub async fn reset_processing_task(
     db: tauri::State<'_, Arc<DatabaseConnection>>,
     task_id: String,
     delete_records: bool,
+    actor: Option<String>,
 ) -> Result<TaskResponse, String> {
     let now = chrono::Utc::now();
 
@@ -407,11 +464,24 @@ pub async fn reset_processing_task(
         // 删除该批次的所有记录
         let batch_number = task.batch_number.clone();
         if let Some(batch) = batch_number {
+            let txn = db.inner().as_ref().begin().await
+                .map_err(|e| format!("开启事务失败: {}", e))?;
+            append_history_from(
+                &txn,
+                "project_records",
+                "batch_number = ?",
+                vec![batch.clone().into()],
+                OP_DELETE,
+                Snapshot::Before,
+                &actor_label(actor.as_deref()),
+            )
+            .await?;
             let delete_result = ProjectRecord::delete_many()
                 .filter(record::Column::BatchNumber.eq(&batch))
-                .exec(db.inner().as_ref())
+                .exec(&txn)
                 .await
                 .map_err(|e| format!("删除记录失败: {}", e))?;
+            txn.commit().await.map_err(|e| format!("提交事务失败: {}", e))?;
             tracing::info!("Deleted {} records for batch {}", delete_result.rows_affected, batch);
         }
     }
@@ -431,6 +501,7 @@ pub async fn reset_processing_task(
     active.processed_rows = Set(0);
     active.success_count = Set(0);
     active.error_count = Set(0);
+    active.checkpoint = Set(None);
     active.updated_at = Set(Some(now));
 
     let result = active
@@ -443,12 +514,31 @@ pub async fn reset_processing_task(
 
 // ============ 导入撤回功能 ============
 
+/// 撤回的记录保留天数，过期后彻底删除
+pub const ROLLBACK_RETENTION_DAYS: i64 = 30;
+
+/// project_records 与 rolled_back_records 共有的列
+const RECORD_COLUMNS: &str = "id, project_id, data, raw_data, source_file, source_sheet, row_number, \
+     batch_number, status, error_message, created_at, updated_at";
+
 /// 撤回结果
 #[derive(Debug, Serialize)]
 pub struct RollbackResult {
     pub success: bool,
     pub deleted_count: u64,
     pub message: String,
+    /// 可恢复的截止时间
+    pub restorable_until: String,
+}
+
+/// 恢复撤回的导入结果
+#[derive(Debug, Serialize)]
+pub struct RestoreBatchResult {
+    pub success: bool,
+    pub restored_count: u64,
+    /// 恢复时与现有记录重复、已标记为 duplicate 的记录数
+    pub duplicate_count: u64,
+    pub message: String,
 }
 
 /// 导入记录详情（以任务为单位，1个任务=1个文件）
@@ -461,17 +551,470 @@ pub struct BatchDetailResponse {
     pub created_at: String,
     pub status: String,
     pub total_records: i32,
+    /// 已撤回、可恢复的记录数
+    pub rolled_back_count: i32,
+    pub rolled_back_at: Option<String>,
+    /// 可恢复的截止时间（过期后撤回的记录被彻底删除）
+    pub restorable_until: Option<String>,
+}
+
+/// 撤回范围：导入批次、源文件、Sheet 可任意组合（至少指定一项，指定 Sheet 时须同时指定文件）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RollbackScope {
+    pub batch_number: Option<String>,
+    pub source_file: Option<String>,
+    pub source_sheet: Option<String>,
+}
+
+impl RollbackScope {
+    fn batch(batch_number: &str) -> Self {
+        Self { batch_number: Some(batch_number.to_string()), ..Default::default() }
+    }
+
+    /// 生成 WHERE 条件及参数（project_records 与 rolled_back_records 通用）
+    fn where_clause(&self, project_id: i32) -> Result<(String, Vec<sea_orm::Value>), String> {
+        let batch_number = self.batch_number.as_deref().filter(|s| !s.is_empty());
+        let source_file = self.source_file.as_deref().filter(|s| !s.is_empty());
+        let source_sheet = self.source_sheet.as_deref().filter(|s| !s.is_empty());
+        if batch_number.is_none() && source_file.is_none() {
+            return Err("撤回范围至少需要指定导入批次或源文件".to_string());
+        }
+        if source_sheet.is_some() && source_file.is_none() {
+            return Err("指定 Sheet 时必须同时指定源文件".to_string());
+        }
+
+        let mut sql = "project_id = ?".to_string();
+        let mut params: Vec<sea_orm::Value> = vec![project_id.into()];
+        for (column, value) in [
+            ("batch_number", batch_number),
+            ("source_file", source_file),
+            ("source_sheet", source_sheet),
+        ] {
+            if let Some(value) = value {
+                sql.push_str(&format!(" AND {} = ?", column));
+                params.push(value.to_string().into());
+            }
+        }
+        Ok((sql, params))
+    }
+
+    /// 用于提示信息的范围描述
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(batch) = self.batch_number.as_deref().filter(|s| !s.is_empty()) {
+            parts.push(format!("导入 {}", batch));
+        }
+        if let Some(file) = self.source_file.as_deref().filter(|s| !s.is_empty()) {
+            parts.push(format!("文件「{}」", file));
+        }
+        if let Some(sheet) = self.source_sheet.as_deref().filter(|s| !s.is_empty()) {
+            parts.push(format!("Sheet「{}」", sheet));
+        }
+        parts.join(" / ")
+    }
+}
+
+/// 校验撤回范围：批次、文件、Sheet 须对应项目中已有的导入任务，避免拼写错误时静默匹配 0 条记录
+async fn validate_scope(db: &DatabaseConnection, project_id: i32, scope: &RollbackScope) -> Result<(), String> {
+    scope.where_clause(project_id)?;
+    let batch_number = scope.batch_number.as_deref().filter(|s| !s.is_empty());
+    let source_file = scope.source_file.as_deref().filter(|s| !s.is_empty());
+    let source_sheet = scope.source_sheet.as_deref().filter(|s| !s.is_empty());
+
+    let mut query = ProcessingTask::find().filter(task::Column::ProjectId.eq(project_id));
+    if let Some(batch) = batch_number {
+        query = query.filter(task::Column::BatchNumber.eq(batch));
+    }
+    let tasks = query
+        .all(db)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+    if let Some(batch) = batch_number {
+        if tasks.is_empty() {
+            return Err(format!("导入批次 {} 不存在或不属于项目 {}", batch, project_id));
+        }
+    }
+
+    let Some(file) = source_file else {
+        return Ok(());
+    };
+    let mut file_tasks = Vec::new();
+    for t in tasks {
+        let count = t
+            .source_files
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<Vec<String>>(json).ok())
+            .map_or(0, |files| files.iter().filter(|f| f.as_str() == file).count());
+        if count > 1 {
+            return Err(ambiguous_file_error(t.batch_number.as_deref().unwrap_or_default(), file));
+        }
+        if count == 1 {
+            file_tasks.push(t.id);
+        }
+    }
+    if file_tasks.is_empty() {
+        return Err(match batch_number {
+            Some(batch) => format!("导入批次 {} 中没有文件「{}」", batch, file),
+            None => format!("项目 {} 没有导入过文件「{}」", project_id, file),
+        });
+    }
+
+    let Some(sheet) = source_sheet else {
+        return Ok(());
+    };
+    let sheet_found = TaskFileProgress::find()
+        .filter(task_file_progress::Column::TaskId.is_in(file_tasks))
+        .filter(task_file_progress::Column::FileName.eq(file))
+        .filter(task_file_progress::Column::SheetName.eq(sheet))
+        .one(db)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+        .is_some();
+    if !sheet_found {
+        return Err(format!("文件「{}」中没有导入过 Sheet「{}」", file, sheet));
+    }
+    Ok(())
+}
+
+fn ambiguous_file_error(batch_number: &str, file_name: &str) -> String {
+    format!(
+        "导入批次 {} 中有多个名为「{}」的文件（来自不同文件夹），无法区分记录所属的文件，不能按文件撤回或恢复",
+        batch_number, file_name
+    )
+}
+
+/// 确认批次中只有一个同名文件
+///
+/// 记录只保存文件名，同一批次导入了不同文件夹下的同名文件时，无法确定撤回 / 恢复的记录对应哪个文件的进度
+async fn ensure_unique_file_name<C: ConnectionTrait>(
+    conn: &C,
+    project_id: i32,
+    batch_number: &str,
+    file_name: &str,
+) -> Result<(), String> {
+    let row = conn
+        .query_one(Statement::from_sql_and_values(
+            conn.get_database_backend(),
+            "SELECT COUNT(DISTINCT COALESCE(file_path, file_name)) FROM task_file_progress \
+             WHERE task_id IN (SELECT id FROM processing_tasks WHERE project_id = ? AND batch_number = ?) \
+             AND file_name = ? AND sheet_name IS NULL",
+            vec![project_id.into(), batch_number.into(), file_name.into()],
+        ))
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+    let files = row.and_then(|r| r.try_get_by_index::<i64>(0).ok()).unwrap_or(0);
+    if files > 1 {
+        return Err(ambiguous_file_error(batch_number, file_name));
+    }
+    Ok(())
+}
+
+/// 撤回预览中按 批次 + 文件 + Sheet 分组的记录数
+#[derive(Debug, Serialize)]
+pub struct RollbackPreviewGroup {
+    pub batch_number: Option<String>,
+    pub source_file: Option<String>,
+    pub source_sheet: Option<String>,
+    /// 导入成功的记录数（计入任务 success_count）
+    pub success_count: i64,
+    /// 被拒绝保存的记录数（重复、验证失败等，计入任务 error_count）
+    pub error_count: i64,
+}
+
+/// 撤回预览（删除前确认）
+#[derive(Debug, Serialize)]
+pub struct RollbackPreview {
+    pub total_count: i64,
+    pub success_count: i64,
+    pub error_count: i64,
+    pub groups: Vec<RollbackPreviewGroup>,
+}
+
+/// 统计表中指定范围的记录数（按 批次 + 文件 + Sheet 分组）
+async fn count_scope_records<C: ConnectionTrait>(
+    conn: &C,
+    table: &str,
+    project_id: i32,
+    scope: &RollbackScope,
+) -> Result<Vec<RollbackPreviewGroup>, String> {
+    let (where_sql, params) = scope.where_clause(project_id)?;
+    let rows = conn
+        .query_all(Statement::from_sql_and_values(
+            conn.get_database_backend(),
+            format!(
+                "SELECT batch_number, source_file, source_sheet, \
+                 SUM(CASE WHEN status = 'success' THEN 1 ELSE 0 END), \
+                 SUM(CASE WHEN status = 'success' THEN 0 ELSE 1 END) \
+                 FROM {} WHERE {} \
+                 GROUP BY batch_number, source_file, source_sheet \
+                 ORDER BY batch_number, source_file, source_sheet",
+                table, where_sql
+            ),
+            params,
+        ))
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| RollbackPreviewGroup {
+            batch_number: row.try_get_by_index::<Option<String>>(0).unwrap_or_default(),
+            source_file: row.try_get_by_index::<Option<String>>(1).unwrap_or_default(),
+            source_sheet: row.try_get_by_index::<Option<String>>(2).unwrap_or_default(),
+            success_count: row.try_get_by_index::<i64>(3).unwrap_or(0),
+            error_count: row.try_get_by_index::<i64>(4).unwrap_or(0),
+        })
+        .collect())
+}
+
+/// 按撤回（sign = 1）或恢复（sign = -1）的记录数同步任务计数与文件/Sheet 进度
+///
+/// 成功、失败与总行数随记录一起减少（恢复时加回）。更新 / 合并计数对应的是对已有记录的修改，
+/// 这些修改不随撤回还原，因此保持不变，总行数中也继续包含这部分行。
+async fn sync_progress_counts<C: ConnectionTrait>(
+    conn: &C,
+    project_id: i32,
+    groups: &[RollbackPreviewGroup],
+    sign: i64,
+) -> Result<(), String> {
+    let backend = conn.get_database_backend();
+    let now = chrono::Utc::now();
+    for group in groups {
+        // 无批次号的记录（手动录入）不对应任何任务
+        let Some(batch_number) = group.batch_number.clone() else {
+            continue;
+        };
+        let success = group.success_count * sign;
+        let errors = group.error_count * sign;
+        let rows = success + errors;
+
+        conn.execute(Statement::from_sql_and_values(
+            backend,
+            "UPDATE processing_tasks SET \
+             success_count = MAX(success_count - ?, 0), error_count = MAX(error_count - ?, 0), \
+             total_rows = MAX(total_rows - ?, 0), processed_rows = MAX(processed_rows - ?, 0), updated_at = ? \
+             WHERE project_id = ? AND batch_number = ?",
+            vec![
+                success.into(),
+                errors.into(),
+                rows.into(),
+                rows.into(),
+                now.into(),
+                project_id.into(),
+                batch_number.clone().into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("更新任务计数失败: {}", e))?;
+
+        // 文件级别记录（sheet_name 为空）与对应 Sheet 记录同时更新
+        let Some(file_name) = group.source_file.clone() else {
+            continue;
+        };
+        ensure_unique_file_name(conn, project_id, &batch_number, &file_name).await?;
+        conn.execute(Statement::from_sql_and_values(
+            backend,
+            "UPDATE task_file_progress SET \
+             success_count = MAX(success_count - ?, 0), error_count = MAX(error_count - ?, 0), \
+             total_rows = MAX(total_rows - ?, updated_count + merged_count), updated_at = ? \
+             WHERE task_id IN (SELECT id FROM processing_tasks WHERE project_id = ? AND batch_number = ?) \
+             AND file_name = ? AND (sheet_name IS NULL OR sheet_name = ?)",
+            vec![
+                success.into(),
+                errors.into(),
+                rows.into(),
+                now.into(),
+                project_id.into(),
+                batch_number.into(),
+                file_name.into(),
+                group.source_sheet.clone().into(),
+            ],
+        ))
+        .await
+        .map_err(|e| format!("更新文件进度失败: {}", e))?;
+    }
+    Ok(())
+}
+
+/// 撤回指定范围的记录：移入 rolled_back_records（保留原 ID）并同步任务进度
+async fn rollback_scope(
+    db: &DatabaseConnection,
+    project_id: i32,
+    scope: &RollbackScope,
+    actor: &str,
+) -> Result<RollbackResult, String> {
+    let (where_sql, params) = scope.where_clause(project_id)?;
+    purge_expired_rollbacks(db).await?;
+
+    let now = chrono::Utc::now();
+    let restorable_until = (now + chrono::Duration::days(ROLLBACK_RETENTION_DAYS)).to_rfc3339();
+
+    // 在同一事务中移入撤回记录表、删除并更新进度
+    let txn = db.begin().await.map_err(|e| format!("开启事务失败: {}", e))?;
+    let backend = txn.get_database_backend();
+    let groups = count_scope_records(&txn, "project_records", project_id, scope).await?;
+    append_history_from(&txn, "project_records", &where_sql, params.clone(), OP_ROLLBACK, Snapshot::Before, actor)
+        .await?;
+
+    let mut insert_params: Vec<sea_orm::Value> = vec![now.to_rfc3339().into()];
+    insert_params.extend(params.iter().cloned());
+    txn.execute(Statement::from_sql_and_values(
+        backend,
+        format!(
+            "INSERT OR REPLACE INTO rolled_back_records ({cols}, rolled_back_at) \
+             SELECT {cols}, ? FROM project_records WHERE {where_sql}",
+            cols = RECORD_COLUMNS,
+            where_sql = where_sql
+        ),
+        insert_params,
+    ))
+    .await
+    .map_err(|e| format!("撤回记录失败: {}", e))?;
+
+    let delete_result = txn
+        .execute(Statement::from_sql_and_values(
+            backend,
+            format!("DELETE FROM project_records WHERE {}", where_sql),
+            params,
+        ))
+        .await
+        .map_err(|e| format!("删除记录失败: {}", e))?;
+
+    sync_progress_counts(&txn, project_id, &groups, 1).await?;
+    txn.commit().await.map_err(|e| format!("提交事务失败: {}", e))?;
+
+    let deleted_count = delete_result.rows_affected();
+    tracing::info!("Rolled back {:?}, moved {} records to rolled_back_records", scope, deleted_count);
+
+    Ok(RollbackResult {
+        success: true,
+        deleted_count,
+        message: format!(
+            "已撤回{}，移除了 {} 条记录（{} 天内可恢复）",
+            scope.describe(), deleted_count, ROLLBACK_RETENTION_DAYS
+        ),
+        restorable_until,
+    })
+}
+
+/// 恢复指定范围内撤回的记录：按原 ID 放回 project_records 并同步任务进度
+///
+/// 撤回期间可能重新导入了相同的数据，恢复的成功记录逐条按项目去重设置检查，
+/// 与现有记录重复的标记为 duplicate（与导入时的「跳过」一致），不会产生两条成功记录
+async fn restore_scope(
+    db: &DatabaseConnection,
+    project_id: i32,
+    scope: &RollbackScope,
+    actor: &str,
+) -> Result<RestoreBatchResult, String> {
+    let (where_sql, params) = scope.where_clause(project_id)?;
+    purge_expired_rollbacks(db).await?;
+
+    let project = Project::find_by_id(project_id)
+        .one(db)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?
+        .ok_or_else(|| format!("项目 {} 不存在", project_id))?;
+    let fields = ProjectField::find()
+        .filter(field::Column::ProjectId.eq(project_id))
+        .filter(field::Column::IsDeleted.eq(false))
+        .all(db)
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+
+    let txn = db.begin().await.map_err(|e| format!("开启事务失败: {}", e))?;
+    let backend = txn.get_database_backend();
+
+    let insert_result = txn
+        .execute(Statement::from_sql_and_values(
+            backend,
+            format!(
+                "INSERT INTO project_records ({cols}) \
+                 SELECT {cols} FROM rolled_back_records WHERE {where_sql}",
+                cols = RECORD_COLUMNS,
+                where_sql = where_sql
+            ),
+            params.clone(),
+        ))
+        .await
+        .map_err(|e| format!("恢复记录失败: {}", e))?;
+
+    let restored_count = insert_result.rows_affected();
+    if restored_count == 0 {
+        return Err(format!("{} 没有可恢复的记录（未撤回或已过期）", scope.describe()));
+    }
+
+    // 按 ID 顺序逐条去重：已标记的记录不再参与比对，同组重复中只保留一条成功记录
+    let restored = txn
+        .query_all(Statement::from_sql_and_values(
+            backend,
+            format!("SELECT id, data FROM rolled_back_records WHERE {} AND status = 'success' ORDER BY id", where_sql),
+            params.clone(),
+        ))
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+    let mut duplicate_count = 0u64;
+    for row in restored {
+        let id: i32 = row.try_get_by("id").map_err(|e| format!("数据库错误: {}", e))?;
+        let data_str: String = row.try_get_by("data").unwrap_or_default();
+        let data: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&data_str).unwrap_or_default();
+        let Some(dup_id) = find_duplicate_of(&txn, &project, &fields, &data, Some(id)).await? else {
+            continue;
+        };
+
+        // 两张表同时标记：历史从撤回记录表读取恢复后的版本
+        for table in ["project_records", "rolled_back_records"] {
+            txn.execute(Statement::from_sql_and_values(
+                backend,
+                format!("UPDATE {} SET status = 'duplicate', error_message = ? WHERE id = ?", table),
+                vec![format!("与记录 #{} 重复（恢复时检查）", dup_id).into(), id.into()],
+            ))
+            .await
+            .map_err(|e| format!("标记重复记录失败: {}", e))?;
+        }
+        duplicate_count += 1;
+    }
+
+    let groups = count_scope_records(&txn, "rolled_back_records", project_id, scope).await?;
+    append_history_from(&txn, "rolled_back_records", &where_sql, params.clone(), OP_RESTORE, Snapshot::After, actor)
+        .await?;
+
+    txn.execute(Statement::from_sql_and_values(
+        backend,
+        format!("DELETE FROM rolled_back_records WHERE {}", where_sql),
+        params,
+    ))
+    .await
+    .map_err(|e| format!("删除撤回记录失败: {}", e))?;
+
+    sync_progress_counts(&txn, project_id, &groups, -1).await?;
+    txn.commit().await.map_err(|e| format!("提交事务失败: {}", e))?;
+
+    tracing::info!("Restored {:?}, {} records ({} duplicates)", scope, restored_count, duplicate_count);
+
+    let mut message = format!("已恢复{}，恢复了 {} 条记录", scope.describe(), restored_count);
+    if duplicate_count > 0 {
+        message.push_str(&format!("，其中 {} 条与现有记录重复，已标记为重复", duplicate_count));
+    }
+    Ok(RestoreBatchResult {
+        success: true,
+        restored_count,
+        duplicate_count,
+        message,
+    })
 }
 
 /// 撤回整个导入（通过 batch_number 关联到 processing_tasks）
+///
+/// 记录移入 rolled_back_records（保留原 ID），ROLLBACK_RETENTION_DAYS 天内可通过 restore_batch 恢复
 #[tauri::command]
 pub async fn rollback_batch(
     db: tauri::State<'_, Arc<DatabaseConnection>>,
     project_id: i32,
     batch_number: String,
+    actor: Option<String>,
 ) -> Result<RollbackResult, String> {
-    use crate::backend::infrastructure::persistence::models::record;
-
     tracing::info!("Rolling back batch {} for project {}", batch_number, project_id);
 
     // 验证该 batch_number 存在且属于该项目（从 processing_tasks 验证）
@@ -483,25 +1026,92 @@ pub async fn rollback_batch(
         .map_err(|e| format!("数据库错误: {}", e))?
         .ok_or_else(|| format!("导入记录 {} 不存在或不属于项目 {}", batch_number, project_id))?;
 
-    // 删除该 batch_number 的所有记录
-    let delete_result = ProjectRecord::delete_many()
-        .filter(record::Column::ProjectId.eq(project_id))
-        .filter(record::Column::BatchNumber.eq(&batch_number))
-        .exec(db.inner().as_ref())
-        .await
-        .map_err(|e| format!("删除记录失败: {}", e))?;
+    let scope = RollbackScope::batch(&batch_number);
+    rollback_scope(db.inner().as_ref(), project_id, &scope, &actor_label(actor.as_deref())).await
+}
 
-    let deleted_count = delete_result.rows_affected;
+/// 恢复撤回的导入：将撤回记录按原 ID 放回 project_records
+#[tauri::command]
+pub async fn restore_batch(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    project_id: i32,
+    batch_number: String,
+    actor: Option<String>,
+) -> Result<RestoreBatchResult, String> {
+    tracing::info!("Restoring batch {} for project {}", batch_number, project_id);
+    let scope = RollbackScope::batch(&batch_number);
+    restore_scope(db.inner().as_ref(), project_id, &scope, &actor_label(actor.as_deref())).await
+}
 
-    tracing::info!("Rolled back batch {}, deleted {} records", batch_number, deleted_count);
+/// 预览撤回范围内的记录数（删除前确认）
+#[tauri::command]
+pub async fn preview_rollback(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    project_id: i32,
+    scope: RollbackScope,
+) -> Result<RollbackPreview, String> {
+    preview_scope(db.inner().as_ref(), project_id, &scope).await
+}
 
-    Ok(RollbackResult {
-        success: true,
-        deleted_count,
-        message: format!("已撤回导入，删除了 {} 条记录", deleted_count),
+async fn preview_scope(db: &DatabaseConnection, project_id: i32, scope: &RollbackScope) -> Result<RollbackPreview, String> {
+    validate_scope(db, project_id, scope).await?;
+    let groups = count_scope_records(db, "project_records", project_id, scope).await?;
+    let success_count = groups.iter().map(|g| g.success_count).sum();
+    let error_count = groups.iter().map(|g| g.error_count).sum();
+
+    Ok(RollbackPreview {
+        total_count: success_count + error_count,
+        success_count,
+        error_count,
+        groups,
     })
 }
 
+/// 按文件或 Sheet 撤回部分导入（可与批次组合），其余记录保持不变
+#[tauri::command]
+pub async fn rollback_records(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    project_id: i32,
+    scope: RollbackScope,
+    actor: Option<String>,
+) -> Result<RollbackResult, String> {
+    tracing::info!("Rolling back {:?} for project {}", scope, project_id);
+    validate_scope(db.inner().as_ref(), project_id, &scope).await?;
+    rollback_scope(db.inner().as_ref(), project_id, &scope, &actor_label(actor.as_deref())).await
+}
+
+/// 恢复按文件或 Sheet 撤回的记录
+#[tauri::command]
+pub async fn restore_records(
+    db: tauri::State<'_, Arc<DatabaseConnection>>,
+    project_id: i32,
+    scope: RollbackScope,
+    actor: Option<String>,
+) -> Result<RestoreBatchResult, String> {
+    tracing::info!("Restoring {:?} for project {}", scope, project_id);
+    validate_scope(db.inner().as_ref(), project_id, &scope).await?;
+    restore_scope(db.inner().as_ref(), project_id, &scope, &actor_label(actor.as_deref())).await
+}
+
+/// 彻底删除超过保留期限的撤回记录，返回删除的记录数
+pub async fn purge_expired_rollbacks(db: &DatabaseConnection) -> Result<u64, String> {
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(ROLLBACK_RETENTION_DAYS)).to_rfc3339();
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "DELETE FROM rolled_back_records WHERE rolled_back_at < ?",
+            vec![cutoff.into()],
+        ))
+        .await
+        .map_err(|e| format!("清理过期撤回记录失败: {}", e))?;
+
+    let purged = result.rows_affected();
+    if purged > 0 {
+        tracing::info!("Purged {} expired rolled back records", purged);
+    }
+    Ok(purged)
+}
+
 /// 获取项目的所有导入记录列表（以任务为单位，带实时记录数统计）
 #[tauri::command]
 pub async fn get_project_batches_with_stats(
@@ -521,13 +1131,15 @@ pub async fn get_project_batches_with_stats(
         return Ok(Vec::new());
     }
 
+    purge_expired_rollbacks(db.inner().as_ref()).await?;
+
     // 收集所有 batch_number，用于单次聚合查询
     let batch_numbers: Vec<String> = tasks
         .iter()
         .filter_map(|t| t.batch_number.clone())
         .collect();
 
-    // 单次聚合查询获取所有批次的记录数，避免 N+1 问题
+    // 单次聚合查询获取所有批次的成功记录数（与 success_count 一致），避免 N+1 问题
     let mut record_counts: std::collections::HashMap<String, i64> =
         std::collections::HashMap::new();
 
@@ -535,7 +1147,7 @@ pub async fn get_project_batches_with_stats(
         let placeholders: Vec<&str> = batch_numbers.iter().map(|_| "?").collect();
         let count_sql = format!(
             "SELECT batch_number, COUNT(*) as cnt FROM project_records \
-             WHERE project_id = ? AND batch_number IN ({}) \
+             WHERE project_id = ? AND status = 'success' AND batch_number IN ({}) \
              GROUP BY batch_number",
             placeholders.join(",")
         );
@@ -563,6 +1175,28 @@ pub async fn get_project_batches_with_stats(
         }
     }
 
+    // 已撤回、尚未过期的记录数与撤回时间
+    let mut rolled_back: std::collections::HashMap<String, (i64, String)> =
+        std::collections::HashMap::new();
+    let rows = db
+        .inner()
+        .as_ref()
+        .query_all(Statement::from_sql_and_values(
+            db.inner().as_ref().get_database_backend(),
+            "SELECT batch_number, COUNT(*) as cnt, MAX(rolled_back_at) as rolled_back_at \
+             FROM rolled_back_records WHERE project_id = ? AND batch_number IS NOT NULL \
+             GROUP BY batch_number",
+            vec![project_id.into()],
+        ))
+        .await
+        .map_err(|e| format!("数据库错误: {}", e))?;
+    for row in rows {
+        let bn: String = row.try_get_by_index::<String>(0).unwrap_or_default();
+        let cnt: i64 = row.try_get_by_index::<i64>(1).unwrap_or(0);
+        let at: String = row.try_get_by_index::<String>(2).unwrap_or_default();
+        rolled_back.insert(bn, (cnt, at));
+    }
+
     // 构建响应
     let results: Vec<BatchDetailResponse> = tasks
         .into_iter()
@@ -578,9 +1212,18 @@ pub async fn get_project_batches_with_stats(
                 .and_then(|v| v.into_iter().next())
                 .unwrap_or_else(|| batch_number.clone());
 
+            let (rolled_back_count, rolled_back_at) = match rolled_back.get(&batch_number) {
+                Some((cnt, at)) => (*cnt, Some(at.clone())),
+                None => (0, None),
+            };
+            let restorable_until = rolled_back_at
+                .as_deref()
+                .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+                .map(|at| (at + chrono::Duration::days(ROLLBACK_RETENTION_DAYS)).to_rfc3339());
+
             let status = if actual_count > 0 {
                 task_model.status.clone()
-            } else if task_model.status == "completed" {
+            } else if rolled_back_count > 0 || task_model.status == "completed" {
                 "rolled_back".to_string()
             } else {
                 task_model.status.clone()
@@ -594,9 +1237,228 @@ pub async fn get_project_batches_with_stats(
                 created_at: task_model.created_at.to_rfc3339(),
                 status,
                 total_records: actual_count as i32,
+                rolled_back_count: rolled_back_count as i32,
+                rolled_back_at,
+                restorable_until,
             })
         })
         .collect();
 
     Ok(results)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::infrastructure::persistence::migrations::run_migrations;
+    use crate::backend::infrastructure::persistence::models::record;
+    use sea_orm::Database;
+
+    async fn exec(db: &DatabaseConnection, sql: &str) {
+        db.execute(Statement::from_string(db.get_database_backend(), sql.to_string()))
+            .await
+            .unwrap();
+    }
+
+    /// 项目 1：手机号（字段 1）为去重字段；导入 B1 的 a.csv / Sheet1 有 2 条成功、1 条验证失败，
+    /// 另有 1 行更新了已有记录（不产生新记录）
+    async fn setup() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&db).await.unwrap();
+        let now = "2026-01-01T00:00:00+00:00";
+        for sql in [
+            format!("INSERT INTO projects (id, name, dedup_enabled, created_at) VALUES (1, 'p', 1, '{now}')"),
+            format!("INSERT INTO project_fields (id, project_id, field_name, field_label, field_type, is_dedup_key, created_at) VALUES (1, 1, 'phone', '手机号', 'phone', 1, '{now}')"),
+            format!("INSERT INTO processing_tasks (id, project_id, status, batch_number, source_files, total_rows, processed_rows, success_count, error_count, created_at) VALUES ('t1', 1, 'completed', 'B1', '[\"a.csv\"]', 4, 4, 2, 1, '{now}')"),
+            format!("INSERT INTO task_file_progress (task_id, file_name, sheet_name, success_count, error_count, total_rows, updated_count, created_at) VALUES ('t1', 'a.csv', NULL, 2, 1, 4, 1, '{now}')"),
+            format!("INSERT INTO task_file_progress (task_id, file_name, sheet_name, success_count, error_count, total_rows, updated_count, created_at) VALUES ('t1', 'a.csv', 'Sheet1', 2, 1, 4, 1, '{now}')"),
+            format!("INSERT INTO project_records (id, project_id, data, source_file, source_sheet, batch_number, status, created_at) VALUES (1, 1, '{{\"1\":\"13800000001\"}}', 'a.csv', 'Sheet1', 'B1', 'success', '{now}')"),
+            format!("INSERT INTO project_records (id, project_id, data, source_file, source_sheet, batch_number, status, created_at) VALUES (2, 1, '{{\"1\":\"13800000002\"}}', 'a.csv', 'Sheet1', 'B1', 'success', '{now}')"),
+            format!("INSERT INTO project_records (id, project_id, data, source_file, source_sheet, batch_number, status, error_message, created_at) VALUES (3, 1, '{{\"1\":\"bad\"}}', 'a.csv', 'Sheet1', 'B1', 'validation_error', '手机号 格式错误', '{now}')"),
+        ] {
+            exec(&db, &sql).await;
+        }
+        db
+    }
+
+    /// 任务计数：(成功, 失败, 总行数)
+    async fn task_counts(db: &DatabaseConnection) -> (i32, i32, i32) {
+        let t = ProcessingTask::find_by_id("t1").one(db).await.unwrap().unwrap();
+        (t.success_count, t.error_count, t.total_rows)
+    }
+
+    /// 文件 / Sheet 进度计数：(成功, 失败, 总行数, 更新)
+    async fn progress_counts(db: &DatabaseConnection) -> Vec<(i32, i32, i32, i32)> {
+        TaskFileProgress::find()
+            .filter(task_file_progress::Column::TaskId.eq("t1"))
+            .order_by_asc(task_file_progress::Column::Id)
+            .all(db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.success_count, p.error_count, p.total_rows, p.updated_count))
+            .collect()
+    }
+
+    async fn statuses(db: &DatabaseConnection) -> Vec<(i32, String)> {
+        ProjectRecord::find()
+            .order_by_asc(record::Column::Id)
+            .all(db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.id, r.status))
+            .collect()
+    }
+
+    fn scope(batch: Option<&str>, file: Option<&str>, sheet: Option<&str>) -> RollbackScope {
+        RollbackScope {
+            batch_number: batch.map(str::to_string),
+            source_file: file.map(str::to_string),
+            source_sheet: sheet.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_where_clause() {
+        let (sql, params) = scope(Some("B1"), None, None).where_clause(1).unwrap();
+        assert_eq!(sql, "project_id = ? AND batch_number = ?");
+        assert_eq!(params, vec![1.into(), "B1".to_string().into()]);
+
+        let (sql, params) = scope(None, Some("a.csv"), Some("Sheet1")).where_clause(1).unwrap();
+        assert_eq!(sql, "project_id = ? AND source_file = ? AND source_sheet = ?");
+        assert_eq!(params.len(), 3);
+
+        // 空字符串视为未指定
+        let (sql, _) = scope(Some("B1"), Some("a.csv"), Some("")).where_clause(1).unwrap();
+        assert_eq!(sql, "project_id = ? AND batch_number = ? AND source_file = ?");
+
+        assert!(scope(None, None, None).where_clause(1).is_err());
+        assert!(scope(Some(""), Some(""), None).where_clause(1).is_err());
+        assert!(scope(Some("B1"), None, Some("Sheet1")).where_clause(1).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_preview_counts_and_scope_validation() {
+        let db = setup().await;
+
+        let preview = preview_scope(&db, 1, &scope(Some("B1"), Some("a.csv"), Some("Sheet1"))).await.unwrap();
+        assert_eq!((preview.total_count, preview.success_count, preview.error_count), (3, 2, 1));
+        assert_eq!(preview.groups.len(), 1);
+        assert_eq!(preview.groups[0].source_sheet.as_deref(), Some("Sheet1"));
+        let preview = preview_scope(&db, 1, &scope(None, Some("a.csv"), None)).await.unwrap();
+        assert_eq!(preview.total_count, 3);
+
+        // 拼写错误的批次 / 文件 / Sheet、其他项目的批次、仅指定 Sheet 都应明确报错
+        for (bad, expected) in [
+            (scope(Some("B2"), None, None), "导入批次 B2 不存在"),
+            (scope(Some("B1"), Some("b.csv"), None), "导入批次 B1 中没有文件「b.csv」"),
+            (scope(None, Some("b.csv"), None), "没有导入过文件「b.csv」"),
+            (scope(None, Some("a.csv"), Some("Sheet2")), "没有导入过 Sheet「Sheet2」"),
+            (scope(Some("B1"), None, Some("Sheet1")), "指定 Sheet 时必须同时指定源文件"),
+        ] {
+            let err = preview_scope(&db, 1, &bad).await.unwrap_err();
+            assert!(err.contains(expected), "{}", err);
+        }
+        assert!(preview_scope(&db, 2, &scope(Some("B1"), None, None)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_and_restore_sync_progress() {
+        let db = setup().await;
+        let scope = RollbackScope::batch("B1");
+
+        let result = rollback_scope(&db, 1, &scope, "tester").await.unwrap();
+        assert_eq!(result.deleted_count, 3);
+        assert!(statuses(&db).await.is_empty());
+        // 更新已有记录的 1 行仍然有效，计入总行数
+        assert_eq!(task_counts(&db).await, (0, 0, 1));
+        assert_eq!(progress_counts(&db).await, vec![(0, 0, 1, 1); 2]);
+
+        let result = restore_scope(&db, 1, &scope, "tester").await.unwrap();
+        assert_eq!((result.restored_count, result.duplicate_count), (3, 0));
+        assert_eq!(statuses(&db).await.len(), 3);
+        assert_eq!(task_counts(&db).await, (2, 1, 4));
+        assert_eq!(progress_counts(&db).await, vec![(2, 1, 4, 1); 2]);
+    }
+
+    /// 同一批次中不同文件夹下的同名文件无法区分进度，撤回应明确报错且不修改任何数据
+    #[tokio::test]
+    async fn test_rollback_rejects_same_named_files_in_batch() {
+        let db = setup().await;
+        let now = "2026-01-01T00:00:00+00:00";
+        exec(&db, "UPDATE task_file_progress SET file_path = '/x/a.csv'").await;
+        exec(&db, "UPDATE processing_tasks SET source_files = '[\"a.csv\",\"a.csv\"]'").await;
+        exec(&db, &format!("INSERT INTO task_file_progress (task_id, file_name, file_path, sheet_name, success_count, error_count, total_rows, created_at) VALUES ('t1', 'a.csv', '/y/a.csv', NULL, 1, 0, 1, '{now}')")).await;
+
+        let err = preview_scope(&db, 1, &scope(Some("B1"), Some("a.csv"), None)).await.unwrap_err();
+        assert!(err.contains("多个名为「a.csv」的文件"), "{}", err);
+        let err = rollback_scope(&db, 1, &RollbackScope::batch("B1"), "tester").await.unwrap_err();
+        assert!(err.contains("多个名为「a.csv」的文件"), "{}", err);
+        assert_eq!(statuses(&db).await.len(), 3);
+        assert_eq!(task_counts(&db).await, (2, 1, 4));
+    }
+
+    #[tokio::test]
+    async fn test_restore_marks_reimported_duplicates() {
+        let db = setup().await;
+        let scope = RollbackScope::batch("B1");
+        rollback_scope(&db, 1, &scope, "tester").await.unwrap();
+
+        // 撤回后重新导入了其中一条
+        exec(
+            &db,
+            "INSERT INTO project_records (id, project_id, data, batch_number, status, created_at) \
+             VALUES (4, 1, '{\"1\":\"13800000002\"}', 'B2', 'success', '2026-01-02T00:00:00+00:00')",
+        )
+        .await;
+
+        let result = restore_scope(&db, 1, &scope, "tester").await.unwrap();
+        assert_eq!((result.restored_count, result.duplicate_count), (3, 1));
+        assert!(result.message.contains("1 条与现有记录重复"));
+        assert_eq!(
+            statuses(&db).await,
+            vec![
+                (1, "success".to_string()),
+                (2, "duplicate".to_string()),
+                (3, "validation_error".to_string()),
+                (4, "success".to_string()),
+            ]
+        );
+        let error = ProjectRecord::find_by_id(2).one(&db).await.unwrap().unwrap().error_message;
+        assert_eq!(error.as_deref(), Some("与记录 #4 重复（恢复时检查）"));
+
+        // 重复的记录计入失败数；恢复历史记录的是标记后的状态
+        assert_eq!(task_counts(&db).await, (1, 2, 4));
+        assert_eq!(progress_counts(&db).await, vec![(1, 2, 4, 1); 2]);
+        let history = db
+            .query_one(Statement::from_string(
+                db.get_database_backend(),
+                "SELECT after_status FROM record_history WHERE record_id = 2 AND operation = 'restore'".to_string(),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(history.try_get_by::<String, _>("after_status").unwrap(), "duplicate");
+    }
+
+    #[tokio::test]
+    async fn test_expired_rollbacks_are_purged() {
+        let db = setup().await;
+        let scope = RollbackScope::batch("B1");
+        let result = rollback_scope(&db, 1, &scope, "tester").await.unwrap();
+        let until = chrono::DateTime::parse_from_rfc3339(&result.restorable_until).unwrap();
+        let days = (until.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_days();
+        assert!((ROLLBACK_RETENTION_DAYS - 1..=ROLLBACK_RETENTION_DAYS).contains(&days));
+
+        // 保留期内不清理
+        assert_eq!(purge_expired_rollbacks(&db).await.unwrap(), 0);
+
+        let expired = (chrono::Utc::now() - chrono::Duration::days(ROLLBACK_RETENTION_DAYS + 1)).to_rfc3339();
+        exec(&db, &format!("UPDATE rolled_back_records SET rolled_back_at = '{}'", expired)).await;
+        assert_eq!(purge_expired_rollbacks(&db).await.unwrap(), 3);
+
+        let err = restore_scope(&db, 1, &scope, "tester").await.unwrap_err();
+        assert!(err.contains("没有可恢复的记录"), "{}", err);
+    }
+}
@@ -149,7 +149,7 @@ pub fn run() {
     });
     println!("✅ 数据库迁移完成");
 
-    // 清理滞留任务：崩溃/强退后残留的 processing/paused → interrupted
+    // 清理滞留任务：崩溃/强退后残留的 processing/paused/awaiting_review → interrupted
     println!("🔄 正在清理滞留任务...");
     runtime.block_on(async {
         use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
@@ -158,7 +158,8 @@ pub fn run() {
             .filter(
                 sea_orm::Condition::any()
                     .add(task::Column::Status.eq("processing"))
-                    .add(task::Column::Status.eq("paused")),
+                    .add(task::Column::Status.eq("paused"))
+                    .add(task::Column::Status.eq("awaiting_review")),
             )
             .all(&db)
             .await
@@ -181,6 +182,15 @@ pub fn run() {
         }
     });
 
+    // 清理超过保留期限的撤回记录
+    runtime.block_on(async {
+        match commands::tasks::purge_expired_rollbacks(&db).await {
+            Ok(count) if count > 0 => println!("🗑️  已清理 {} 条过期的撤回记录", count),
+            Ok(_) => {}
+            Err(e) => eprintln!("警告: {}", e),
+        }
+    });
+
     // 将数据库连接包装为 Arc，用于在多个 commands 之间共享
     let db = Arc::new(db);
 
@@ -197,6 +207,9 @@ pub fn run() {
             commands::get_project,
             commands::update_project,
             commands::delete_project,
+            commands::get_deleted_projects,
+            commands::restore_project,
+            commands::purge_project,
             // 项目分组 Commands
             commands::get_project_groups,
             commands::get_project_groups_flat,
@@ -233,6 +246,7 @@ pub fn run() {
             commands::query_records_advanced,
             commands::get_record,
             commands::update_record,
+            commands::revalidate_record,
             commands::delete_record,
             commands::delete_project_records,
             commands::get_record_count,
@@ -240,6 +254,9 @@ pub fn run() {
             commands::get_field_distinct_values,
             commands::get_source_files,
             commands::export_records_xlsx,
+            // 记录历史 Commands
+            commands::get_record_history,
+            commands::revert_record,
             // Excel 解析 Commands
             commands::get_excel_sheets,
             commands::preview_excel,
@@ -252,12 +269,21 @@ pub fn run() {
             commands::reset_processing_task,
             // 导入撤回 Commands
             commands::rollback_batch,
+            commands::restore_batch,
+            commands::preview_rollback,
+            commands::rollback_records,
+            commands::restore_records,
             commands::get_project_batches_with_stats,
             // 处理 Commands
             commands::start_processing,
+            commands::resume_interrupted_task,
             commands::pause_processing_task,
             commands::resume_processing_task,
             commands::cancel_processing_task,
+            commands::confirm_column_mapping,
+            commands::list_mapping_templates,
+            commands::delete_mapping_template,
+            commands::clear_mapping_cache,
             // 统计 Commands
             commands::get_project_statistics,
         ])